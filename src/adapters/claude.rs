@@ -22,16 +22,51 @@ struct ClaudeHookInput {
     tool_use_id: Option<String>,
 }
 
-/// Claude tool-specific input fields.
+/// Claude tool-specific input fields. `raw` keeps the whole `tool_input`
+/// object for tools whose input has no fixed shape (MCP calls).
 #[derive(Debug, Deserialize)]
+#[serde(try_from = "serde_json::Value")]
 struct ClaudeToolInput {
     command: Option<String>,
     #[allow(dead_code)]
     description: Option<String>,
     file_path: Option<String>,
+    notebook_path: Option<String>,
     path: Option<String>,
     #[allow(dead_code)]
     pattern: Option<String>,
+    url: Option<String>,
+    raw: serde_json::Value,
+}
+
+/// The typed fields of [`ClaudeToolInput`], deserialized from `raw`.
+#[derive(Deserialize)]
+struct ClaudeToolFields {
+    command: Option<String>,
+    description: Option<String>,
+    file_path: Option<String>,
+    notebook_path: Option<String>,
+    path: Option<String>,
+    pattern: Option<String>,
+    url: Option<String>,
+}
+
+impl TryFrom<serde_json::Value> for ClaudeToolInput {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: serde_json::Value) -> Result<Self, Self::Error> {
+        let fields: ClaudeToolFields = serde_json::from_value(raw.clone())?;
+        Ok(Self {
+            command: fields.command,
+            description: fields.description,
+            file_path: fields.file_path,
+            notebook_path: fields.notebook_path,
+            path: fields.path,
+            pattern: fields.pattern,
+            url: fields.url,
+            raw,
+        })
+    }
 }
 
 /// Claude hook-specific output wrapper.
@@ -156,6 +191,10 @@ fn run_hook_input(
             }));
 
             let output = match outcome {
+                Ok(outcome) if outcome.passthrough => {
+                    println!("{{}}");
+                    return 0;
+                }
                 Ok(outcome) => ClaudeHookOutput::decision(outcome.decision, &outcome.reason),
                 Err(panic) => {
                     let reason = super::describe_panic(panic.as_ref());
@@ -203,11 +242,39 @@ fn action_from_input(input: ClaudeHookInput) -> ClaudeHookAction {
             cwd: input.cwd,
             session_id: input.session_id,
         }),
+        "Write" | "Edit" | "MultiEdit" => {
+            ClaudeHookAction::Evaluate(evaluator::Invocation::WritePath {
+                tool_name: input.tool_name.clone(),
+                path: input.tool_input.file_path,
+                cwd: input.cwd,
+                session_id: input.session_id,
+            })
+        }
+        "NotebookEdit" => ClaudeHookAction::Evaluate(evaluator::Invocation::WritePath {
+            tool_name: input.tool_name.clone(),
+            path: input.tool_input.notebook_path,
+            cwd: input.cwd,
+            session_id: input.session_id,
+        }),
+        "WebFetch" => ClaudeHookAction::Evaluate(evaluator::Invocation::Url {
+            tool_name: input.tool_name.clone(),
+            url: input.tool_input.url,
+            cwd: input.cwd,
+            session_id: input.session_id,
+        }),
         "Bash" => ClaudeHookAction::Evaluate(evaluator::Invocation::Shell {
             command: input.tool_input.command,
             cwd: input.cwd,
             session_id: input.session_id,
         }),
+        name if evaluator::split_mcp_tool_name(name).is_some() => {
+            ClaudeHookAction::Evaluate(evaluator::Invocation::Mcp {
+                tool_name: input.tool_name.clone(),
+                input: input.tool_input.raw,
+                cwd: input.cwd,
+                session_id: input.session_id,
+            })
+        }
         _ => ClaudeHookAction::Passthrough { cwd: input.cwd },
     }
 }
@@ -406,13 +473,126 @@ mod tests {
         }
     }
 
+    #[test]
+    fn maps_edit_to_write_path_invocation_with_fields_preserved() {
+        let action = parse_action(serde_json::json!({
+            "session_id": "session-6",
+            "cwd": "/repo",
+            "tool_name": "Edit",
+            "tool_input": {
+                "file_path": "/repo/src/lib.rs",
+                "old_string": "a",
+                "new_string": "b"
+            }
+        }));
+
+        match action {
+            ClaudeHookAction::Evaluate(evaluator::Invocation::WritePath {
+                tool_name,
+                path,
+                cwd,
+                session_id,
+            }) => {
+                assert_eq!(tool_name, "Edit");
+                assert_eq!(path.as_deref(), Some("/repo/src/lib.rs"));
+                assert_eq!(cwd.as_deref(), Some("/repo"));
+                assert_eq!(session_id.as_deref(), Some("session-6"));
+            }
+            other => panic!("expected write path invocation, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn maps_notebook_edit_to_write_path_using_notebook_path() {
+        let action = parse_action(serde_json::json!({
+            "cwd": "/repo",
+            "tool_name": "NotebookEdit",
+            "tool_input": { "notebook_path": "/repo/analysis.ipynb", "new_source": "x" }
+        }));
+
+        match action {
+            ClaudeHookAction::Evaluate(evaluator::Invocation::WritePath {
+                tool_name,
+                path,
+                ..
+            }) => {
+                assert_eq!(tool_name, "NotebookEdit");
+                assert_eq!(path.as_deref(), Some("/repo/analysis.ipynb"));
+            }
+            other => panic!("expected write path invocation, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn maps_mcp_tool_to_mcp_invocation_with_raw_input() {
+        let action = parse_action(serde_json::json!({
+            "session_id": "session-6",
+            "cwd": "/repo",
+            "tool_name": "mcp__postgres__query",
+            "tool_input": { "sql": "SELECT 1", "path": "/ignored" }
+        }));
+
+        match action {
+            ClaudeHookAction::Evaluate(evaluator::Invocation::Mcp {
+                tool_name,
+                input,
+                cwd,
+                session_id,
+            }) => {
+                assert_eq!(tool_name, "mcp__postgres__query");
+                assert_eq!(
+                    input,
+                    serde_json::json!({ "sql": "SELECT 1", "path": "/ignored" })
+                );
+                assert_eq!(cwd.as_deref(), Some("/repo"));
+                assert_eq!(session_id.as_deref(), Some("session-6"));
+            }
+            other => panic!("expected mcp invocation, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn maps_web_fetch_to_url_invocation() {
+        let action = parse_action(serde_json::json!({
+            "cwd": "/repo",
+            "tool_name": "WebFetch",
+            "tool_input": { "url": "https://docs.rs/serde", "prompt": "summarize" }
+        }));
+
+        match action {
+            ClaudeHookAction::Evaluate(evaluator::Invocation::Url { tool_name, url, .. }) => {
+                assert_eq!(tool_name, "WebFetch");
+                assert_eq!(url.as_deref(), Some("https://docs.rs/serde"));
+            }
+            other => panic!("expected url invocation, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn classifies_web_search_as_passthrough() {
+        let action = parse_action(serde_json::json!({
+            "tool_name": "WebSearch",
+            "tool_input": { "query": "serde derive" }
+        }));
+        assert!(matches!(action, ClaudeHookAction::Passthrough { .. }));
+    }
+
+    #[test]
+    fn classifies_malformed_mcp_name_as_passthrough() {
+        let action = parse_action(serde_json::json!({
+            "tool_name": "mcp__postgres",
+            "tool_input": {}
+        }));
+        assert!(matches!(action, ClaudeHookAction::Passthrough { .. }));
+    }
+
     #[test]
     fn classifies_unsupported_tool_as_passthrough_with_cwd_preserved() {
         let action = parse_action(serde_json::json!({
             "session_id": "session-5",
             "cwd": "/repo",
-            "tool_name": "Write",
-            "tool_input": { "file_path": "/repo/out.txt" }
+            "tool_name": "TodoWrite",
+            "tool_input": { "todos": [] }
         }));
 
         match action {
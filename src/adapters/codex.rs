@@ -33,10 +33,42 @@ struct CodexPermissionRequestInput {
 }
 
 /// A loose union shape that captures whichever fields a tool sends. For
-/// `Bash` we only consume `command`; other tools may set arbitrary fields.
+/// `Bash` we only consume `command`. `apply_patch` carries the patch
+/// envelope in `command`, or in `input` for the freeform tool variant;
+/// other tools may set arbitrary fields.
+/// `raw` keeps the whole `tool_input` object for MCP calls.
 #[derive(Debug, Deserialize)]
+#[serde(try_from = "serde_json::Value")]
 struct CodexToolInput {
     command: Option<String>,
+    input: Option<String>,
+    raw: serde_json::Value,
+}
+
+/// The typed fields of [`CodexToolInput`], deserialized from `raw`.
+#[derive(Deserialize)]
+struct CodexToolFields {
+    command: Option<String>,
+    input: Option<String>,
+}
+
+impl TryFrom<serde_json::Value> for CodexToolInput {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: serde_json::Value) -> Result<Self, Self::Error> {
+        let fields: CodexToolFields = serde_json::from_value(raw.clone())?;
+        Ok(Self {
+            command: fields.command,
+            input: fields.input,
+            raw,
+        })
+    }
+}
+
+impl CodexToolInput {
+    fn into_patch(self) -> Option<String> {
+        self.command.or(self.input)
+    }
 }
 
 /// Typed enum prevents emitting forbidden literals like "allow" or "ask"
@@ -135,12 +167,14 @@ enum CodexEvent {
 
 #[derive(Debug, Clone)]
 enum CodexHookAction {
-    /// Bash on a recognized event: evaluate the command.
+    /// Bash or apply_patch on a recognized event: evaluate the command or
+    /// the patched paths.
     Evaluate {
         event: CodexEvent,
         invocation: evaluator::Invocation,
     },
-    /// Recognized event with non-Bash tool, or recognized-but-unhandled event.
+    /// Recognized event with an unevaluated tool, or recognized-but-unhandled
+    /// event.
     /// Empty stdout, no stderr, no JSONL.
     SilentPassthrough,
     /// Malformed input (parse failed, missing/empty hook_event_name, etc.).
@@ -149,30 +183,64 @@ enum CodexHookAction {
 }
 
 fn action_from_pre_tool_use(input: CodexPreToolUseInput) -> CodexHookAction {
-    match input.tool_name.as_deref() {
-        Some("Bash") => CodexHookAction::Evaluate {
+    match invocation_for_tool(
+        input.tool_name,
+        input.tool_input,
+        input.cwd,
+        input.session_id,
+    ) {
+        Some(invocation) => CodexHookAction::Evaluate {
             event: CodexEvent::PreToolUse,
-            invocation: evaluator::Invocation::Shell {
-                command: input.tool_input.and_then(|t| t.command),
-                cwd: input.cwd,
-                session_id: input.session_id,
-            },
+            invocation,
         },
-        _ => CodexHookAction::SilentPassthrough,
+        None => CodexHookAction::SilentPassthrough,
     }
 }
 
 fn action_from_permission_request(input: CodexPermissionRequestInput) -> CodexHookAction {
-    match input.tool_name.as_deref() {
-        Some("Bash") => CodexHookAction::Evaluate {
+    match invocation_for_tool(
+        input.tool_name,
+        input.tool_input,
+        input.cwd,
+        input.session_id,
+    ) {
+        Some(invocation) => CodexHookAction::Evaluate {
             event: CodexEvent::PermissionRequest,
-            invocation: evaluator::Invocation::Shell {
-                command: input.tool_input.and_then(|t| t.command),
-                cwd: input.cwd,
-                session_id: input.session_id,
-            },
+            invocation,
         },
-        _ => CodexHookAction::SilentPassthrough,
+        None => CodexHookAction::SilentPassthrough,
+    }
+}
+
+/// Map an evaluated Codex tool to its invocation; `None` for tools that pass
+/// through.
+fn invocation_for_tool(
+    tool_name: Option<String>,
+    tool_input: Option<CodexToolInput>,
+    cwd: Option<String>,
+    session_id: Option<String>,
+) -> Option<evaluator::Invocation> {
+    match tool_name.as_deref() {
+        Some("Bash") => Some(evaluator::Invocation::Shell {
+            command: tool_input.and_then(|t| t.command),
+            cwd,
+            session_id,
+        }),
+        Some("apply_patch") => Some(evaluator::Invocation::Patch {
+            tool_name: "apply_patch".to_string(),
+            patch: tool_input.and_then(CodexToolInput::into_patch),
+            cwd,
+            session_id,
+        }),
+        Some(name) if evaluator::split_mcp_tool_name(name).is_some() => {
+            Some(evaluator::Invocation::Mcp {
+                tool_name: name.to_string(),
+                input: tool_input.map_or(serde_json::Value::Null, |t| t.raw),
+                cwd,
+                session_id,
+            })
+        }
+        _ => None,
     }
 }
 
@@ -294,6 +362,7 @@ fn run_hook_input(
             }));
 
             match outcome {
+                Ok(outcome) if outcome.passthrough => 0,
                 Ok(outcome) => {
                     emit_decision(event, outcome.decision, &outcome.reason);
                     0
@@ -609,21 +678,65 @@ mod tests {
     }
 
     #[test]
-    fn action_pre_tool_use_apply_patch_is_silent_passthrough() {
-        let json = r#"{"hook_event_name":"PreToolUse","tool_name":"apply_patch","tool_input":{}}"#;
-        assert!(matches!(
-            action_from_input_str(json),
-            CodexHookAction::SilentPassthrough
-        ));
+    fn action_pre_tool_use_apply_patch_evaluates_patch() {
+        let json = r#"{"hook_event_name":"PreToolUse","tool_name":"apply_patch","cwd":"/repo","tool_input":{"command":"*** Begin Patch\n*** Delete File: a\n*** End Patch\n"}}"#;
+        match action_from_input_str(json) {
+            CodexHookAction::Evaluate {
+                event: CodexEvent::PreToolUse,
+                invocation: evaluator::Invocation::Patch { patch, cwd, .. },
+            } => {
+                assert!(patch.unwrap().starts_with("*** Begin Patch"));
+                assert_eq!(cwd.as_deref(), Some("/repo"));
+            }
+            other => panic!("expected Evaluate(PreToolUse, Patch), got {other:?}"),
+        }
     }
 
     #[test]
-    fn action_pre_tool_use_mcp_is_silent_passthrough() {
-        let json = r#"{"hook_event_name":"PreToolUse","tool_name":"mcp__filesystem__read_file","tool_input":{}}"#;
-        assert!(matches!(
-            action_from_input_str(json),
-            CodexHookAction::SilentPassthrough
-        ));
+    fn action_permission_request_apply_patch_reads_freeform_input() {
+        let json = r#"{"hook_event_name":"PermissionRequest","tool_name":"apply_patch","tool_input":{"input":"*** Begin Patch"}}"#;
+        match action_from_input_str(json) {
+            CodexHookAction::Evaluate {
+                event: CodexEvent::PermissionRequest,
+                invocation: evaluator::Invocation::Patch { patch, .. },
+            } => assert_eq!(patch.as_deref(), Some("*** Begin Patch")),
+            other => panic!("expected Evaluate(PermissionRequest, Patch), got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn action_pre_tool_use_mcp_evaluates_with_raw_input() {
+        let json = r#"{"hook_event_name":"PreToolUse","tool_name":"mcp__filesystem__read_file","tool_input":{"path":"/etc/hosts"}}"#;
+        match action_from_input_str(json) {
+            CodexHookAction::Evaluate {
+                event: CodexEvent::PreToolUse,
+                invocation:
+                    evaluator::Invocation::Mcp {
+                        tool_name, input, ..
+                    },
+            } => {
+                assert_eq!(tool_name, "mcp__filesystem__read_file");
+                assert_eq!(input, serde_json::json!({"path": "/etc/hosts"}));
+            }
+            other => panic!("expected Evaluate(PreToolUse, Mcp), got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn unmatched_mcp_call_writes_no_jsonl() {
+        let home = test_home();
+        let options = HookOptions {
+            ask_on_deny: false,
+            ask_ai: false,
+            ask_ai_lenient: false,
+            cli_trust_level: None,
+            cli_safety_level: None,
+            profile_override: None,
+        };
+        let json = r#"{"hook_event_name":"PermissionRequest","tool_name":"mcp__filesystem__read_file","tool_input":{}}"#;
+        let exit = run_hook_input(embedded_rules(), &home, options, json);
+        assert_eq!(exit, 0);
+        assert_eq!(count_jsonl_entries(&home), 0);
     }
 
     #[test]
@@ -736,7 +849,7 @@ mod tests {
     }
 
     #[test]
-    fn passthrough_apply_patch_writes_no_jsonl() {
+    fn apply_patch_without_payload_asks_and_writes_jsonl() {
         let home = test_home();
         let options = HookOptions {
             ask_on_deny: false,
@@ -749,7 +862,10 @@ mod tests {
         let json = r#"{"hook_event_name":"PreToolUse","tool_name":"apply_patch","tool_input":{}}"#;
         let exit = run_hook_input(embedded_rules(), &home, options, json);
         assert_eq!(exit, 0);
-        assert_eq!(count_jsonl_entries(&home), 0);
+        let entry = read_last_jsonl(&home);
+        assert_eq!(entry["tool"], "apply_patch");
+        assert_eq!(entry["decision"], "ask");
+        assert_eq!(entry["parse_ok"], false);
     }
 
     #[test]
@@ -1,7 +1,9 @@
+use crate::config::from_yaml_str;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use std::path::{Path, PathBuf};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct AiJudgeConfig {
     #[serde(default = "default_command")]
     pub command: String,
@@ -50,7 +52,7 @@ impl AiJudgeConfig {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct TriggersConfig {
     #[serde(default = "default_interpreters")]
     pub interpreters: Vec<InterpreterTrigger>,
@@ -67,7 +69,7 @@ impl Default for TriggersConfig {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct InterpreterTrigger {
     pub name: Vec<String>,
     pub inline_flag: String,
@@ -146,7 +148,7 @@ fn default_runners() -> Vec<String> {
     ]
 }
 
-fn default_config_path() -> PathBuf {
+pub fn default_config_path() -> PathBuf {
     super::home::home_dir()
         .join(".config")
         .join("longline")
@@ -159,22 +161,23 @@ pub fn load_config() -> AiJudgeConfig {
 }
 
 fn load_config_from_path(path: &Path) -> AiJudgeConfig {
+    try_load_config_from_path(path).unwrap_or_else(|e| {
+        eprintln!("longline: {e}");
+        default_config()
+    })
+}
+
+/// Like `load_config`, but an unreadable or unparseable file is an error
+/// instead of a silent fall-back to the defaults. A missing file is not.
+pub fn try_load_config_from_path(path: &Path) -> Result<AiJudgeConfig, String> {
     if !path.exists() {
-        return default_config();
-    }
-    match std::fs::read_to_string(path) {
-        Ok(content) => match serde_norway::from_str::<AiJudgeConfig>(&content) {
-            Ok(cfg) => cfg.finalize(),
-            Err(e) => {
-                eprintln!("longline: failed to parse ai-judge config: {e}");
-                default_config()
-            }
-        },
-        Err(e) => {
-            eprintln!("longline: failed to read ai-judge config: {e}");
-            default_config()
-        }
+        return Ok(default_config());
     }
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read ai-judge config: {e}"))?;
+    from_yaml_str::<AiJudgeConfig>(&content)
+        .map(AiJudgeConfig::finalize)
+        .map_err(|e| format!("failed to parse ai-judge config: {e}"))
 }
 
 pub(crate) fn default_config() -> AiJudgeConfig {
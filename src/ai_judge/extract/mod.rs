@@ -22,7 +22,7 @@ const MAX_EXTRACTED_CODE_BYTES: usize = 32 * 1024;
 ///
 /// Precedence:
 /// 1) Inline flags (`python -c`, `node -e`, etc), including runner-wrapped commands.
-/// 2) Heredoc / here-string to python or Django shell (from raw command text).
+/// 2) Heredoc / here-string to python or Django shell.
 /// 3) Pipelines feeding Django shell from `echo`/`printf`/`cat <file>` (CWD+/tmp only).
 /// 4) Python script execution (`python script.py`, heredoc-created scripts, or `python < file.py`)
 ///    when the script contents can be safely extracted (CWD+/tmp only).
@@ -43,7 +43,7 @@ pub fn extract_code(
         return Some(extracted);
     }
 
-    if let Some(extracted) = heredoc::extract_from_heredoc_or_herestring(raw_command) {
+    if let Some(extracted) = heredoc::extract_from_heredoc_or_herestring(raw_command, stmt) {
         if extracted.code.len() <= MAX_EXTRACTED_CODE_BYTES {
             return Some(extracted);
         }
@@ -57,8 +57,7 @@ pub fn extract_code(
         return Some(extracted);
     }
 
-    if let Some(extracted) = python::extract_python_script_execution(raw_command, stmt, cwd, config)
-    {
+    if let Some(extracted) = python::extract_python_script_execution(stmt, stmt, cwd, config) {
         return Some(extracted);
     }
 
@@ -119,8 +119,10 @@ pub(super) fn extract_from_python_stdin_pipeline(
     }
 }
 
+/// `root` is the whole command, searched for a heredoc that wrote the
+/// script (`cat > s.py <<'EOF' ... EOF; python3 s.py`).
 pub(super) fn extract_python_script_execution(
-    raw_command: &str,
+    root: &Statement,
     stmt: &Statement,
     cwd: &str,
     config: &AiJudgeConfig,
@@ -128,47 +130,40 @@ pub(super) fn extract_python_script_execution(
     match stmt {
         Statement::SimpleCommand(cmd) => {
             for sub in &cmd.embedded_substitutions {
-                if let Some(result) = extract_python_script_execution(raw_command, sub, cwd, config)
-                {
+                if let Some(result) = extract_python_script_execution(root, sub, cwd, config) {
                     return Some(result);
                 }
             }
-            extract_python_script_from_simple_command(raw_command, cmd, cwd, config)
+            extract_python_script_from_simple_command(root, cmd, cwd, config)
         }
         Statement::Pipeline(pipeline) => {
             for stage in &pipeline.stages {
-                if let Some(result) =
-                    extract_python_script_execution(raw_command, stage, cwd, config)
-                {
+                if let Some(result) = extract_python_script_execution(root, stage, cwd, config) {
                     return Some(result);
                 }
             }
             None
         }
         Statement::List(list) => {
-            if let Some(result) =
-                extract_python_script_execution(raw_command, &list.first, cwd, config)
-            {
+            if let Some(result) = extract_python_script_execution(root, &list.first, cwd, config) {
                 return Some(result);
             }
             for (_, stmt) in &list.rest {
-                if let Some(result) =
-                    extract_python_script_execution(raw_command, stmt, cwd, config)
-                {
+                if let Some(result) = extract_python_script_execution(root, stmt, cwd, config) {
                     return Some(result);
                 }
             }
             None
         }
         Statement::Subshell(inner) | Statement::CommandSubstitution(inner) => {
-            extract_python_script_execution(raw_command, inner, cwd, config)
+            extract_python_script_execution(root, inner, cwd, config)
         }
         Statement::Opaque(_) | Statement::Empty => None,
     }
 }
 
 fn extract_python_script_from_simple_command(
-    raw_command: &str,
+    root: &Statement,
     cmd: &crate::parser::SimpleCommand,
     cwd: &str,
     config: &AiJudgeConfig,
@@ -209,7 +204,7 @@ fn extract_python_script_from_simple_command(
             return None;
         }
 
-        if let Some(code) = extract_heredoc_written_script(raw_command, script_path) {
+        if let Some(code) = extract_heredoc_written_script(root, script_path) {
             return Some(ExtractedCode {
                 language: cmd_name.to_string(),
                 code,
@@ -74,6 +74,16 @@ fn test_extract_python_script_from_heredoc_write_then_execute() {
     assert!(result.code.contains("print(42)"));
 }
 
+#[test]
+fn test_extract_python_script_from_tee_heredoc() {
+    let cmd = "tee ./gen.py <<-EOF >/dev/null\n\tprint(7)\n\tEOF\npython3 gen.py";
+    let stmt = parser::parse(cmd).unwrap();
+    let config = test_config();
+    let result = extract_code(cmd, &stmt, "/tmp", &config).unwrap();
+    assert_eq!(result.language, "python3");
+    assert_eq!(result.code, "print(7)");
+}
+
 #[test]
 fn test_no_extract_for_version() {
     let cmd = "python3 --version";
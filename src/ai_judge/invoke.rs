@@ -101,7 +101,6 @@ fn run(config: &AiJudgeConfig, prompt: String) -> JudgeVerdict {
 /// `--settings` AND its path token so claude runs with `--setting-sources ""`
 /// alone, and note `settings_unavailable` on stderr.
 fn ensure_claude_settings(providers: &mut [Provider]) {
-    const JOINED: &str = "--settings=";
     for p in providers.iter_mut() {
         if p.name != "claude" {
             continue;
@@ -110,24 +109,10 @@ fn ensure_claude_settings(providers: &mut [Provider]) {
         // form (`--settings=<path>`), so the cleanupPeriodDays>=3650 guarantee
         // holds for either shape a user might write in `fallback_command`.
         // `remove` lists the argv indices to drop if the settings file can't be
-        // placed (so claude falls back to `--setting-sources ""` alone).
-        let split = p.argv.iter().position(|a| a == "--settings");
-        let resolved: Option<(std::path::PathBuf, Vec<usize>)> = if let Some(i) = split {
-            // A dangling `--settings` with no following path is left untouched
-            // (it will fail at spawn → ExitError → provider disabled → ask).
-            p.argv
-                .get(i + 1)
-                .map(|path| (std::path::PathBuf::from(path), vec![i, i + 1]))
-        } else {
-            p.argv.iter().position(|a| a.starts_with(JOINED)).map(|i| {
-                (
-                    std::path::PathBuf::from(&p.argv[i][JOINED.len()..]),
-                    vec![i],
-                )
-            })
-        };
-
-        let Some((path, mut remove)) = resolved else {
+        // placed (so claude falls back to `--setting-sources ""` alone). A
+        // dangling `--settings` with no following path is left untouched (it
+        // will fail at spawn → ExitError → provider disabled → ask).
+        let Some((path, mut remove)) = settings::settings_arg(&p.argv) else {
             continue;
         };
         if let SettingsOutcome::Unavailable = settings::ensure_settings_file(&path) {
@@ -9,7 +9,7 @@ mod provider;
 mod response;
 mod settings;
 
-pub use config::load_config;
+pub use config::{default_config_path, load_config, try_load_config_from_path};
 #[allow(unused_imports)]
 pub use config::{AiJudgeConfig, InterpreterTrigger, TriggersConfig};
 #[allow(unused_imports)]
@@ -35,6 +35,6 @@ pub use provider::{RealClock, RealHandle, RealRunner};
 pub use response::{parse_output, ParsedOutput, Verdict};
 #[allow(unused_imports)] // consumed by invoke.rs in Task 11 / cli.rs in Task 14
 pub use settings::{
-    content_is_safe, ensure_settings_file, is_inert_and_safe, SettingsOutcome,
+    content_is_safe, ensure_settings_file, is_inert_and_safe, settings_arg, SettingsOutcome,
     EMBEDDED as JUDGE_CLAUDE_SETTINGS,
 };
@@ -41,6 +41,22 @@ pub fn is_inert_and_safe(v: &serde_json::Value) -> bool {
     })
 }
 
+/// Locate the settings file in a claude provider argv, in either the split
+/// form (`--settings <path>`) or the joined form (`--settings=<path>`).
+/// Returns the path and the argv indices that carry it. A dangling
+/// `--settings` with no following path yields `None`.
+pub fn settings_arg(argv: &[String]) -> Option<(std::path::PathBuf, Vec<usize>)> {
+    const JOINED: &str = "--settings=";
+    if let Some(i) = argv.iter().position(|a| a == "--settings") {
+        return argv
+            .get(i + 1)
+            .map(|path| (std::path::PathBuf::from(path), vec![i, i + 1]));
+    }
+    argv.iter()
+        .position(|a| a.starts_with(JOINED))
+        .map(|i| (std::path::PathBuf::from(&argv[i][JOINED.len()..]), vec![i]))
+}
+
 /// Validate the on-disk file; atomically repair from EMBEDDED if missing/unsafe.
 /// Atomic = write unique temp in the SAME dir, fsync, rename over target, fsync dir.
 pub fn ensure_settings_file(path: &std::path::Path) -> SettingsOutcome {
@@ -0,0 +1,220 @@
+//! Parser for the Codex `apply_patch` envelope.
+//!
+//! Only the file-level headers are extracted; hunk bodies are skipped. The
+//! grammar is:
+//!
+//! ```text
+//! *** Begin Patch
+//! *** Add File: <path>
+//! +<line>
+//! *** Update File: <path>
+//! *** Move to: <new path>
+//! @@ <context>
+//! -<old>
+//! +<new>
+//! *** Delete File: <path>
+//! *** End Patch
+//! ```
+
+/// Kind of change a patch makes to one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchOp {
+    Add,
+    Update,
+    Delete,
+    /// The destination of an update with `*** Move to:`. Never the `op` of
+    /// a `PatchFile`, whose `move_to` carries it.
+    MoveTo,
+}
+
+impl std::fmt::Display for PatchOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            PatchOp::Add => "add",
+            PatchOp::Update => "update",
+            PatchOp::Delete => "delete",
+            PatchOp::MoveTo => "move to",
+        })
+    }
+}
+
+/// One file section of a patch. `move_to` is set for an update that also
+/// renames the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchFile {
+    pub op: PatchOp,
+    pub path: String,
+    pub move_to: Option<String>,
+}
+
+const BEGIN_PATCH: &str = "*** Begin Patch";
+const END_PATCH: &str = "*** End Patch";
+const ADD_FILE: &str = "*** Add File: ";
+const UPDATE_FILE: &str = "*** Update File: ";
+const DELETE_FILE: &str = "*** Delete File: ";
+const MOVE_TO: &str = "*** Move to: ";
+
+/// Parse the file headers of an `apply_patch` envelope. Text before
+/// `*** Begin Patch` (e.g. an `apply_patch <<'EOF'` wrapper) is ignored.
+/// Returns an error for anything Codex itself would reject: a missing
+/// begin/end marker, an empty path, a stray line outside a file section,
+/// a misplaced `*** Move to:`, or a patch that touches no files.
+pub fn parse(patch: &str) -> Result<Vec<PatchFile>, String> {
+    let mut lines = patch.lines().map(|line| line.trim_end_matches('\r'));
+    if !lines.by_ref().any(|line| line.trim() == BEGIN_PATCH) {
+        return Err("missing '*** Begin Patch' marker".to_string());
+    }
+
+    let mut files: Vec<PatchFile> = Vec::new();
+    // Whether the previous line was an `*** Update File:` header, the only
+    // place a `*** Move to:` may appear.
+    let mut after_update_header = false;
+    for line in lines {
+        if line.trim() == END_PATCH {
+            if files.is_empty() {
+                return Err("patch touches no files".to_string());
+            }
+            return Ok(files);
+        }
+        let header = [
+            (ADD_FILE, PatchOp::Add),
+            (UPDATE_FILE, PatchOp::Update),
+            (DELETE_FILE, PatchOp::Delete),
+        ]
+        .into_iter()
+        .find_map(|(prefix, op)| line.strip_prefix(prefix).map(|path| (op, path)));
+        if let Some((op, path)) = header {
+            files.push(PatchFile {
+                op,
+                path: non_empty_path(path, line)?,
+                move_to: None,
+            });
+            after_update_header = op == PatchOp::Update;
+            continue;
+        }
+        if let Some(path) = line.strip_prefix(MOVE_TO) {
+            if !after_update_header {
+                return Err(format!("'{line}' must follow an '*** Update File:' header"));
+            }
+            let file = files.last_mut().expect("update header recorded");
+            file.move_to = Some(non_empty_path(path, line)?);
+            after_update_header = false;
+            continue;
+        }
+        after_update_header = false;
+        if files.is_empty() && !line.trim().is_empty() {
+            return Err(format!(
+                "unexpected line before first file header: '{line}'"
+            ));
+        }
+        // Anything else is hunk content (including `*** End of File`).
+    }
+    Err("missing '*** End Patch' marker".to_string())
+}
+
+fn non_empty_path(path: &str, line: &str) -> Result<String, String> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err(format!("empty path in '{line}'"));
+    }
+    Ok(path.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(op: PatchOp, path: &str, move_to: Option<&str>) -> PatchFile {
+        PatchFile {
+            op,
+            path: path.to_string(),
+            move_to: move_to.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_parse_all_operations() {
+        let patch = "\
+*** Begin Patch
+*** Add File: docs/new.md
++# New
+*** Update File: src/lib.rs
+@@ fn main() {
+-    old();
++    new();
+*** End of File
+*** Update File: src/old.rs
+*** Move to: src/new.rs
+@@
+-a
++b
+*** Delete File: scratch.txt
+*** End Patch
+";
+        assert_eq!(
+            parse(patch).unwrap(),
+            vec![
+                file(PatchOp::Add, "docs/new.md", None),
+                file(PatchOp::Update, "src/lib.rs", None),
+                file(PatchOp::Update, "src/old.rs", Some("src/new.rs")),
+                file(PatchOp::Delete, "scratch.txt", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_ignores_heredoc_wrapper() {
+        let patch =
+            "apply_patch <<'EOF'\n*** Begin Patch\n*** Delete File: a.txt\n*** End Patch\nEOF\n";
+        assert_eq!(
+            parse(patch).unwrap(),
+            vec![file(PatchOp::Delete, "a.txt", None)]
+        );
+    }
+
+    #[test]
+    fn test_parse_crlf_line_endings() {
+        let patch = "*** Begin Patch\r\n*** Add File: a.txt\r\n+x\r\n*** End Patch\r\n";
+        assert_eq!(
+            parse(patch).unwrap(),
+            vec![file(PatchOp::Add, "a.txt", None)]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_missing_begin() {
+        let err = parse("*** Add File: a.txt\n+x\n*** End Patch\n").unwrap_err();
+        assert!(err.contains("Begin Patch"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_rejects_missing_end() {
+        let err = parse("*** Begin Patch\n*** Add File: a.txt\n+x\n").unwrap_err();
+        assert!(err.contains("End Patch"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_rejects_empty_patch() {
+        let err = parse("*** Begin Patch\n*** End Patch\n").unwrap_err();
+        assert!(err.contains("no files"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_rejects_empty_path() {
+        let err = parse("*** Begin Patch\n*** Delete File:  \n*** End Patch\n").unwrap_err();
+        assert!(err.contains("empty path"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_rejects_move_without_update() {
+        let err = parse("*** Begin Patch\n*** Add File: a\n+x\n*** Move to: b\n*** End Patch\n")
+            .unwrap_err();
+        assert!(err.contains("Move to"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_rejects_stray_line_before_header() {
+        let err = parse("*** Begin Patch\n+x\n*** Add File: a\n*** End Patch\n").unwrap_err();
+        assert!(err.contains("unexpected line"), "{err}");
+    }
+}
@@ -0,0 +1,524 @@
+//! `longline audit`: ask/deny frequency reports over the JSONL decision logs
+//! the Claude and Codex hooks write, including rotated `.N` files.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use longline::config;
+use longline::domain::Decision;
+use longline::parser::{self, Statement};
+
+use crate::logger;
+
+/// Which log entries `analyze` counts.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AuditFilter {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub session: Option<String>,
+}
+
+/// The fields of a log line the report needs. Every field is optional so
+/// older entries (and fail-open entries) still count.
+#[derive(Debug, Deserialize)]
+pub(crate) struct LoggedDecision {
+    #[serde(default)]
+    pub runtime: Option<String>,
+    #[serde(default)]
+    profile: Option<String>,
+    #[serde(default)]
+    ts: Option<String>,
+    #[serde(default)]
+    pub tool: String,
+    #[serde(default)]
+    pub cwd: String,
+    #[serde(default)]
+    pub command: String,
+    pub decision: Decision,
+    /// The policy decision before `--ask-on-deny` or the AI judge changed it.
+    #[serde(default)]
+    pub original_decision: Option<Decision>,
+    #[serde(default)]
+    matched_rules: Vec<String>,
+    #[serde(default)]
+    session_id: Option<String>,
+    #[serde(default)]
+    deciding_leaf: Option<LoggedLeaf>,
+}
+
+impl LoggedDecision {
+    /// Whether the entry is a shell command (entries that predate the
+    /// `tool` field always were).
+    pub fn is_shell(&self) -> bool {
+        self.tool == "Bash" || self.tool.is_empty()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LoggedLeaf {
+    text: String,
+}
+
+/// Ask and deny counts for one key of one dimension.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct Tally {
+    pub key: String,
+    pub ask: usize,
+    pub deny: usize,
+}
+
+impl Tally {
+    pub fn total(&self) -> usize {
+        self.ask + self.deny
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct AuditReport {
+    pub files: Vec<String>,
+    /// Entries inside the filter window, whatever their decision.
+    pub entries: usize,
+    /// Lines that were not valid log entries.
+    pub skipped_lines: usize,
+    pub ask: usize,
+    pub deny: usize,
+    pub by_family: Vec<Tally>,
+    pub by_project: Vec<Tally>,
+    pub by_rule: Vec<Tally>,
+    pub by_profile: Vec<Tally>,
+    pub by_runtime: Vec<Tally>,
+}
+
+/// Key used when an ask/deny entry has no matched rule (the default
+/// decision for an unrecognised command, an opaque parse, ...).
+const NO_RULE: &str = "(no rule)";
+const UNKNOWN: &str = "(unknown)";
+
+/// The log file of `runtime` and its rotated siblings, oldest first.
+pub(crate) fn log_files(home: &Path, runtime: &str) -> Vec<PathBuf> {
+    let current = match runtime {
+        "codex" => crate::runtime::codex::audit_log_path(home),
+        _ => crate::runtime::claude::audit_log_path(home),
+    };
+    let mut files: Vec<PathBuf> = (1..=logger::MAX_ROTATED_LOG_FILES)
+        .rev()
+        .map(|index| logger::rotated_log_path(&current, index))
+        .filter(|path| path.exists())
+        .collect();
+    if current.exists() {
+        files.push(current);
+    }
+    files
+}
+
+/// Build the report from `files`. `(path, runtime)` pairs name the runtime
+/// to assume for entries that predate the `runtime` field.
+pub(crate) fn analyze(
+    files: &[(PathBuf, &'static str)],
+    filter: &AuditFilter,
+    top: usize,
+) -> Result<AuditReport, String> {
+    let mut report = AuditReport {
+        files: files.iter().map(|(p, _)| p.display().to_string()).collect(),
+        entries: 0,
+        skipped_lines: 0,
+        ask: 0,
+        deny: 0,
+        by_family: vec![],
+        by_project: vec![],
+        by_rule: vec![],
+        by_profile: vec![],
+        by_runtime: vec![],
+    };
+    let mut by_family = Counter::default();
+    let mut by_project = Counter::default();
+    let mut by_rule = Counter::default();
+    let mut by_profile = Counter::default();
+    let mut by_runtime = Counter::default();
+    let mut project_roots: HashMap<String, String> = HashMap::new();
+
+    report.skipped_lines = read_entries(files, filter, |entry, runtime| {
+        report.entries += 1;
+        match entry.decision {
+            Decision::Ask => report.ask += 1,
+            Decision::Deny => report.deny += 1,
+            Decision::Allow => return,
+        }
+
+        let decision = entry.decision;
+        by_family.add(command_family(&entry), decision);
+        let project = project_roots
+            .entry(entry.cwd.clone())
+            .or_insert_with(|| project_root(&entry.cwd))
+            .clone();
+        by_project.add(project, decision);
+        if entry.matched_rules.is_empty() {
+            by_rule.add(NO_RULE.to_string(), decision);
+        }
+        for rule in &entry.matched_rules {
+            by_rule.add(rule.clone(), decision);
+        }
+        by_profile.add(
+            entry.profile.unwrap_or_else(|| UNKNOWN.to_string()),
+            decision,
+        );
+        by_runtime.add(
+            entry.runtime.unwrap_or_else(|| runtime.to_string()),
+            decision,
+        );
+    })?;
+
+    report.by_family = by_family.top(top);
+    report.by_project = by_project.top(top);
+    report.by_rule = by_rule.top(top);
+    report.by_profile = by_profile.top(top);
+    report.by_runtime = by_runtime.top(top);
+    Ok(report)
+}
+
+/// Shell commands that were asked while working in `root` or below it,
+/// with the directory each ran in, one per log entry, oldest first. Input
+/// for `longline suggest`.
+pub(crate) fn asked_commands_under(
+    files: &[(PathBuf, &'static str)],
+    filter: &AuditFilter,
+    root: &Path,
+) -> Result<Vec<(String, String)>, String> {
+    let mut commands = Vec::new();
+    read_entries(files, filter, |entry, _| {
+        if entry.decision == Decision::Ask
+            && entry.is_shell()
+            && !entry.command.is_empty()
+            && !entry.cwd.is_empty()
+            && Path::new(&entry.cwd).starts_with(root)
+        {
+            commands.push((entry.command, entry.cwd));
+        }
+    })?;
+    Ok(commands)
+}
+
+/// Feed every admitted entry of `files` to `visit` with its file's runtime.
+/// Returns the number of lines that were not valid log entries.
+pub(crate) fn read_entries(
+    files: &[(PathBuf, &'static str)],
+    filter: &AuditFilter,
+    mut visit: impl FnMut(LoggedDecision, &'static str),
+) -> Result<usize, String> {
+    let mut skipped = 0;
+    for (path, runtime) in files {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            let Ok(entry) = serde_json::from_str::<LoggedDecision>(line) else {
+                skipped += 1;
+                continue;
+            };
+            if filter.admits(&entry) {
+                visit(entry, runtime);
+            }
+        }
+    }
+    Ok(skipped)
+}
+
+impl AuditFilter {
+    fn admits(&self, entry: &LoggedDecision) -> bool {
+        if let Some(session) = &self.session {
+            if entry.session_id.as_deref() != Some(session.as_str()) {
+                return false;
+            }
+        }
+        if self.since.is_none() && self.until.is_none() {
+            return true;
+        }
+        let Some(ts) = entry
+            .ts
+            .as_deref()
+            .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+            .map(|ts| ts.with_timezone(&Utc))
+        else {
+            return false;
+        };
+        self.since.is_none_or(|since| ts >= since) && self.until.is_none_or(|until| ts < until)
+    }
+}
+
+#[derive(Default)]
+struct Counter(HashMap<String, Tally>);
+
+impl Counter {
+    fn add(&mut self, key: String, decision: Decision) {
+        let tally = self.0.entry(key.clone()).or_insert(Tally {
+            key,
+            ask: 0,
+            deny: 0,
+        });
+        match decision {
+            Decision::Ask => tally.ask += 1,
+            Decision::Deny => tally.deny += 1,
+            Decision::Allow => {}
+        }
+    }
+
+    /// The `n` keys with the most asks and denies, ties broken by key.
+    fn top(self, n: usize) -> Vec<Tally> {
+        let mut tallies: Vec<Tally> = self.0.into_values().collect();
+        tallies.sort_by(|a, b| b.total().cmp(&a.total()).then_with(|| a.key.cmp(&b.key)));
+        tallies.truncate(n);
+        tallies
+    }
+}
+
+/// Basename of the first command in the deciding leaf (or the whole
+/// command when the entry predates `deciding_leaf`). Non-shell tools are
+/// grouped under their tool name.
+fn command_family(entry: &LoggedDecision) -> String {
+    if entry.tool != "Bash" && !entry.tool.is_empty() {
+        return entry.tool.clone();
+    }
+    let text = entry
+        .deciding_leaf
+        .as_ref()
+        .map_or(entry.command.as_str(), |leaf| leaf.text.as_str());
+    let Ok(stmt) = parser::parse(text) else {
+        return UNKNOWN.to_string();
+    };
+    parser::flatten(&stmt)
+        .into_iter()
+        .find_map(|leaf| match leaf {
+            Statement::SimpleCommand(cmd) => cmd.name.as_deref(),
+            _ => None,
+        })
+        .map(|name| name.rsplit('/').next().unwrap_or(name).to_string())
+        .unwrap_or_else(|| UNKNOWN.to_string())
+}
+
+fn project_root(cwd: &str) -> String {
+    if cwd.is_empty() {
+        return UNKNOWN.to_string();
+    }
+    config::find_project_root(Path::new(cwd))
+        .map(|root| root.display().to_string())
+        .unwrap_or_else(|| cwd.to_string())
+}
+
+/// Parse a `--since`/`--until` bound: a duration back from `now` (`90m`,
+/// `24h`, `7d`, `2w`), an RFC 3339 timestamp, or a `YYYY-MM-DD` date
+/// (midnight UTC).
+pub(crate) fn parse_time_bound(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let value = value.trim();
+    if let Some(unit) = value.chars().last().filter(|c| c.is_ascii_alphabetic()) {
+        if let Ok(amount) = value[..value.len() - 1].parse::<i64>() {
+            let span = match unit {
+                'm' => Duration::try_minutes(amount),
+                'h' => Duration::try_hours(amount),
+                'd' => Duration::try_days(amount),
+                'w' => Duration::try_weeks(amount),
+                _ => None,
+            };
+            if let Some(span) = span {
+                return Ok(now - span);
+            }
+        }
+    }
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        return Ok(ts.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).expect("midnight").and_utc());
+    }
+    Err(format!(
+        "invalid time '{value}' -- use a duration (30m, 24h, 7d, 2w), an RFC 3339 timestamp or YYYY-MM-DD"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_log(dir: &Path, name: &str, lines: &[serde_json::Value]) -> PathBuf {
+        let path = dir.join(name);
+        let body: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        std::fs::write(&path, body.join("\n") + "\n").unwrap();
+        path
+    }
+
+    fn entry(decision: &str, command: &str, rules: &[&str]) -> serde_json::Value {
+        serde_json::json!({
+            "runtime": "claude",
+            "profile": "default",
+            "ts": "2026-03-01T12:00:00.000Z",
+            "tool": "Bash",
+            "cwd": "",
+            "command": command,
+            "decision": decision,
+            "matched_rules": rules,
+            "session_id": "s1",
+        })
+    }
+
+    #[test]
+    fn test_log_files_lists_rotated_files_oldest_first() {
+        let home = tempfile::tempdir().unwrap();
+        let dir = home.path().join(".claude/hooks-logs");
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["longline.jsonl", "longline.jsonl.1", "longline.jsonl.3"] {
+            std::fs::write(dir.join(name), "").unwrap();
+        }
+        let names: Vec<String> = log_files(home.path(), "claude")
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["longline.jsonl.3", "longline.jsonl.1", "longline.jsonl"]
+        );
+        assert!(log_files(home.path(), "codex").is_empty());
+    }
+
+    #[test]
+    fn test_analyze_tallies_asks_and_denies() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = write_log(
+            dir.path(),
+            "longline.jsonl",
+            &[
+                entry("ask", "kubectl get pods", &[]),
+                entry("ask", "kubectl apply -f x.yaml", &[]),
+                entry("deny", "timeout 5 rm -rf /", &["rm-recursive-root"]),
+                entry("allow", "ls", &[]),
+                serde_json::json!("not an entry"),
+            ],
+        );
+        let report = analyze(&[(log, "claude")], &AuditFilter::default(), 10).unwrap();
+        assert_eq!(report.entries, 4);
+        assert_eq!(report.skipped_lines, 1);
+        assert_eq!((report.ask, report.deny), (2, 1));
+        assert_eq!(
+            report.by_family[0],
+            Tally {
+                key: "kubectl".to_string(),
+                ask: 2,
+                deny: 0
+            }
+        );
+        // Without deciding_leaf the family is the outer command.
+        assert_eq!(report.by_family[1].key, "timeout");
+        assert_eq!(report.by_rule[0].key, NO_RULE);
+        assert_eq!(report.by_rule[1].key, "rm-recursive-root");
+        assert_eq!(report.by_project[0].key, UNKNOWN);
+        assert_eq!(report.by_runtime[0].total(), 3);
+    }
+
+    #[test]
+    fn test_family_uses_deciding_leaf_and_tool_name() {
+        let mut shell = entry("deny", "timeout 5 rm -rf /", &["rm-recursive-root"]);
+        shell["deciding_leaf"] = serde_json::json!({ "text": "rm -rf /", "bucket": "wrapper" });
+        let mut write = entry("ask", "/home/u/.bashrc", &["redirect-write-shell-rc"]);
+        write["tool"] = serde_json::json!("Edit");
+        let dir = tempfile::tempdir().unwrap();
+        let log = write_log(dir.path(), "longline.jsonl", &[shell, write]);
+        let report = analyze(&[(log, "claude")], &AuditFilter::default(), 10).unwrap();
+        let families: Vec<&str> = report.by_family.iter().map(|t| t.key.as_str()).collect();
+        assert_eq!(families, vec!["Edit", "rm"]);
+    }
+
+    #[test]
+    fn test_filter_by_window_and_session() {
+        let mut old = entry("ask", "make", &[]);
+        old["ts"] = serde_json::json!("2026-01-01T00:00:00Z");
+        let mut other_session = entry("ask", "make", &[]);
+        other_session["session_id"] = serde_json::json!("s2");
+        let dir = tempfile::tempdir().unwrap();
+        let log = write_log(
+            dir.path(),
+            "longline.jsonl",
+            &[old, other_session, entry("ask", "make", &[])],
+        );
+        let filter = AuditFilter {
+            since: Some(parse_time_bound("2026-02-01", Utc::now()).unwrap()),
+            until: None,
+            session: Some("s1".to_string()),
+        };
+        let report = analyze(&[(log, "claude")], &filter, 10).unwrap();
+        assert_eq!(report.entries, 1);
+    }
+
+    #[test]
+    fn test_asked_commands_under_project() {
+        let at = |decision: &str, command: &str, cwd: &str| {
+            let mut e = entry(decision, command, &[]);
+            e["cwd"] = serde_json::json!(cwd);
+            e
+        };
+        let mut read = at("ask", "/repo/x", "/repo");
+        read["tool"] = serde_json::json!("Read");
+        let dir = tempfile::tempdir().unwrap();
+        let log = write_log(
+            dir.path(),
+            "longline.jsonl",
+            &[
+                at("ask", "make build", "/repo"),
+                at("ask", "make test", "/repo/sub"),
+                at("deny", "rm -rf /", "/repo"),
+                at("ask", "make lint", "/other"),
+                at("ask", "make fmt", "/repository"),
+                read,
+            ],
+        );
+        let commands = asked_commands_under(
+            &[(log, "claude")],
+            &AuditFilter::default(),
+            Path::new("/repo"),
+        )
+        .unwrap();
+        assert_eq!(
+            commands,
+            vec![
+                ("make build".to_string(), "/repo".to_string()),
+                ("make test".to_string(), "/repo/sub".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_time_bound() {
+        let now = DateTime::parse_from_rfc3339("2026-03-10T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            parse_time_bound("7d", now).unwrap().to_rfc3339(),
+            "2026-03-03T00:00:00+00:00"
+        );
+        assert_eq!(
+            parse_time_bound("90m", now).unwrap().to_rfc3339(),
+            "2026-03-09T22:30:00+00:00"
+        );
+        assert_eq!(
+            parse_time_bound("2026-03-01T08:00:00+02:00", now)
+                .unwrap()
+                .to_rfc3339(),
+            "2026-03-01T06:00:00+00:00"
+        );
+        assert!(parse_time_bound("yesterday", now).is_err());
+    }
+
+    #[test]
+    fn test_top_limits_and_orders() {
+        let mut counter = Counter::default();
+        for (key, decision) in [
+            ("b", Decision::Ask),
+            ("a", Decision::Ask),
+            ("c", Decision::Deny),
+            ("c", Decision::Ask),
+        ] {
+            counter.add(key.to_string(), decision);
+        }
+        let keys: Vec<String> = counter.top(2).into_iter().map(|t| t.key).collect();
+        assert_eq!(keys, vec!["c", "a"]);
+    }
+}
@@ -103,6 +103,124 @@ enum Commands {
         #[arg(short = 'p', long)]
         profile: Option<String>,
     },
+    /// Report ask/deny frequency from the Claude and Codex decision logs
+    Audit {
+        /// Read only this runtime's log (default: both)
+        #[arg(short, long, value_enum)]
+        runtime: Option<HookAdapter>,
+
+        /// Only entries at or after this time: a duration ago (30m, 24h, 7d, 2w), RFC 3339 or YYYY-MM-DD
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only entries before this time (same formats as --since)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Only entries from this session id
+        #[arg(long)]
+        session: Option<String>,
+
+        /// Rows per breakdown
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+
+        /// Emit the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Trace how a command is evaluated: leaves, rule hits, allowlist and classifier hits, and the all-covered gate
+    Explain {
+        /// Command string to explain
+        command: String,
+
+        /// Emit the trace as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Profile to activate (overrides runtime default)
+        #[arg(short = 'p', long)]
+        profile: Option<String>,
+    },
+    /// Re-evaluate logged shell commands under the current configuration and report decisions that would flip
+    Replay {
+        /// Decision log to replay; repeatable (default: the Claude and Codex logs, including rotated files)
+        #[arg(long)]
+        log: Vec<PathBuf>,
+
+        /// Only entries at or after this time (same formats as `audit --since`)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Exit 1 when more than this many entries become more restrictive
+        #[arg(long)]
+        max_tightened: Option<usize>,
+
+        /// Exit 1 when more than this many entries become less restrictive
+        #[arg(long)]
+        max_loosened: Option<usize>,
+
+        /// Emit the report as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Profile to activate (overrides runtime default)
+        #[arg(short = 'p', long)]
+        profile: Option<String>,
+    },
+    /// Propose allowlist entries for a project from commands it asked about by default
+    Suggest {
+        /// Project directory (default: --dir or the current directory)
+        #[arg(long)]
+        project: Option<PathBuf>,
+
+        /// Read only this runtime's log (default: both)
+        #[arg(short, long, value_enum)]
+        runtime: Option<HookAdapter>,
+
+        /// Only entries at or after this time (same formats as `audit --since`)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Propose only labels asked at least this many times
+        #[arg(long, default_value_t = 2)]
+        min_count: usize,
+
+        /// Emit the suggestions as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Profile to activate (overrides runtime default)
+        #[arg(short = 'p', long)]
+        profile: Option<String>,
+    },
+    /// Verify the `examples:` on every rule and allowlist entry, or run YAML decision suites
+    Test {
+        /// Suite file or directory of `.yaml` suites (default: check inline examples)
+        path: Option<PathBuf>,
+
+        /// Emit the results as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Also write suite results as JUnit XML to this file
+        #[arg(long, requires = "path")]
+        junit: Option<PathBuf>,
+
+        /// Profile to activate (overrides runtime default)
+        #[arg(short = 'p', long)]
+        profile: Option<String>,
+    },
+    /// Check the finalized rules for matchers that never fire, shadowed allowlist entries, duplicate ids and similar mistakes
+    Lint {
+        /// Emit the issues as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Profile to activate (overrides runtime default)
+        #[arg(short = 'p', long)]
+        profile: Option<String>,
+    },
     /// Show current rule configuration
     Rules {
         /// Show full matcher patterns and details
@@ -137,6 +255,50 @@ enum Commands {
         #[arg(short, long)]
         force: bool,
     },
+    /// Add longline's hook entries to the Claude or Codex settings
+    Install {
+        /// Runtime whose settings to edit
+        #[arg(short, long, value_enum)]
+        runtime: HookAdapter,
+
+        /// Edit the user settings or the current project's
+        #[arg(long, value_enum, default_value_t = InstallScope::User)]
+        scope: InstallScope,
+
+        /// Run the hook with the lenient AI judge
+        #[arg(long = "ask-ai-lenient")]
+        ask_ai_lenient: bool,
+
+        /// Print the diff without writing the file
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Remove longline's hook entries from the Claude or Codex settings
+    Uninstall {
+        /// Runtime whose settings to edit
+        #[arg(short, long, value_enum)]
+        runtime: HookAdapter,
+
+        /// Edit the user settings or the current project's
+        #[arg(long, value_enum, default_value_t = InstallScope::User)]
+        scope: InstallScope,
+
+        /// Print the diff without writing the file
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Check configs, hook wiring, log directories and AI judge providers, and suggest fixes
+    Doctor {
+        /// Emit the checks as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the JSON Schema for a config file, for editor validation
+    Schema {
+        /// Which file the schema describes
+        #[arg(value_enum, default_value_t = SchemaArg::Rules)]
+        kind: SchemaArg,
+    },
     /// Run a hook for a specific runtime (Codex or explicit Claude)
     Hook {
         /// Adapter to dispatch to
@@ -164,6 +326,29 @@ enum HookAdapter {
     Codex,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+enum InstallScope {
+    User,
+    Project,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+enum SchemaArg {
+    Rules,
+    Overlay,
+    AiJudge,
+}
+
+impl SchemaArg {
+    fn to_schema_kind(self) -> config::SchemaKind {
+        match self {
+            SchemaArg::Rules => config::SchemaKind::Rules,
+            SchemaArg::Overlay => config::SchemaKind::Overlay,
+            SchemaArg::AiJudge => config::SchemaKind::AiJudge,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 enum RulesFilter {
     Decision(DecisionFilter),
@@ -312,6 +497,9 @@ fn print_global_config_banner() {
                 if !allowlists.commands.is_empty() {
                     println!("  allowlists: {} commands", allowlists.commands.len());
                 }
+                if !allowlists.paths.is_empty() {
+                    println!("  allowlists: {} paths", allowlists.paths.len());
+                }
             }
             if let Some(ref rules) = config.rules {
                 if !rules.is_empty() {
@@ -386,6 +574,65 @@ pub fn run() -> i32 {
         return run_init(*force);
     }
 
+    // Handle Install/Uninstall early (edit runtime settings, no rules config needed)
+    match &cli.command {
+        Some(Commands::Install {
+            runtime,
+            scope,
+            ask_ai_lenient,
+            dry_run,
+        }) => {
+            return run_install(
+                *runtime,
+                *scope,
+                Some(*ask_ai_lenient),
+                *dry_run,
+                cli.dir.as_ref(),
+            )
+        }
+        Some(Commands::Uninstall {
+            runtime,
+            scope,
+            dry_run,
+        }) => return run_install(*runtime, *scope, None, *dry_run, cli.dir.as_ref()),
+        _ => {}
+    }
+
+    // Handle Schema early (generated from the config types, no config needed)
+    if let Some(Commands::Schema { kind }) = &cli.command {
+        let schema = config::json_schema(kind.to_schema_kind());
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&schema).expect("schema serializes")
+        );
+        return 0;
+    }
+
+    // Handle Doctor early (loads the rules itself so a broken manifest is reported, not fatal)
+    if let Some(Commands::Doctor { json }) = &cli.command {
+        return run_doctor(cli.config.as_ref(), cli.dir.as_ref(), *json);
+    }
+
+    // Handle Audit command early (reads the decision logs, no rules config needed)
+    if let Some(Commands::Audit {
+        runtime,
+        since,
+        until,
+        session,
+        top,
+        json,
+    }) = &cli.command
+    {
+        return run_audit(
+            *runtime,
+            since.as_deref(),
+            until.as_deref(),
+            session.clone(),
+            *top,
+            *json,
+        );
+    }
+
     // Handle Profiles command early (reads overlays independently, no rules config needed)
     if let Some(Commands::Profiles { runtime, json }) = &cli.command {
         return run_profiles(runtime.clone(), *json, cli.dir.as_ref());
@@ -449,20 +696,37 @@ pub fn run() -> i32 {
     // finalizes after reading cwd from stdin. Other subcommands resolve
     // project dir and finalize config eagerly.
     let is_hook_mode = matches!(cli.command, Some(Commands::Hook { .. }) | None);
+    // Replay finalizes per logged cwd and runtime, like the hooks do, and
+    // suites per case.
+    let defers_finalize = is_hook_mode
+        || matches!(
+            cli.command,
+            Some(Commands::Replay { .. }) | Some(Commands::Test { path: Some(_), .. })
+        );
 
     // Extract the subcommand-level profile override before consuming cli.command.
     // Hook and bare-form profiles are threaded separately (via HookOptions).
     let subcommand_profile: Option<String> = match &cli.command {
         Some(Commands::Check { profile, .. }) => profile.clone(),
+        Some(Commands::Explain { profile, .. }) => profile.clone(),
+        Some(Commands::Suggest { profile, .. }) => profile.clone(),
+        Some(Commands::Test { profile, .. }) => profile.clone(),
+        Some(Commands::Lint { profile, .. }) => profile.clone(),
         Some(Commands::Rules { profile, .. }) => profile.clone(),
         Some(Commands::Files { profile }) => profile.clone(),
         _ => None,
     };
 
-    let (rules_config, project_config_path, replaced_ids) = if is_hook_mode {
+    let (rules_config, project_config_path, replaced_ids) = if defers_finalize {
         (base_config, None, Vec::new())
     } else {
-        let project_dir = resolve_dir(cli.dir.as_ref());
+        let project_dir = match &cli.command {
+            Some(Commands::Suggest {
+                project: Some(project),
+                ..
+            }) => Some(project.clone()),
+            _ => resolve_dir(cli.dir.as_ref()),
+        };
         let final_config = match config::finalize_config(
             base_config,
             &home_dir(),
@@ -488,8 +752,82 @@ pub fn run() -> i32 {
 
     match cli.command {
         Some(Commands::Check { file, filter, .. }) => {
-            run_check(&rules_config, file, filter, project_config_path.as_ref())
+            let dir =
+                resolve_dir(cli.dir.as_ref()).map(|dir| std::path::absolute(&dir).unwrap_or(dir));
+            run_check(
+                &rules_config,
+                file,
+                filter,
+                dir.as_deref(),
+                project_config_path.as_ref(),
+            )
+        }
+        Some(Commands::Explain { command, json, .. }) => {
+            let dir =
+                resolve_dir(cli.dir.as_ref()).map(|dir| std::path::absolute(&dir).unwrap_or(dir));
+            run_explain(&rules_config, &command, dir.as_deref(), json)
         }
+        Some(Commands::Test {
+            path: Some(path),
+            json,
+            junit,
+            profile,
+        }) => run_test_suites(
+            &rules_config,
+            &path,
+            crate::suites::SuiteOptions {
+                home: &home_dir(),
+                dir_override: cli.dir.as_deref(),
+                cli_trust_level,
+                cli_safety_level,
+                profile: profile.as_deref(),
+            },
+            json,
+            junit.as_deref(),
+        ),
+        Some(Commands::Test { json, .. }) => run_test(&rules_config, json),
+        Some(Commands::Lint { json, .. }) => run_lint(
+            &rules_config,
+            cli.config.as_deref(),
+            project_config_path.as_deref(),
+            json,
+        ),
+        Some(Commands::Replay {
+            log,
+            since,
+            max_tightened,
+            max_loosened,
+            json,
+            profile,
+        }) => run_replay(
+            &rules_config,
+            log,
+            since.as_deref(),
+            crate::replay::ReplayOptions {
+                home: &home_dir(),
+                dir_override: cli.dir.as_deref(),
+                cli_trust_level,
+                cli_safety_level,
+                profile: profile.as_deref(),
+            },
+            (max_tightened, max_loosened),
+            json,
+        ),
+        Some(Commands::Suggest {
+            project,
+            runtime,
+            since,
+            min_count,
+            json,
+            ..
+        }) => run_suggest(
+            &rules_config,
+            project.or_else(|| resolve_dir(cli.dir.as_ref())),
+            runtime,
+            since.as_deref(),
+            min_count,
+            json,
+        ),
         Some(Commands::Rules {
             verbose,
             filter,
@@ -507,7 +845,11 @@ pub fn run() -> i32 {
         ),
         Some(Commands::Files { .. }) => unreachable!(), // handled above
         Some(Commands::Init { .. }) => unreachable!(),  // handled above
+        Some(Commands::Install { .. } | Commands::Uninstall { .. }) => unreachable!(), // handled above
+        Some(Commands::Doctor { .. }) => unreachable!(), // handled above
+        Some(Commands::Schema { .. }) => unreachable!(), // handled above
         Some(Commands::Profiles { .. }) => unreachable!(), // handled above
+        Some(Commands::Audit { .. }) => unreachable!(),  // handled above
         Some(Commands::Hook {
             adapter: HookAdapter::Codex,
             profile,
@@ -570,8 +912,10 @@ fn run_check(
     config: &policy::RulesConfig,
     file: Option<PathBuf>,
     filter: Option<DecisionFilter>,
+    dir: Option<&Path>,
     project_config_path: Option<&PathBuf>,
 ) -> i32 {
+    let cwd = dir.and_then(Path::to_str);
     print_global_config_banner();
 
     if let Some(path) = project_config_path {
@@ -598,7 +942,7 @@ fn run_check(
     for cmd_str in commands {
         let (decision, rule_label) = match parser::parse(cmd_str) {
             Ok(stmt) => {
-                let result = policy::evaluate(config, &stmt);
+                let result = policy::evaluate_with_findings(config, &stmt, cwd).result;
                 let label = match &result.rule_id {
                     Some(id) => id.clone(),
                     None => match result.decision {
@@ -635,6 +979,275 @@ fn run_check(
     0
 }
 
+/// The runtimes whose decision logs `audit` and `suggest` read.
+fn audit_runtimes(runtime: Option<HookAdapter>) -> &'static [&'static str] {
+    match runtime {
+        Some(HookAdapter::Claude) => &["claude"],
+        Some(HookAdapter::Codex) => &["codex"],
+        None => &["claude", "codex"],
+    }
+}
+
+fn run_audit(
+    runtime: Option<HookAdapter>,
+    since: Option<&str>,
+    until: Option<&str>,
+    session: Option<String>,
+    top: usize,
+    json: bool,
+) -> i32 {
+    let now = chrono::Utc::now();
+    let bound = |value: Option<&str>| {
+        value
+            .map(|v| crate::audit::parse_time_bound(v, now))
+            .transpose()
+    };
+    let filter = match (bound(since), bound(until)) {
+        (Ok(since), Ok(until)) => crate::audit::AuditFilter {
+            since,
+            until,
+            session,
+        },
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("longline: {e}");
+            return 1;
+        }
+    };
+
+    let home = home_dir();
+    let files: Vec<(PathBuf, &'static str)> = audit_runtimes(runtime)
+        .iter()
+        .flat_map(|rt| {
+            crate::audit::log_files(&home, rt)
+                .into_iter()
+                .map(move |path| (path, *rt))
+        })
+        .collect();
+
+    let report = match crate::audit::analyze(&files, &filter, top) {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("longline: {e}");
+            return 1;
+        }
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        crate::output::print_audit_report(&report);
+    }
+    0
+}
+
+fn run_replay(
+    base: &policy::RulesConfig,
+    logs: Vec<PathBuf>,
+    since: Option<&str>,
+    options: crate::replay::ReplayOptions,
+    (max_tightened, max_loosened): (Option<usize>, Option<usize>),
+    json: bool,
+) -> i32 {
+    let filter = match since
+        .map(|v| crate::audit::parse_time_bound(v, chrono::Utc::now()))
+        .transpose()
+    {
+        Ok(since) => crate::audit::AuditFilter {
+            since,
+            ..Default::default()
+        },
+        Err(e) => {
+            eprintln!("longline: {e}");
+            return 1;
+        }
+    };
+    let files: Vec<(PathBuf, &'static str)> = if logs.is_empty() {
+        audit_runtimes(None)
+            .iter()
+            .flat_map(|rt| {
+                crate::audit::log_files(options.home, rt)
+                    .into_iter()
+                    .map(move |path| (path, *rt))
+            })
+            .collect()
+    } else {
+        logs.into_iter().map(|path| (path, "claude")).collect()
+    };
+
+    let report = match crate::replay::replay(base, &files, &filter, &options) {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("longline: {e}");
+            return 1;
+        }
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        crate::output::print_replay_report(&report);
+    }
+
+    let violations = crate::replay::budget_violations(&report, max_tightened, max_loosened);
+    for violation in &violations {
+        eprintln!("longline: {violation}");
+    }
+    i32::from(!violations.is_empty())
+}
+
+fn run_suggest(
+    config: &policy::RulesConfig,
+    project: Option<PathBuf>,
+    runtime: Option<HookAdapter>,
+    since: Option<&str>,
+    min_count: usize,
+    json: bool,
+) -> i32 {
+    let Some(project) = project else {
+        eprintln!("longline: cannot determine the project directory; pass --project");
+        return 1;
+    };
+    let root = config::find_project_root(&project).unwrap_or(project);
+    let filter = match since
+        .map(|v| crate::audit::parse_time_bound(v, chrono::Utc::now()))
+        .transpose()
+    {
+        Ok(since) => crate::audit::AuditFilter {
+            since,
+            ..Default::default()
+        },
+        Err(e) => {
+            eprintln!("longline: {e}");
+            return 1;
+        }
+    };
+
+    let home = home_dir();
+    let files: Vec<(PathBuf, &'static str)> = audit_runtimes(runtime)
+        .iter()
+        .flat_map(|rt| {
+            crate::audit::log_files(&home, rt)
+                .into_iter()
+                .map(move |path| (path, *rt))
+        })
+        .collect();
+    let commands = match crate::audit::asked_commands_under(&files, &filter, &root) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("longline: {e}");
+            return 1;
+        }
+    };
+
+    let suggestions = policy::suggest_allowlist(
+        config,
+        commands
+            .iter()
+            .map(|(command, cwd)| (command.as_str(), Some(cwd.as_str()))),
+        min_count,
+    );
+    let snippet = policy::render_allowlist_snippet(&suggestions, config.trust_level);
+    if json {
+        let report = serde_json::json!({
+            "project": root.display().to_string(),
+            "asked_commands": commands.len(),
+            "trust_level": config.trust_level,
+            "suggestions": suggestions,
+            "snippet": snippet,
+        });
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        crate::output::print_suggestions(&root, commands.len(), &suggestions, &snippet);
+    }
+    0
+}
+
+fn run_explain(config: &policy::RulesConfig, command: &str, dir: Option<&Path>, json: bool) -> i32 {
+    let cwd = dir.and_then(Path::to_str);
+    let explanation = match policy::explain(config, command, cwd) {
+        Ok(e) => e,
+        Err(e) => {
+            eprintln!("longline: {e}");
+            return 1;
+        }
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&explanation).unwrap());
+    } else {
+        crate::output::print_explanation(&explanation);
+    }
+    0
+}
+
+/// Check every inline example; exit 1 when any fails.
+fn run_test(config: &policy::RulesConfig, json: bool) -> i32 {
+    let results = policy::check_examples(config);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&results).unwrap());
+    } else {
+        crate::output::print_example_results(&results);
+    }
+    i32::from(results.iter().any(|r| !r.passed()))
+}
+
+/// Lint the finalized rules against the files they came from; exit 1 when
+/// any issue is an error.
+fn run_lint(
+    config: &policy::RulesConfig,
+    explicit_config: Option<&Path>,
+    project_overlay: Option<&Path>,
+    json: bool,
+) -> i32 {
+    let default_path = default_config_path();
+    let rules_path = explicit_config.or(Some(default_path.as_path()).filter(|p| p.exists()));
+    let files = match crate::lint::source_files(rules_path, &home_dir(), project_overlay) {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("longline: {e}");
+            return 2;
+        }
+    };
+    let issues = crate::lint::lint(config, &files);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&issues).unwrap());
+    } else {
+        crate::output::print_lint_issues(&issues);
+    }
+    i32::from(
+        issues
+            .iter()
+            .any(|i| i.severity == crate::lint::Severity::Error),
+    )
+}
+
+/// Run every suite under `path`; exit 1 when any case fails.
+fn run_test_suites(
+    base: &policy::RulesConfig,
+    path: &Path,
+    options: crate::suites::SuiteOptions,
+    json: bool,
+    junit: Option<&Path>,
+) -> i32 {
+    let files = match crate::suites::suite_files(path) {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("longline: {e}");
+            return 2;
+        }
+    };
+    let reports = crate::suites::run_suites(base, &files, &options);
+    if let Some(junit) = junit {
+        if let Err(e) = std::fs::write(junit, crate::suites::junit_xml(&reports)) {
+            eprintln!("longline: Failed to write {}: {e}", junit.display());
+            return 2;
+        }
+    }
+    if json {
+        println!("{}", serde_json::to_string_pretty(&reports).unwrap());
+    } else {
+        crate::output::print_suite_reports(&reports);
+    }
+    i32::from(reports.iter().any(|r| !r.passed()))
+}
+
 fn read_check_input(file_or_command: Option<PathBuf>) -> Result<String, String> {
     match file_or_command {
         Some(path) if path.to_str() == Some("-") => read_check_stdin(),
@@ -793,6 +1406,7 @@ fn run_rules(
     } else {
         crate::output::print_allowlist_summary(&display_commands);
     }
+    crate::output::print_path_allowlist(&config.allowlists.paths);
 
     println!(
         "Safety level: {} | Trust level: {} ({}/{} allowlist active) | Default decision: {}",
@@ -946,6 +1560,92 @@ fn run_files(
     0
 }
 
+/// Install (`ask_ai_lenient` is `Some`) or uninstall longline's hooks.
+fn run_install(
+    runtime: HookAdapter,
+    scope: InstallScope,
+    ask_ai_lenient: Option<bool>,
+    dry_run: bool,
+    dir_override: Option<&PathBuf>,
+) -> i32 {
+    use crate::install;
+
+    let runtime = match runtime {
+        HookAdapter::Claude => install::Runtime::Claude,
+        HookAdapter::Codex => install::Runtime::Codex,
+    };
+    let scope = match scope {
+        InstallScope::User => install::Scope::User,
+        InstallScope::Project => install::Scope::Project,
+    };
+    let project = resolve_dir(dir_override).unwrap_or_else(|| PathBuf::from("."));
+    let project = config::find_project_root(&project).unwrap_or(project);
+    let path = install::settings_path(runtime, scope, &home_dir(), &project);
+
+    let change = match ask_ai_lenient {
+        Some(lenient) => install::install(runtime, &path, lenient),
+        None => install::uninstall(&path),
+    };
+    let change = match change {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("longline: {e}");
+            return 1;
+        }
+    };
+    let action = if ask_ai_lenient.is_some() {
+        "installed in"
+    } else {
+        "removed from"
+    };
+    if change.is_noop() {
+        println!(
+            "No changes: longline hooks already {action} {}",
+            path.display()
+        );
+        return 0;
+    }
+    if dry_run {
+        print!("{}", install::unified_diff(&change));
+        return 0;
+    }
+    if let Err(e) = install::apply(&change) {
+        eprintln!("longline: {e}");
+        return 1;
+    }
+    println!("longline hooks {action} {}", path.display());
+    0
+}
+
+/// Run the health checks. Exits 1 when any check fails.
+fn run_doctor(
+    explicit_config: Option<&PathBuf>,
+    dir_override: Option<&PathBuf>,
+    json: bool,
+) -> i32 {
+    let rules_source = match explicit_config {
+        Some(path) => path.display().to_string(),
+        None if default_config_path().exists() => default_config_path().display().to_string(),
+        None => "embedded defaults".to_string(),
+    };
+    let project_dir = resolve_dir(dir_override);
+    let checks = crate::doctor::run_checks(crate::doctor::DoctorContext {
+        home: &home_dir(),
+        project_dir: project_dir.as_deref(),
+        rules: load_config(explicit_config),
+        rules_source,
+    });
+    if json {
+        println!("{}", serde_json::to_string_pretty(&checks).unwrap());
+    } else {
+        crate::output::print_doctor_report(&checks);
+    }
+    let failed = checks
+        .iter()
+        .any(|c| c.status == crate::doctor::Status::Fail);
+    i32::from(failed)
+}
+
 fn run_init(force: bool) -> i32 {
     let rules_yaml_path = default_config_path();
     let target_dir = rules_yaml_path
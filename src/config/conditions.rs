@@ -0,0 +1,201 @@
+//! `when:` clauses on rules, checked against where longline is running.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::discovery::find_project_root;
+use crate::config::rules::{RuleCondition, StringOrList};
+
+/// What a `when:` clause is checked against, gathered once per finalized
+/// configuration.
+#[derive(Debug, Clone)]
+pub struct ConditionContext {
+    pub home: PathBuf,
+    pub cwd: Option<PathBuf>,
+    pub project_root: Option<PathBuf>,
+    pub branch: Option<String>,
+    pub runtime: String,
+}
+
+impl ConditionContext {
+    /// Resolve the project root and branch for `cwd`, made absolute first
+    /// so relative `--dir` values compare like hook working directories.
+    pub fn detect(home: &Path, cwd: Option<&Path>, runtime: &str) -> Self {
+        let cwd = cwd.map(|dir| std::path::absolute(dir).unwrap_or_else(|_| dir.to_path_buf()));
+        let project_root = cwd.as_deref().and_then(find_project_root);
+        let branch = project_root.as_deref().and_then(current_branch);
+        Self {
+            home: home.to_path_buf(),
+            cwd,
+            project_root,
+            branch,
+            runtime: runtime.to_string(),
+        }
+    }
+}
+
+/// The branch checked out under `root`, from `.git/HEAD`, following a
+/// worktree's `.git` file to its git directory. None on a detached HEAD.
+pub fn current_branch(root: &Path) -> Option<String> {
+    let dot_git = root.join(".git");
+    let git_dir = if dot_git.is_file() {
+        let pointer = fs::read_to_string(&dot_git).ok()?;
+        root.join(pointer.trim().strip_prefix("gitdir:")?.trim())
+    } else {
+        dot_git
+    };
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    head.trim()
+        .strip_prefix("ref: refs/heads/")
+        .map(str::to_string)
+}
+
+impl RuleCondition {
+    pub fn holds(&self, context: &ConditionContext) -> bool {
+        let root = context.project_root.as_deref();
+        let matches_any = |patterns: &StringOrList, value: Option<&str>| {
+            value.is_some_and(|value| {
+                patterns
+                    .patterns()
+                    .iter()
+                    .any(|pattern| glob_match::glob_match(pattern, value))
+            })
+        };
+        self.cwd.as_ref().is_none_or(|patterns| {
+            patterns
+                .patterns()
+                .iter()
+                .any(|pattern| cwd_matches(pattern, context))
+        }) && self.project.as_ref().is_none_or(|patterns| {
+            matches_any(
+                patterns,
+                root.and_then(Path::file_name)
+                    .and_then(|name| name.to_str()),
+            )
+        }) && self
+            .branch
+            .as_ref()
+            .is_none_or(|patterns| matches_any(patterns, context.branch.as_deref()))
+            && self
+                .markers
+                .iter()
+                .all(|marker| root.is_some_and(|root| root.join(marker).exists()))
+            && self
+                .runtime
+                .as_ref()
+                .is_none_or(|patterns| matches_any(patterns, Some(&context.runtime)))
+    }
+}
+
+/// Whether the working directory is the directory `pattern` names or lies
+/// beneath it.
+fn cwd_matches(pattern: &str, context: &ConditionContext) -> bool {
+    let Some(cwd) = context.cwd.as_deref().and_then(Path::to_str) else {
+        return false;
+    };
+    let pattern = if let Some(rest) = pattern.strip_prefix("~/") {
+        context.home.join(rest)
+    } else if Path::new(pattern).is_absolute() {
+        PathBuf::from(pattern)
+    } else {
+        match &context.project_root {
+            Some(root) => root.join(pattern),
+            None => return false,
+        }
+    };
+    let pattern = std::path::absolute(&pattern).unwrap_or(pattern);
+    let Some(pattern) = pattern.to_str().map(|p| p.trim_end_matches('/')) else {
+        return false;
+    };
+    glob_match::glob_match(pattern, cwd) || glob_match::glob_match(&format!("{pattern}/**"), cwd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn condition(yaml: &str) -> RuleCondition {
+        serde_norway::from_str(yaml).unwrap()
+    }
+
+    fn repo(branch_head: &str) -> tempfile::TempDir {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        fs::write(dir.path().join(".git/HEAD"), branch_head).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_current_branch_reads_head() {
+        let dir = repo("ref: refs/heads/feature/login\n");
+        assert_eq!(current_branch(dir.path()).as_deref(), Some("feature/login"));
+        let detached = repo("3f2a9c1e0b7d4a6f8e5c2b1a0d9e8f7c6b5a4d3e\n");
+        assert_eq!(current_branch(detached.path()), None);
+    }
+
+    #[test]
+    fn test_current_branch_follows_worktree_pointer() {
+        let main = repo("ref: refs/heads/main\n");
+        let worktree_git = main.path().join(".git/worktrees/wt");
+        fs::create_dir_all(&worktree_git).unwrap();
+        fs::write(worktree_git.join("HEAD"), "ref: refs/heads/hotfix\n").unwrap();
+        let worktree = tempfile::TempDir::new().unwrap();
+        fs::write(
+            worktree.path().join(".git"),
+            format!("gitdir: {}\n", worktree_git.display()),
+        )
+        .unwrap();
+        assert_eq!(current_branch(worktree.path()).as_deref(), Some("hotfix"));
+    }
+
+    #[test]
+    fn test_branch_and_project_conditions() {
+        let dir = repo("ref: refs/heads/main\n");
+        let context = ConditionContext::detect(Path::new("/home/u"), Some(dir.path()), "claude");
+        assert!(condition("branch: { any_of: [main, master] }").holds(&context));
+        assert!(!condition("branch: \"feature/*\"").holds(&context));
+        let name = dir.path().file_name().unwrap().to_str().unwrap();
+        assert!(condition(&format!("project: \"{name}\"")).holds(&context));
+        assert!(!condition("project: other").holds(&context));
+    }
+
+    #[test]
+    fn test_cwd_patterns_cover_subdirectories() {
+        let dir = repo("ref: refs/heads/main\n");
+        let prod = dir.path().join("infra/prod/network");
+        fs::create_dir_all(&prod).unwrap();
+        let home = dir.path().parent().unwrap();
+        let context = ConditionContext::detect(home, Some(&prod), "claude");
+        assert!(condition("cwd: infra/prod").holds(&context));
+        assert!(condition("cwd: \"infra/*/network\"").holds(&context));
+        assert!(!condition("cwd: infra/staging").holds(&context));
+        let absolute = format!("cwd: \"{}\"", dir.path().join("infra").display());
+        assert!(condition(&absolute).holds(&context));
+        let from_home = format!(
+            "cwd: \"~/{}/infra\"",
+            dir.path().file_name().unwrap().to_str().unwrap()
+        );
+        assert!(condition(&from_home).holds(&context));
+    }
+
+    #[test]
+    fn test_markers_and_runtime_conditions() {
+        let dir = repo("ref: refs/heads/main\n");
+        fs::write(dir.path().join("main.tf"), "").unwrap();
+        let context = ConditionContext::detect(Path::new("/home/u"), Some(dir.path()), "codex");
+        assert!(condition("markers: [main.tf, .git]").holds(&context));
+        assert!(!condition("markers: [main.tf, Cargo.toml]").holds(&context));
+        assert!(condition("runtime: codex").holds(&context));
+        assert!(!condition("runtime: claude").holds(&context));
+    }
+
+    #[test]
+    fn test_conditions_fail_without_a_directory() {
+        let context = ConditionContext::detect(Path::new("/home/u"), None, "claude");
+        assert!(condition("{}").holds(&context));
+        assert!(condition("runtime: claude").holds(&context));
+        assert!(!condition("cwd: \"/**\"").holds(&context));
+        assert!(!condition("branch: \"*\"").holds(&context));
+        assert!(!condition("markers: [.git]").holds(&context));
+    }
+}
@@ -0,0 +1,444 @@
+//! Readable YAML load errors: the failing key's line and column, a precise
+//! reason when an untagged `match:` fails, and "did you mean" for
+//! misspelt keys and values.
+
+use serde::de::DeserializeOwned;
+use serde_norway::Value;
+
+use crate::config::rules::{
+    ArgsMatcher, EnvMatcher, FlagsMatcher, GitConfigMatcher, Matcher, McpMatcher, PathMatcher,
+    PipelineMatcher, RedirectMatcher, StringOrList, UrlMatcher,
+};
+
+/// Deserialize `content`, rendering a failure with [`describe_yaml_error`].
+pub fn from_yaml_str<T: DeserializeOwned>(content: &str) -> Result<T, String> {
+    serde_norway::from_str(content).map_err(|e| describe_yaml_error(content, &e))
+}
+
+/// `path: reason (did you mean ...?) at line L column C`, where serde's own
+/// message for an untagged `match:` is replaced by what is actually wrong
+/// with it, and the location moves to the offending key when it can be
+/// found.
+pub fn describe_yaml_error(content: &str, err: &serde_norway::Error) -> String {
+    let raw = err.to_string();
+    let mut location = err.location().map(|l| (l.line(), l.column()));
+    let message = match raw.rfind(" at line ") {
+        Some(i) if location.is_some() => &raw[..i],
+        _ => raw.as_str(),
+    };
+    let (mut path, mut reason) = split_path(message);
+
+    let node = serde_norway::from_str::<Value>(content)
+        .ok()
+        .and_then(|doc| resolve(&doc, &path).cloned());
+    let mut key = None;
+    if reason.starts_with("data did not match any variant of untagged enum Matcher") {
+        if let Some(diagnosis) = node
+            .as_ref()
+            .and_then(|n| n.get("match"))
+            .map(diagnose_matcher)
+        {
+            path = join_path(&join_path(&path, "match"), &diagnosis.subpath);
+            reason = diagnosis.reason;
+            key = diagnosis.key;
+        }
+    } else if let Some(field) = backticked_after(&reason, "missing field ") {
+        // Rules accept unknown keys, so a misspelt required key surfaces
+        // as the correct one missing.
+        let keys = node.as_ref().map(mapping_keys).unwrap_or_default();
+        if let Some(typo) = keys.iter().find(|k| similar(k, &field)) {
+            reason = format!("{reason}; found `{typo}`, did you mean `{field}`?");
+            key = Some(typo.clone());
+        }
+    } else {
+        reason = with_suggestion(&reason);
+    }
+
+    if let (Some(key), Some((line, _))) = (&key, location) {
+        if let Some(found) = find_key(content, key, line) {
+            location = Some(found);
+        }
+    }
+    let mut out = if path.is_empty() {
+        reason
+    } else {
+        format!("{path}: {reason}")
+    };
+    if let Some((line, column)) = location {
+        out.push_str(&format!(" at line {line} column {column}"));
+    }
+    out
+}
+
+/// Split serde's `path: message` prefix off, when there is one.
+fn split_path(message: &str) -> (String, String) {
+    if let Some((path, reason)) = message.split_once(": ") {
+        let is_path = !path.is_empty()
+            && path
+                .chars()
+                .all(|c| c.is_alphanumeric() || "_-.[]".contains(c));
+        if is_path {
+            return (path.to_string(), reason.to_string());
+        }
+    }
+    (String::new(), message.to_string())
+}
+
+fn join_path(path: &str, segment: &str) -> String {
+    match (path.is_empty(), segment.is_empty()) {
+        (_, true) => path.to_string(),
+        (true, false) => segment.to_string(),
+        (false, false) => format!("{path}.{segment}"),
+    }
+}
+
+/// The node at a serde path such as `profiles.strict.rules[2]`.
+fn resolve<'a>(doc: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut node = doc;
+    if path.is_empty() {
+        return Some(node);
+    }
+    for segment in path.split('.') {
+        let (name, indices) = segment.split_once('[').unwrap_or((segment, ""));
+        if !name.is_empty() {
+            node = node.get(name)?;
+        }
+        for index in indices.split('[').filter(|s| !s.is_empty()) {
+            node = node.get(index.trim_end_matches(']').parse::<usize>().ok()?)?;
+        }
+    }
+    Some(node)
+}
+
+fn mapping_keys(node: &Value) -> Vec<String> {
+    node.as_mapping()
+        .map(|m| {
+            m.keys()
+                .filter_map(|k| k.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+const MATCHER_KINDS: [&str; 10] = [
+    "all",
+    "any",
+    "not",
+    "command",
+    "pipeline",
+    "redirect",
+    "git_config",
+    "path",
+    "mcp",
+    "url",
+];
+const COMMAND_FIELDS: [&str; 4] = ["command", "flags", "args", "env"];
+
+struct Diagnosis {
+    /// Path below `match`.
+    subpath: String,
+    reason: String,
+    /// Key whose line the error should point at.
+    key: Option<String>,
+}
+
+/// Work out why a `match:` mapping, or a matcher nested under `all`, `any`
+/// or `not`, fits none of the matcher kinds.
+fn diagnose_matcher(node: &Value) -> Diagnosis {
+    let diagnosis = |subpath: &str, reason: String, key: Option<&str>| Diagnosis {
+        subpath: subpath.to_string(),
+        reason,
+        key: key.map(str::to_string),
+    };
+    let keys = mapping_keys(node);
+    let kinds: Vec<&str> = MATCHER_KINDS
+        .into_iter()
+        .filter(|kind| keys.iter().any(|k| k == kind))
+        .collect();
+    let expected = backtick_list(&MATCHER_KINDS);
+    let kind = match kinds.as_slice() {
+        [] => {
+            let Some(first) = keys.first() else {
+                return diagnosis("", format!("a matcher needs one of {expected}"), None);
+            };
+            let hint = suggestion(first, &MATCHER_KINDS);
+            return diagnosis(
+                "",
+                format!("a matcher needs one of {expected}, found `{first}`{hint}"),
+                Some(first),
+            );
+        }
+        [kind] => *kind,
+        [first, second, ..] => {
+            return diagnosis(
+                "",
+                format!("a matcher has both `{first}` and `{second}`; combine them under `all` or `any`"),
+                Some(second),
+            );
+        }
+    };
+
+    let allowed: &[&str] = if kind == "command" {
+        &COMMAND_FIELDS
+    } else {
+        std::slice::from_ref(&kind)
+    };
+    if let Some(unknown) = keys.iter().find(|k| !allowed.contains(&k.as_str())) {
+        return diagnosis(
+            "",
+            format!(
+                "unknown field `{unknown}` in a `{kind}` matcher, expected {}{}",
+                if allowed.len() == 1 {
+                    format!("only `{kind}`")
+                } else {
+                    format!("one of {}", backtick_list(allowed))
+                },
+                suggestion(unknown, allowed)
+            ),
+            Some(unknown),
+        );
+    }
+
+    // Point into the first nested matcher that fails on its own.
+    let children: Vec<(String, &Value)> = match (kind, node.get(kind)) {
+        ("all" | "any", Some(Value::Sequence(items))) => items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| (format!("{kind}[{i}]"), item))
+            .collect(),
+        ("not", Some(item)) => vec![("not".to_string(), item)],
+        _ => vec![],
+    };
+    for (subpath, child) in children {
+        if serde_norway::from_value::<Matcher>(child.clone()).is_err() {
+            let inner = diagnose_matcher(child);
+            return Diagnosis {
+                subpath: join_path(&subpath, &inner.subpath),
+                ..inner
+            };
+        }
+    }
+
+    for field in allowed {
+        let Some(value) = node.get(*field) else {
+            continue;
+        };
+        if let Err(e) = check_field(field, value) {
+            let message = e.to_string();
+            let (_, reason) = split_path(&message);
+            let key = backticked_after(&reason, "unknown field ");
+            return Diagnosis {
+                subpath: field.to_string(),
+                reason: with_suggestion(&reason),
+                key: key.or_else(|| Some(field.to_string())),
+            };
+        }
+    }
+    diagnosis(
+        "",
+        format!("data did not match a `{kind}` matcher"),
+        Some(kind),
+    )
+}
+
+fn check_field(field: &str, value: &Value) -> Result<(), serde_norway::Error> {
+    fn check<T: DeserializeOwned>(value: &Value) -> Result<(), serde_norway::Error> {
+        serde_norway::from_value::<T>(value.clone()).map(|_| ())
+    }
+    match field {
+        "all" | "any" => check::<Vec<Matcher>>(value),
+        "not" => check::<Matcher>(value),
+        "command" => check::<StringOrList>(value),
+        "flags" => check::<FlagsMatcher>(value),
+        "args" => check::<ArgsMatcher>(value),
+        "env" => check::<EnvMatcher>(value),
+        "pipeline" => check::<PipelineMatcher>(value),
+        "redirect" => check::<RedirectMatcher>(value),
+        "git_config" => check::<GitConfigMatcher>(value),
+        "path" => check::<PathMatcher>(value),
+        "mcp" => check::<McpMatcher>(value),
+        "url" => check::<UrlMatcher>(value),
+        _ => Ok(()),
+    }
+}
+
+/// Append "did you mean" to serde's `unknown field`/`unknown variant`
+/// messages when one of the expected names is close.
+fn with_suggestion(reason: &str) -> String {
+    let Some(got) = backticked_after(reason, "unknown field ")
+        .or_else(|| backticked_after(reason, "unknown variant "))
+    else {
+        return reason.to_string();
+    };
+    let Some((_, expected)) = reason.split_once("expected ") else {
+        return reason.to_string();
+    };
+    let candidates: Vec<&str> = expected.split('`').skip(1).step_by(2).collect();
+    format!("{reason}{}", suggestion(&got, &candidates))
+}
+
+/// ` (did you mean `x`?)` for the closest candidate, or nothing.
+fn suggestion(got: &str, candidates: &[&str]) -> String {
+    candidates
+        .iter()
+        .filter(|c| similar(got, c))
+        .min_by_key(|c| strsim::damerau_levenshtein(got, c))
+        .map(|c| format!(" (did you mean `{c}`?)"))
+        .unwrap_or_default()
+}
+
+fn similar(a: &str, b: &str) -> bool {
+    a != b && strsim::damerau_levenshtein(a, b) <= (b.chars().count() / 3).max(1)
+}
+
+/// The first backticked word right after `prefix`.
+fn backticked_after(text: &str, prefix: &str) -> Option<String> {
+    let rest = &text[text.find(prefix)? + prefix.len()..];
+    let rest = rest.strip_prefix('`')?;
+    Some(rest[..rest.find('`')?].to_string())
+}
+
+fn backtick_list(items: &[&str]) -> String {
+    items
+        .iter()
+        .map(|i| format!("`{i}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Line and column of `key:` at or after `from_line`, in block or flow
+/// style.
+fn find_key(content: &str, key: &str, from_line: usize) -> Option<(usize, usize)> {
+    let needle = format!("{key}:");
+    content
+        .lines()
+        .enumerate()
+        .skip(from_line.saturating_sub(1))
+        .find_map(|(i, line)| {
+            line.match_indices(&needle).find_map(|(col, _)| {
+                let boundary = line[..col]
+                    .chars()
+                    .last()
+                    .is_none_or(|c| c == ' ' || c == '{' || c == ',');
+                boundary.then_some((i + 1, col + 1))
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::rules::RulesConfig;
+
+    fn error(yaml: &str) -> String {
+        from_yaml_str::<RulesConfig>(yaml).unwrap_err()
+    }
+
+    #[test]
+    fn test_unknown_top_level_key_suggests_and_locates() {
+        let err = error("version: 1\nalowlists: {}\n");
+        assert!(err.contains("(did you mean `allowlists`?)"), "{err}");
+        assert!(err.ends_with("at line 2 column 1"), "{err}");
+    }
+
+    #[test]
+    fn test_unknown_variant_suggests() {
+        let err = error("version: 1\nsafety_level: hihg\n");
+        assert!(
+            err.starts_with("safety_level: unknown variant `hihg`"),
+            "{err}"
+        );
+        assert!(err.contains("(did you mean `high`?)"), "{err}");
+    }
+
+    #[test]
+    fn test_misspelt_required_rule_key() {
+        let err = error(
+            "version: 1\nrules:\n  - id: a\n    lvel: high\n    match: { command: x }\n    decision: ask\n    reason: r\n",
+        );
+        assert!(
+            err.starts_with("rules[0]: missing field `level`; found `lvel`, did you mean `level`?"),
+            "{err}"
+        );
+        assert!(err.ends_with("at line 4 column 5"), "{err}");
+    }
+
+    #[test]
+    fn test_untagged_matcher_reports_inner_unknown_field() {
+        let err = error(
+            "version: 1\nrules:\n  - id: a\n    level: high\n    match:\n      path:\n        targets: x\n    decision: ask\n    reason: r\n",
+        );
+        assert!(
+            err.starts_with("rules[0].match.path: unknown field `targets`"),
+            "{err}"
+        );
+        assert!(err.contains("(did you mean `target`?)"), "{err}");
+        assert!(err.ends_with("at line 7 column 9"), "{err}");
+    }
+
+    #[test]
+    fn test_untagged_matcher_reports_unknown_command_field() {
+        let err = error(
+            "version: 1\nrules:\n  - id: a\n    level: high\n    match: { command: x, arg: { any_of: [y] } }\n    decision: ask\n    reason: r\n",
+        );
+        assert!(
+            err.starts_with("rules[0].match: unknown field `arg` in a `command` matcher"),
+            "{err}"
+        );
+        assert!(err.contains("(did you mean `args`?)"), "{err}");
+        assert!(err.ends_with("at line 5 column 26"), "{err}");
+    }
+
+    #[test]
+    fn test_untagged_matcher_without_kind() {
+        let err = error(
+            "version: 1\nrules:\n  - id: a\n    level: high\n    match:\n      comand: x\n    decision: ask\n    reason: r\n",
+        );
+        assert!(
+            err.contains("a matcher needs one of `all`, `any`, `not`, `command`"),
+            "{err}"
+        );
+        assert!(
+            err.contains("found `comand` (did you mean `command`?)"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_untagged_matcher_in_profile_rule() {
+        let err = from_yaml_str::<crate::config::ProjectConfig>(
+            "profiles:\n  strict:\n    rules:\n      - id: a\n        level: high\n        match: { command: x, args: { anyof: [y] } }\n        decision: ask\n        reason: r\n",
+        )
+        .unwrap_err();
+        assert!(
+            err.starts_with("profiles.strict.rules[0].match.args: unknown field `anyof`"),
+            "{err}"
+        );
+        assert!(err.contains("(did you mean `any_of`?)"), "{err}");
+    }
+
+    #[test]
+    fn test_nested_matcher_error_points_into_combinator() {
+        let err = error(
+            "version: 1\nrules:\n  - id: a\n    level: high\n    match:\n      all:\n        - path: { target: \"**/.env*\" }\n        - not: { path: { targt: x } }\n    decision: ask\n    reason: r\n",
+        );
+        assert!(
+            err.starts_with("rules[0].match.all[1].not.path: unknown field `targt`"),
+            "{err}"
+        );
+        assert!(err.contains("(did you mean `target`?)"), "{err}");
+        assert!(err.ends_with("at line 8 column 26"), "{err}");
+    }
+
+    #[test]
+    fn test_invalid_regex_names_the_pattern() {
+        let err = error(
+            "version: 1\nrules:\n  - id: a\n    level: high\n    match:\n      command: helm\n      args:\n        regex: [\"(\"]\n    decision: ask\n    reason: r\n",
+        );
+        assert!(
+            err.starts_with("rules[0].match.args: invalid regex '('"),
+            "{err}"
+        );
+        assert!(err.ends_with("at line 7 column 7"), "{err}");
+    }
+}
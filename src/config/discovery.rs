@@ -1,6 +1,7 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use super::diagnostics::from_yaml_str;
 use crate::config::overlays::ProjectConfig;
 use crate::config::prompt::validate_project_ai_judge_prompt;
 
@@ -50,7 +51,7 @@ pub fn load_project_config(cwd: &Path) -> Result<Option<ProjectConfig>, String>
         Ok(c) => c,
         Err(_) => return Ok(None),
     };
-    let config: ProjectConfig = serde_norway::from_str(&content)
+    let config: ProjectConfig = from_yaml_str(&content)
         .map_err(|e| format!("Failed to parse {}: {e}", config_path.display()))?;
     validate_project_ai_judge_prompt(&config, &config_path)?;
     Ok(Some(config))
@@ -64,7 +65,7 @@ pub fn load_global_config(home: &Path) -> Result<Option<ProjectConfig>, String>
         Ok(c) => c,
         Err(_) => return Ok(None),
     };
-    let config: ProjectConfig = serde_norway::from_str(&content)
+    let config: ProjectConfig = from_yaml_str(&content)
         .map_err(|e| format!("Failed to parse {}: {e}", config_path.display()))?;
     if config
         .ai_judge
@@ -1,5 +1,6 @@
 use std::path::Path;
 
+use crate::config::conditions::ConditionContext;
 use crate::config::discovery::{
     find_project_root, global_config_path, load_global_config, load_project_config,
     project_config_path,
@@ -144,6 +145,14 @@ pub fn finalize_config(
         }
     }
 
+    // `when:` clauses resolve once here, after profiles have added their
+    // rules, so evaluation only ever sees rules that apply in this
+    // directory and runtime as active.
+    let context = ConditionContext::detect(home, project_dir, runtime);
+    for rule in &mut config.rules {
+        rule.applies = rule.when.as_ref().is_none_or(|when| when.holds(&context));
+    }
+
     // CLI flags apply LAST per the field-precedence ladder.
     if let Some(level) = cli_trust_level {
         config.trust_level = level;
@@ -401,9 +410,11 @@ mod tests {
             default_decision: Decision::Ask,
             safety_level: High,
             trust_level: Standard,
+            glob_expansion: Default::default(),
             allowlists: Allowlists {
                 commands: vec![],
                 paths: vec![],
+                hosts: vec![],
             },
             rules: vec![],
         };
@@ -424,9 +435,11 @@ mod tests {
             default_decision: Decision::Ask,
             safety_level: High,
             trust_level: Standard,
+            glob_expansion: Default::default(),
             allowlists: Allowlists {
                 commands: vec![],
                 paths: vec![],
+                hosts: vec![],
             },
             rules: vec![],
         };
@@ -454,9 +467,11 @@ mod tests {
             default_decision: Decision::Ask,
             safety_level: High,
             trust_level: Standard,
+            glob_expansion: Default::default(),
             allowlists: Allowlists {
                 commands: vec![],
                 paths: vec![],
+                hosts: vec![],
             },
             rules: vec![],
         };
@@ -578,4 +593,67 @@ profiles:
         .unwrap();
         assert_eq!(result.resolved_profile, "default");
     }
+
+    #[test]
+    fn test_finalize_activates_only_rules_whose_when_holds() {
+        use crate::config;
+        let home = tempfile::TempDir::new().unwrap();
+        let project_dir = tempfile::tempdir().unwrap();
+        let repo = project_dir.path();
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::write(repo.join(".git").join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        std::fs::create_dir(repo.join(".claude")).unwrap();
+        std::fs::write(
+            repo.join(".claude").join("longline.yaml"),
+            r#"
+rules:
+  - id: ask-push-main
+    level: high
+    match: { command: git, args: { any_of: [push] } }
+    decision: ask
+    reason: "Pushing from main"
+    when: { branch: main }
+  - id: ask-push-release
+    level: high
+    match: { command: git, args: { any_of: [push] } }
+    decision: ask
+    reason: "Pushing from a release branch"
+    when: { branch: "release/*" }
+  - id: codex-only
+    level: high
+    match: { command: curl }
+    decision: deny
+    reason: "Codex only"
+    when: { runtime: codex }
+"#,
+        )
+        .unwrap();
+
+        let result = finalize_config(
+            config::load_embedded_rules().unwrap(),
+            home.path(),
+            Some(repo),
+            None,
+            None,
+            "claude",
+            None,
+        )
+        .unwrap();
+
+        let active: Vec<(&str, bool)> = result
+            .rules
+            .rules
+            .iter()
+            .filter(|r| r.when.is_some())
+            .map(|r| (r.id.as_str(), result.rules.is_active(r)))
+            .collect();
+        assert_eq!(
+            active,
+            vec![
+                ("ask-push-main", true),
+                ("ask-push-release", false),
+                ("codex-only", false)
+            ]
+        );
+    }
 }
@@ -1,10 +1,15 @@
+pub mod conditions;
+pub mod diagnostics;
 pub mod discovery;
 pub mod finalize;
 pub mod overlays;
 pub mod profiles;
 pub mod prompt;
 pub mod rules;
+pub mod schema;
 
+pub use conditions::{current_branch, ConditionContext};
+pub use diagnostics::{describe_yaml_error, from_yaml_str};
 pub use discovery::{
     default_rules_path, existing_project_config_path, find_project_root, global_config_path,
     load_global_config, load_project_config, project_config_path,
@@ -16,9 +21,12 @@ pub use overlays::{
 };
 pub use prompt::{validate_ai_judge_prompt, validate_project_ai_judge_prompt};
 pub use rules::{
-    load_embedded_rules, load_embedded_rules_with_info, load_rules, load_rules_with_info,
-    ArgsMatcher, EnvException, EnvMatcher, EnvValueClass, FlagsMatcher, GitConfigMatcher,
-    GitConfigSource, LoadedConfig, LoadedFileInfo, Matcher, PartialRulesConfig, PipelineMatcher,
-    RedirectMatcher, Rule, RulesConfig, RulesManifestConfig, SafetyLevel, StageMatcher,
-    StringOrList, TrustLevel,
+    load_embedded_rules, load_embedded_rules_with_info, load_rules, load_rules_with_info, ArgRegex,
+    ArgsMatcher, EnvException, EnvMatcher, EnvValueClass, Examples, FlagsMatcher, GitConfigMatcher,
+    GitConfigSource, GlobExpansion, JsonPointer, LoadedConfig, LoadedFileInfo, Matcher,
+    MatcherScope, McpInputCondition, McpMatcher, PartialRulesConfig, PathMatcher, PathTool,
+    PipelineMatcher, PortList, RedirectMatcher, RegexPattern, Rule, RuleCondition, RulesConfig,
+    RulesManifestConfig, SafetyLevel, StageMatcher, StringOrList, TrustLevel, UrlMatcher,
+    ValuePattern,
 };
+pub use schema::{json_schema, SchemaKind};
@@ -1,9 +1,11 @@
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-use crate::config::rules::{Rule, RulesConfig, SafetyLevel, TrustLevel};
+use crate::config::rules::{Examples, GlobExpansion, Rule, RulesConfig, SafetyLevel, TrustLevel};
 
 /// Tracks whether a rule/entry came from built-in defaults, global config, or project config.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum RuleSource {
     #[default]
     BuiltIn,
@@ -11,27 +13,32 @@ pub enum RuleSource {
     Project,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct AllowlistEntry {
     pub command: String,
     pub trust: TrustLevel,
     #[serde(default)]
     pub reason: Option<String>,
+    #[serde(default)]
+    pub examples: Examples,
     #[serde(skip)]
     pub source: RuleSource,
 }
 
-#[derive(Debug, Default, Clone, Deserialize)]
+#[derive(Debug, Default, Clone, Deserialize, JsonSchema)]
 pub struct Allowlists {
     #[serde(default)]
     pub commands: Vec<AllowlistEntry>,
+    /// Glob patterns for paths exempt from `ask` path rules.
     #[serde(default)]
-    #[allow(dead_code)]
     pub paths: Vec<String>,
+    /// Glob patterns for hosts exempt from `ask` url rules.
+    #[serde(default)]
+    pub hosts: Vec<String>,
 }
 
 /// Per-project AI judge customization in `.claude/longline.yaml`.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct ProjectAiJudgeConfig {
     /// Full reasoning prompt that overrides the built-in template.
@@ -42,7 +49,7 @@ pub struct ProjectAiJudgeConfig {
 
 /// Per-project config loaded from `.claude/longline.yaml`.
 /// All fields are optional; only specified fields override the global config.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct ProjectConfig {
     pub override_safety_level: Option<SafetyLevel>,
@@ -51,6 +58,7 @@ pub struct ProjectConfig {
     pub rules: Option<Vec<Rule>>,
     pub disable_rules: Option<Vec<String>>,
     pub ai_judge: Option<ProjectAiJudgeConfig>,
+    pub glob_expansion: Option<GlobExpansion>,
     #[serde(default)]
     pub defaults: Option<crate::config::profiles::Defaults>,
     #[serde(default)]
@@ -59,6 +67,7 @@ pub struct ProjectConfig {
 
 /// Merge a project config into a rules config (mutates in place).
 /// - override_safety_level replaces safety_level
+/// - glob_expansion replaces glob_expansion
 /// - allowlists are appended
 /// - disable_rules filters out matching rule IDs (applied before rules are appended)
 /// - rules are appended (not affected by disable_rules)
@@ -76,12 +85,17 @@ pub fn merge_overlay_config(config: &mut RulesConfig, overlay: ProjectConfig, so
         config.trust_level = level;
     }
 
+    if let Some(glob_expansion) = overlay.glob_expansion {
+        config.glob_expansion = glob_expansion;
+    }
+
     if let Some(allowlists) = overlay.allowlists {
         for mut entry in allowlists.commands {
             entry.source = source;
             config.allowlists.commands.push(entry);
         }
         config.allowlists.paths.extend(allowlists.paths);
+        config.allowlists.hosts.extend(allowlists.hosts);
     }
 
     if let Some(disable) = overlay.disable_rules {
@@ -157,6 +171,7 @@ disable_rules:
             default_decision: Decision::Ask,
             safety_level: SafetyLevel::High,
             trust_level: TrustLevel::default(),
+            glob_expansion: Default::default(),
             allowlists: Allowlists::default(),
             rules: vec![],
         };
@@ -167,6 +182,7 @@ disable_rules:
             rules: None,
             disable_rules: None,
             ai_judge: None,
+            glob_expansion: None,
             defaults: None,
             profiles: None,
         };
@@ -181,14 +197,17 @@ disable_rules:
             default_decision: Decision::Ask,
             safety_level: SafetyLevel::High,
             trust_level: TrustLevel::default(),
+            glob_expansion: Default::default(),
             allowlists: Allowlists {
                 commands: vec![AllowlistEntry {
                     command: "ls".to_string(),
                     trust: TrustLevel::Standard,
                     reason: None,
+                    examples: Default::default(),
                     source: RuleSource::default(),
                 }],
                 paths: vec![],
+                hosts: vec![],
             },
             rules: vec![],
         };
@@ -200,13 +219,16 @@ disable_rules:
                     command: "docker compose".to_string(),
                     trust: TrustLevel::Standard,
                     reason: None,
+                    examples: Default::default(),
                     source: RuleSource::default(),
                 }],
                 paths: vec![],
+                hosts: vec![],
             }),
             rules: None,
             disable_rules: None,
             ai_judge: None,
+            glob_expansion: None,
             defaults: None,
             profiles: None,
         };
@@ -227,6 +249,7 @@ disable_rules:
             default_decision: Decision::Ask,
             safety_level: SafetyLevel::High,
             trust_level: TrustLevel::default(),
+            glob_expansion: Default::default(),
             allowlists: Allowlists::default(),
             rules: vec![
                 Rule {
@@ -240,6 +263,9 @@ disable_rules:
                     },
                     decision: Decision::Deny,
                     reason: "test".to_string(),
+                    examples: Default::default(),
+                    when: None,
+                    applies: true,
                     source: RuleSource::default(),
                 },
                 Rule {
@@ -253,6 +279,9 @@ disable_rules:
                     },
                     decision: Decision::Ask,
                     reason: "test".to_string(),
+                    examples: Default::default(),
+                    when: None,
+                    applies: true,
                     source: RuleSource::default(),
                 },
             ],
@@ -264,6 +293,7 @@ disable_rules:
             rules: None,
             disable_rules: Some(vec!["rule-a".to_string()]),
             ai_judge: None,
+            glob_expansion: None,
             defaults: None,
             profiles: None,
         };
@@ -279,6 +309,7 @@ disable_rules:
             default_decision: Decision::Ask,
             safety_level: SafetyLevel::High,
             trust_level: TrustLevel::default(),
+            glob_expansion: Default::default(),
             allowlists: Allowlists::default(),
             rules: vec![],
         };
@@ -304,14 +335,17 @@ rules:
             default_decision: Decision::Ask,
             safety_level: SafetyLevel::High,
             trust_level: TrustLevel::default(),
+            glob_expansion: Default::default(),
             allowlists: Allowlists {
                 commands: vec![AllowlistEntry {
                     command: "ls".to_string(),
                     trust: TrustLevel::Standard,
                     reason: None,
+                    examples: Default::default(),
                     source: RuleSource::default(),
                 }],
                 paths: vec![],
+                hosts: vec![],
             },
             rules: vec![],
         };
@@ -322,6 +356,7 @@ rules:
             rules: None,
             disable_rules: None,
             ai_judge: None,
+            glob_expansion: None,
             defaults: None,
             profiles: None,
         };
@@ -395,6 +430,7 @@ rules:
             default_decision: Decision::Ask,
             safety_level: SafetyLevel::High,
             trust_level: TrustLevel::default(),
+            glob_expansion: Default::default(),
             allowlists: Allowlists::default(),
             rules: vec![],
         };
@@ -419,14 +455,17 @@ rules:
             default_decision: Decision::Ask,
             safety_level: SafetyLevel::High,
             trust_level: TrustLevel::default(),
+            glob_expansion: Default::default(),
             allowlists: Allowlists {
                 commands: vec![AllowlistEntry {
                     command: "ls".to_string(),
                     trust: TrustLevel::Standard,
                     reason: None,
+                    examples: Default::default(),
                     source: RuleSource::BuiltIn,
                 }],
                 paths: vec![],
+                hosts: vec![],
             },
             rules: vec![],
         };
@@ -438,13 +477,16 @@ rules:
                     command: "docker compose".to_string(),
                     trust: TrustLevel::Standard,
                     reason: None,
+                    examples: Default::default(),
                     source: RuleSource::default(),
                 }],
                 paths: vec![],
+                hosts: vec![],
             }),
             rules: None,
             disable_rules: None,
             ai_judge: None,
+            glob_expansion: None,
             defaults: None,
             profiles: None,
         };
@@ -460,6 +502,7 @@ rules:
             default_decision: Decision::Ask,
             safety_level: SafetyLevel::High,
             trust_level: TrustLevel::default(),
+            glob_expansion: Default::default(),
             allowlists: Allowlists::default(),
             rules: vec![],
         };
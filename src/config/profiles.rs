@@ -1,10 +1,11 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 use std::collections::{BTreeMap, HashSet};
 
 use crate::config::overlays::{Allowlists, ProjectAiJudgeConfig};
 use crate::config::rules::{Rule, SafetyLevel};
 
-#[derive(Debug, Default, Deserialize, Clone)]
+#[derive(Debug, Default, Deserialize, Clone, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Defaults {
     #[serde(default)]
@@ -23,7 +24,7 @@ impl Defaults {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct ProfileEntry {
     #[serde(default)]
@@ -179,6 +180,7 @@ pub fn apply_profile_overlay_full(
             config.allowlists.commands.push(ae);
         }
         config.allowlists.paths.extend(allow.paths.iter().cloned());
+        config.allowlists.hosts.extend(allow.hosts.iter().cloned());
     }
     if let Some(aj) = entry.ai_judge.as_ref() {
         if let Some(prompt) = aj.prompt.as_deref() {
@@ -668,6 +670,7 @@ ai_judge:
             default_decision: Decision::Ask,
             safety_level: SafetyLevel::High,
             trust_level: TrustLevel::default(),
+            glob_expansion: Default::default(),
             allowlists: Allowlists::default(),
             rules: vec![
                 Rule {
@@ -681,6 +684,9 @@ ai_judge:
                     },
                     decision: Decision::Deny,
                     reason: "builtin denies".into(),
+                    examples: Default::default(),
+                    when: None,
+                    applies: true,
                     source: RuleSource::BuiltIn,
                 },
                 Rule {
@@ -694,6 +700,9 @@ ai_judge:
                     },
                     decision: Decision::Ask,
                     reason: "global ask".into(),
+                    examples: Default::default(),
+                    when: None,
+                    applies: true,
                     source: RuleSource::Global,
                 },
             ],
@@ -746,6 +755,7 @@ rules:
             default_decision: Decision::Ask,
             safety_level: SafetyLevel::High,
             trust_level: TrustLevel::default(),
+            glob_expansion: Default::default(),
             allowlists: Allowlists::default(),
             rules: vec![],
         };
@@ -821,6 +831,9 @@ rules:
             },
             decision: Decision::Deny,
             reason: "test".to_string(),
+            examples: Default::default(),
+            when: None,
+            applies: true,
             source: RuleSource::BuiltIn,
         };
         let entry = ProfileEntry {
@@ -867,6 +880,9 @@ rules:
             },
             decision: Decision::Deny,
             reason: "test".to_string(),
+            examples: Default::default(),
+            when: None,
+            applies: true,
             source: RuleSource::BuiltIn,
         };
         let entry = ProfileEntry {
@@ -904,6 +920,9 @@ rules:
             },
             decision: Decision::Deny,
             reason: "test".to_string(),
+            examples: Default::default(),
+            when: None,
+            applies: true,
             source: RuleSource::BuiltIn,
         };
         let entry_a = ProfileEntry {
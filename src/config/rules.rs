@@ -1,12 +1,15 @@
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use super::diagnostics::from_yaml_str;
 use crate::config::overlays::{AllowlistEntry, Allowlists, RuleSource};
 use crate::domain::Decision;
 
 /// Rules manifest configuration that lists files to include.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct RulesManifestConfig {
     pub version: u32,
     #[serde(default = "default_decision")]
@@ -15,11 +18,13 @@ pub struct RulesManifestConfig {
     pub safety_level: SafetyLevel,
     #[serde(default)]
     pub trust_level: TrustLevel,
+    #[serde(default)]
+    pub glob_expansion: GlobExpansion,
     pub include: Vec<String>,
 }
 
 /// Partial rules config for individual files (no version/default_decision/safety_level).
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct PartialRulesConfig {
     #[serde(default)]
     pub allowlists: Allowlists,
@@ -27,6 +32,14 @@ pub struct PartialRulesConfig {
     pub rules: Vec<Rule>,
 }
 
+impl RulesConfig {
+    /// Whether `rule` takes part in decisions: its `when` holds here and
+    /// its level is within the safety level.
+    pub fn is_active(&self, rule: &Rule) -> bool {
+        rule.applies && rule.level <= self.safety_level
+    }
+}
+
 /// Information about a loaded rule file.
 #[derive(Debug, Clone)]
 pub struct LoadedFileInfo {
@@ -56,7 +69,7 @@ fn is_rules_manifest(content: &str) -> bool {
 }
 
 /// Top-level rules configuration loaded from YAML.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct RulesConfig {
     #[allow(dead_code)]
@@ -68,20 +81,66 @@ pub struct RulesConfig {
     #[serde(default)]
     pub trust_level: TrustLevel,
     #[serde(default)]
+    pub glob_expansion: GlobExpansion,
+    #[serde(default)]
     pub allowlists: Allowlists,
     #[serde(default)]
     pub rules: Vec<Rule>,
 }
 
+/// Filesystem-aware expansion of unquoted glob arguments (`rm -rf *`,
+/// `chmod -R 777 ./*`) for destructive commands. The glob is expanded
+/// against the directory the command runs in and every match is held to
+/// the rules and to the write `path` rules, so a glob that would sweep up
+/// `.git` or `.env` asks and names them. Off unless `enabled` is set.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GlobExpansion {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Command names whose glob arguments are expanded.
+    #[serde(default = "default_glob_commands")]
+    pub commands: Vec<String>,
+    /// Stop expanding a glob after this many matches.
+    #[serde(default = "default_glob_max_matches")]
+    pub max_matches: usize,
+}
+
+impl Default for GlobExpansion {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            commands: default_glob_commands(),
+            max_matches: default_glob_max_matches(),
+        }
+    }
+}
+
+fn default_glob_commands() -> Vec<String> {
+    ["rm", "mv", "chmod", "chown", "truncate"]
+        .map(String::from)
+        .to_vec()
+}
+
+fn default_glob_max_matches() -> usize {
+    200
+}
+
 pub(crate) fn default_decision() -> Decision {
     Decision::Ask
 }
 
+fn default_applies() -> bool {
+    true
+}
+
 pub(crate) fn default_safety_level() -> SafetyLevel {
     SafetyLevel::High
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, JsonSchema,
+)]
 #[serde(rename_all = "lowercase")]
 pub enum SafetyLevel {
     Critical,
@@ -99,7 +158,9 @@ impl std::fmt::Display for SafetyLevel {
     }
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, JsonSchema,
+)]
 #[serde(rename_all = "lowercase")]
 pub enum TrustLevel {
     Minimal,
@@ -118,7 +179,7 @@ impl std::fmt::Display for TrustLevel {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct Rule {
     pub id: String,
     pub level: SafetyLevel,
@@ -126,11 +187,66 @@ pub struct Rule {
     pub matcher: Matcher,
     pub decision: Decision,
     pub reason: String,
+    #[serde(default)]
+    pub examples: Examples,
+    /// Where the rule applies; a rule without `when` applies everywhere.
+    /// Checked once per configuration, when it is finalized for a
+    /// working directory and runtime.
+    #[serde(default)]
+    pub when: Option<RuleCondition>,
+    /// Whether `when` held where the configuration was finalized. A rule
+    /// that doesn't apply is kept, so `rules`, `lint` and `test` still see
+    /// it, but never fires.
+    #[serde(skip, default = "default_applies")]
+    pub applies: bool,
     #[serde(skip)]
     pub source: RuleSource,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// The conditions under which a rule applies. Every field that is set must
+/// hold; patterns within a field are alternatives.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RuleCondition {
+    /// Globs for the working directory. A relative pattern is taken from
+    /// the project root, `~/` from the home directory, and a pattern
+    /// matches the directory and everything beneath it.
+    #[serde(default)]
+    pub cwd: Option<StringOrList>,
+    /// Globs for the project root's directory name.
+    #[serde(default)]
+    pub project: Option<StringOrList>,
+    /// Globs for the checked-out branch, read from the project root's
+    /// `.git/HEAD`. Never holds on a detached HEAD or outside a repository.
+    #[serde(default)]
+    pub branch: Option<StringOrList>,
+    /// Paths, relative to the project root, that must all exist.
+    #[serde(default)]
+    pub markers: Vec<String>,
+    /// Runtimes the rule applies under (`claude`, `codex`).
+    #[serde(default)]
+    pub runtime: Option<StringOrList>,
+}
+
+/// Inputs a rule or allowlist entry must and must not match, checked by
+/// `longline test`. Shell commands, except for `path` rules (target paths)
+/// and `mcp` rules (`mcp__<server>__<tool>` names).
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Examples {
+    #[serde(default)]
+    pub matches: Vec<String>,
+    #[serde(default)]
+    pub not_matches: Vec<String>,
+}
+
+impl Examples {
+    pub fn is_empty(&self) -> bool {
+        self.matches.is_empty() && self.not_matches.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 #[serde(untagged, deny_unknown_fields)]
 // Matchers are constructed at rules-load time and live for the life of the
 // process; the size difference between the Command variant (with its
@@ -138,6 +254,18 @@ pub struct Rule {
 // Pipeline/Redirect variants is not a hot-path concern.
 #[allow(clippy::large_enum_variant)]
 pub enum Matcher {
+    /// Fires when every nested matcher fires.
+    All {
+        all: Vec<Matcher>,
+    },
+    /// Fires when at least one nested matcher fires.
+    Any {
+        any: Vec<Matcher>,
+    },
+    /// Fires when the nested matcher does not.
+    Not {
+        not: Box<Matcher>,
+    },
     Pipeline {
         pipeline: PipelineMatcher,
     },
@@ -147,6 +275,15 @@ pub enum Matcher {
     GitConfig {
         git_config: GitConfigMatcher,
     },
+    Path {
+        path: PathMatcher,
+    },
+    Mcp {
+        mcp: McpMatcher,
+    },
+    Url {
+        url: UrlMatcher,
+    },
     Command {
         command: StringOrList,
         #[serde(default)]
@@ -161,8 +298,87 @@ pub enum Matcher {
     },
 }
 
+/// What a matcher is evaluated against, which decides where `evaluate`
+/// consults it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatcherScope {
+    /// Each simple command (`command`, `redirect`, `git_config`, or a
+    /// combination mixing those with `url`).
+    Command,
+    /// Only `url` leaves: each simple command's URLs and WebFetch URLs.
+    Url,
+    /// A whole pipeline; command-level leaves fire when any stage does.
+    Pipeline,
+    /// File-tool paths.
+    Path,
+    /// MCP tool calls.
+    Mcp,
+}
+
+impl MatcherScope {
+    /// The scope of two matchers combined, or None when no single
+    /// evaluation sees both.
+    fn combine(self, other: MatcherScope) -> Option<MatcherScope> {
+        use MatcherScope::*;
+        match (self, other) {
+            (a, b) if a == b => Some(a),
+            (Command | Url, Command | Url) => Some(Command),
+            (Command | Url | Pipeline, Command | Url | Pipeline) => Some(Pipeline),
+            _ => None,
+        }
+    }
+}
+
+impl Matcher {
+    /// Whether this is an `all`, `any` or `not` node.
+    pub fn is_composite(&self) -> bool {
+        matches!(
+            self,
+            Matcher::All { .. } | Matcher::Any { .. } | Matcher::Not { .. }
+        )
+    }
+
+    /// Where this matcher is evaluated. None for a combination that never
+    /// fires: an empty `all`/`any`, or leaves of kinds no single evaluation
+    /// sees together (e.g. `path` with `command`).
+    pub fn scope(&self) -> Option<MatcherScope> {
+        match self {
+            Matcher::All { all: children } | Matcher::Any { any: children } => {
+                let mut scope: Option<MatcherScope> = None;
+                for child in children {
+                    let child = child.scope()?;
+                    scope = Some(match scope {
+                        None => child,
+                        Some(scope) => scope.combine(child)?,
+                    });
+                }
+                scope
+            }
+            Matcher::Not { not } => not.scope(),
+            Matcher::Pipeline { .. } => Some(MatcherScope::Pipeline),
+            Matcher::Path { .. } => Some(MatcherScope::Path),
+            Matcher::Mcp { .. } => Some(MatcherScope::Mcp),
+            Matcher::Url { .. } => Some(MatcherScope::Url),
+            Matcher::Redirect { .. } | Matcher::GitConfig { .. } | Matcher::Command { .. } => {
+                Some(MatcherScope::Command)
+            }
+        }
+    }
+
+    /// The non-composite matchers nested in this one, in document order.
+    pub fn leaves(&self) -> Vec<&Matcher> {
+        match self {
+            Matcher::All { all: children } | Matcher::Any { any: children } => {
+                children.iter().flat_map(Matcher::leaves).collect()
+            }
+            Matcher::Not { not } => not.leaves(),
+            leaf => vec![leaf],
+        }
+    }
+}
+
 /// Structural matcher for Git's command-line config override records.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct GitConfigMatcher {
     pub command: StringOrList,
@@ -174,7 +390,7 @@ pub struct GitConfigMatcher {
 }
 
 /// Canonical source of Git config overrides understood by policy matching.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub enum GitConfigSource {
     CliC,
@@ -188,19 +404,19 @@ impl std::fmt::Display for GitConfigSource {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct PipelineMatcher {
     pub stages: Vec<StageMatcher>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct StageMatcher {
     pub command: StringOrList,
     #[serde(default)]
     pub flags: Option<FlagsMatcher>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct RedirectMatcher {
     #[serde(default)]
     pub op: Option<StringOrList>,
@@ -208,7 +424,141 @@ pub struct RedirectMatcher {
     pub target: Option<StringOrList>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// Matcher for file paths handed to the agent's own file tools (Claude's
+/// Read/Grep/Glob/Write/Edit family) rather than to a shell command.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PathMatcher {
+    /// Glob patterns matched against the tool's target path (and its
+    /// `~/`-contracted form when it lives under `$HOME`).
+    pub target: StringOrList,
+    /// Tool kinds the rule applies to. Empty means every kind.
+    #[serde(default)]
+    pub tools: Vec<PathTool>,
+}
+
+/// Matcher for MCP tool calls (`mcp__<server>__<tool>`) made by the agent.
+/// Every field that is set must match.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct McpMatcher {
+    /// Glob patterns for the MCP server name.
+    #[serde(default)]
+    pub server: Option<StringOrList>,
+    /// Glob patterns for the tool name within the server.
+    #[serde(default)]
+    pub tool: Option<StringOrList>,
+    /// Conditions on the call's `tool_input`; all must hold.
+    #[serde(default)]
+    pub input: Vec<McpInputCondition>,
+}
+
+/// Matcher for URLs fetched by the agent: Claude's WebFetch `url` and the
+/// URL arguments of `curl`, `wget` and HTTPie. Every field that is set must
+/// match; a matcher with no fields matches every URL.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UrlMatcher {
+    /// Glob patterns for the lowercased host (`docs.rs`, `*.github.com`).
+    #[serde(default)]
+    pub host: Option<StringOrList>,
+    /// Schemes (`https`, `http`), compared case-insensitively.
+    #[serde(default)]
+    pub scheme: Option<StringOrList>,
+    /// Ports, explicit or the scheme's default (80, 443).
+    #[serde(default)]
+    pub port: Option<PortList>,
+    /// Glob patterns for the path (`/api/**`).
+    #[serde(default)]
+    pub path: Option<StringOrList>,
+    /// Glob patterns for the query string, without the leading `?`.
+    #[serde(default)]
+    pub query: Option<StringOrList>,
+}
+
+/// One port or `any_of` a list of ports.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum PortList {
+    Single(u16),
+    List { any_of: Vec<u16> },
+}
+
+impl PortList {
+    pub fn contains(&self, port: u16) -> bool {
+        match self {
+            PortList::Single(p) => *p == port,
+            PortList::List { any_of } => any_of.contains(&port),
+        }
+    }
+}
+
+/// A condition on the value a JSON pointer selects in an MCP call's
+/// `tool_input`. Every operator that is set must hold; with none set, the
+/// pointer just has to resolve.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct McpInputCondition {
+    pub pointer: JsonPointer,
+    /// Require the pointer to resolve (`true`) or not (`false`).
+    #[serde(default)]
+    pub exists: Option<bool>,
+    /// Exact JSON value the pointer must select.
+    #[serde(default)]
+    pub equals: Option<serde_json::Value>,
+    /// Glob patterns for a string, number or boolean value.
+    #[serde(default)]
+    pub matches: Option<StringOrList>,
+    /// Case-insensitive substrings, any of which a string value must contain.
+    #[serde(default)]
+    pub contains: Option<StringOrList>,
+}
+
+/// An RFC 6901 JSON pointer (`""` or `/a/0/b`), validated at load time.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(try_from = "String")]
+pub struct JsonPointer(String);
+
+impl JsonPointer {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for JsonPointer {
+    type Error = String;
+
+    fn try_from(pointer: String) -> Result<Self, Self::Error> {
+        if pointer.is_empty() || pointer.starts_with('/') {
+            Ok(Self(pointer))
+        } else {
+            Err(format!(
+                "invalid JSON pointer '{pointer}': must be empty or start with '/'"
+            ))
+        }
+    }
+}
+
+/// Kind of file access a path rule can be scoped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PathTool {
+    Read,
+    Search,
+    Write,
+}
+
+impl std::fmt::Display for PathTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            PathTool::Read => "read",
+            PathTool::Search => "search",
+            PathTool::Write => "write",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct FlagsMatcher {
     #[serde(default)]
     pub any_of: Vec<String>,
@@ -222,7 +572,7 @@ pub struct FlagsMatcher {
     pub starts_with: Vec<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct EnvMatcher {
     /// Match if any env-var assignment's name matches one of these glob
@@ -243,7 +593,7 @@ pub struct EnvMatcher {
     pub except: Vec<EnvException>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct EnvException {
     /// Env-var name patterns eligible for this exception.
@@ -257,7 +607,7 @@ pub struct EnvException {
 
 /// Serde-facing safe-value classes supported by environment exceptions.
 /// Policy matching maps these exhaustively to its private predicates.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub enum EnvValueClass {
     ShellNoop,
@@ -271,7 +621,7 @@ impl std::fmt::Display for EnvValueClass {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct ArgsMatcher {
     #[serde(default)]
@@ -338,9 +688,106 @@ pub struct ArgsMatcher {
     /// `git -C <path> config <key>`, which raw `argv.len()` would over-count.
     #[serde(default)]
     pub min_args: Option<usize>,
+    /// Regex counterpart of `any_of`: at least one expression must match
+    /// some argument. Unanchored, so use `^…$` to match whole arguments.
+    #[serde(default)]
+    pub regex: Vec<ArgRegex>,
+    /// Patterns for arguments by position, counted from the effective
+    /// subcommand (0 is the subcommand itself; leading global value-flag
+    /// pairs are stripped as for `subcommand`). Every listed position must
+    /// exist and match.
+    #[serde(default)]
+    pub positional: BTreeMap<usize, ValuePattern>,
+    /// Option values by flag name, in both the `--flag value` and
+    /// `--flag=value` forms. Fires when any listed option carries a
+    /// matching value, so aliases (`-n`, `--namespace`) can sit side by
+    /// side; combine matchers with `all` to require several options. With
+    /// a `subcommand` pin only the argv from the subcommand onward is
+    /// scanned, so a global option (`git -c`) cannot stand in for the
+    /// subcommand's own.
+    #[serde(default)]
+    pub option_values: BTreeMap<String, ValuePattern>,
+}
+
+/// An argument pattern: a glob, or `{ regex: … }` for a regular expression.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ValuePattern {
+    Glob(String),
+    Regex(RegexPattern),
 }
 
+impl ValuePattern {
+    /// Whether `value` matches, lowercasing both sides of a glob (and
+    /// matching a regex case-insensitively) when `case_insensitive`.
+    pub fn matches(&self, value: &str, case_insensitive: bool) -> bool {
+        match self {
+            ValuePattern::Glob(pattern) if case_insensitive => {
+                glob_match::glob_match(&pattern.to_lowercase(), &value.to_lowercase())
+            }
+            ValuePattern::Glob(pattern) => glob_match::glob_match(pattern, value),
+            ValuePattern::Regex(pattern) => pattern.regex.is_match(value, case_insensitive),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RegexPattern {
+    pub regex: ArgRegex,
+}
+
+/// A regular expression, compiled when the rules load so a bad pattern is
+/// a load error rather than a rule that never fires.
 #[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct ArgRegex {
+    exact: regex::Regex,
+    folded: regex::Regex,
+}
+
+impl ArgRegex {
+    pub fn as_str(&self) -> &str {
+        self.exact.as_str()
+    }
+
+    pub fn is_match(&self, text: &str, case_insensitive: bool) -> bool {
+        if case_insensitive {
+            self.folded.is_match(text)
+        } else {
+            self.exact.is_match(text)
+        }
+    }
+}
+
+impl TryFrom<String> for ArgRegex {
+    type Error = String;
+
+    fn try_from(pattern: String) -> Result<Self, Self::Error> {
+        let build = |case_insensitive: bool| {
+            regex::RegexBuilder::new(&pattern)
+                .case_insensitive(case_insensitive)
+                .build()
+                .map_err(|e| format!("invalid regex '{pattern}': {e}"))
+        };
+        Ok(Self {
+            exact: build(false)?,
+            folded: build(true)?,
+        })
+    }
+}
+
+impl JsonSchema for ArgRegex {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Regex".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({ "type": "string", "format": "regex" })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum StringOrList {
     Single(String),
@@ -354,6 +801,14 @@ impl StringOrList {
             StringOrList::List { any_of } => any_of.iter().any(|s| s == value),
         }
     }
+
+    /// The patterns as a slice, whichever form was written.
+    pub fn patterns(&self) -> &[String] {
+        match self {
+            StringOrList::Single(s) => std::slice::from_ref(s),
+            StringOrList::List { any_of } => any_of,
+        }
+    }
 }
 
 /// Load rules from a YAML file (manifest or monolithic).
@@ -364,7 +819,7 @@ pub fn load_rules(path: &Path) -> Result<RulesConfig, String> {
     if is_rules_manifest(&content) {
         load_rules_manifest(path, &content)
     } else {
-        let config: RulesConfig = serde_norway::from_str(&content)
+        let config: RulesConfig = from_yaml_str(&content)
             .map_err(|e| format!("Failed to parse rules file {}: {e}", path.display()))?;
         Ok(config)
     }
@@ -372,12 +827,14 @@ pub fn load_rules(path: &Path) -> Result<RulesConfig, String> {
 
 /// Load a rules manifest file and merge all included files.
 fn load_rules_manifest(manifest_path: &Path, content: &str) -> Result<RulesConfig, String> {
-    let manifest: RulesManifestConfig = serde_norway::from_str(content)
+    let manifest: RulesManifestConfig = from_yaml_str(content)
         .map_err(|e| format!("Failed to parse manifest {}: {e}", manifest_path.display()))?;
 
     let manifest_dir = manifest_path.parent().unwrap_or(Path::new("."));
 
     let mut merged_allowlists: Vec<AllowlistEntry> = Vec::new();
+    let mut merged_paths: Vec<String> = Vec::new();
+    let mut merged_hosts: Vec<String> = Vec::new();
     let mut merged_rules: Vec<Rule> = Vec::new();
 
     for file_name in &manifest.include {
@@ -385,10 +842,12 @@ fn load_rules_manifest(manifest_path: &Path, content: &str) -> Result<RulesConfi
         let file_content = fs::read_to_string(&file_path)
             .map_err(|e| format!("Failed to read included file {}: {e}", file_path.display()))?;
 
-        let partial: PartialRulesConfig = serde_norway::from_str(&file_content)
+        let partial: PartialRulesConfig = from_yaml_str(&file_content)
             .map_err(|e| format!("Failed to parse included file {}: {e}", file_path.display()))?;
 
         merged_allowlists.extend(partial.allowlists.commands);
+        merged_paths.extend(partial.allowlists.paths);
+        merged_hosts.extend(partial.allowlists.hosts);
         merged_rules.extend(partial.rules);
     }
 
@@ -397,9 +856,11 @@ fn load_rules_manifest(manifest_path: &Path, content: &str) -> Result<RulesConfi
         default_decision: manifest.default_decision,
         safety_level: manifest.safety_level,
         trust_level: manifest.trust_level,
+        glob_expansion: manifest.glob_expansion,
         allowlists: Allowlists {
             commands: merged_allowlists,
-            paths: Vec::new(),
+            paths: merged_paths,
+            hosts: merged_hosts,
         },
         rules: merged_rules,
     })
@@ -410,20 +871,24 @@ pub fn load_embedded_rules() -> Result<RulesConfig, String> {
     let content = crate::embedded_rules::get("rules.yaml")
         .ok_or_else(|| "Embedded rules.yaml not found".to_string())?;
 
-    let manifest: RulesManifestConfig = serde_norway::from_str(content)
-        .map_err(|e| format!("Failed to parse embedded rules.yaml: {e}"))?;
+    let manifest: RulesManifestConfig =
+        from_yaml_str(content).map_err(|e| format!("Failed to parse embedded rules.yaml: {e}"))?;
 
     let mut merged_allowlists: Vec<AllowlistEntry> = Vec::new();
+    let mut merged_paths: Vec<String> = Vec::new();
+    let mut merged_hosts: Vec<String> = Vec::new();
     let mut merged_rules: Vec<Rule> = Vec::new();
 
     for file_name in &manifest.include {
         let file_content = crate::embedded_rules::get(file_name)
             .ok_or_else(|| format!("Embedded file '{}' not found", file_name))?;
 
-        let partial: PartialRulesConfig = serde_norway::from_str(file_content)
+        let partial: PartialRulesConfig = from_yaml_str(file_content)
             .map_err(|e| format!("Failed to parse embedded file {}: {e}", file_name))?;
 
         merged_allowlists.extend(partial.allowlists.commands);
+        merged_paths.extend(partial.allowlists.paths);
+        merged_hosts.extend(partial.allowlists.hosts);
         merged_rules.extend(partial.rules);
     }
 
@@ -432,9 +897,11 @@ pub fn load_embedded_rules() -> Result<RulesConfig, String> {
         default_decision: manifest.default_decision,
         safety_level: manifest.safety_level,
         trust_level: manifest.trust_level,
+        glob_expansion: manifest.glob_expansion,
         allowlists: Allowlists {
             commands: merged_allowlists,
-            paths: Vec::new(),
+            paths: merged_paths,
+            hosts: merged_hosts,
         },
         rules: merged_rules,
     })
@@ -445,10 +912,12 @@ pub fn load_embedded_rules_with_info() -> Result<LoadedConfig, String> {
     let content = crate::embedded_rules::get("rules.yaml")
         .ok_or_else(|| "Embedded rules.yaml not found".to_string())?;
 
-    let manifest: RulesManifestConfig = serde_norway::from_str(content)
-        .map_err(|e| format!("Failed to parse embedded rules.yaml: {e}"))?;
+    let manifest: RulesManifestConfig =
+        from_yaml_str(content).map_err(|e| format!("Failed to parse embedded rules.yaml: {e}"))?;
 
     let mut merged_allowlists: Vec<AllowlistEntry> = Vec::new();
+    let mut merged_paths: Vec<String> = Vec::new();
+    let mut merged_hosts: Vec<String> = Vec::new();
     let mut merged_rules: Vec<Rule> = Vec::new();
     let mut files: Vec<LoadedFileInfo> = Vec::new();
 
@@ -456,7 +925,7 @@ pub fn load_embedded_rules_with_info() -> Result<LoadedConfig, String> {
         let file_content = crate::embedded_rules::get(file_name)
             .ok_or_else(|| format!("Embedded file '{}' not found", file_name))?;
 
-        let partial: PartialRulesConfig = serde_norway::from_str(file_content)
+        let partial: PartialRulesConfig = from_yaml_str(file_content)
             .map_err(|e| format!("Failed to parse embedded file {}: {e}", file_name))?;
 
         let trust_counts = compute_trust_counts(&partial.allowlists.commands);
@@ -468,6 +937,8 @@ pub fn load_embedded_rules_with_info() -> Result<LoadedConfig, String> {
         });
 
         merged_allowlists.extend(partial.allowlists.commands);
+        merged_paths.extend(partial.allowlists.paths);
+        merged_hosts.extend(partial.allowlists.hosts);
         merged_rules.extend(partial.rules);
     }
 
@@ -477,9 +948,11 @@ pub fn load_embedded_rules_with_info() -> Result<LoadedConfig, String> {
             default_decision: manifest.default_decision,
             safety_level: manifest.safety_level,
             trust_level: manifest.trust_level,
+            glob_expansion: manifest.glob_expansion,
             allowlists: Allowlists {
                 commands: merged_allowlists,
-                paths: Vec::new(),
+                paths: merged_paths,
+                hosts: merged_hosts,
             },
             rules: merged_rules,
         },
@@ -515,7 +988,7 @@ pub fn load_rules_with_info(path: &Path) -> Result<LoadedConfig, String> {
     if is_rules_manifest(&content) {
         load_rules_manifest_with_info(path, &content)
     } else {
-        let config: RulesConfig = serde_norway::from_str(&content)
+        let config: RulesConfig = from_yaml_str(&content)
             .map_err(|e| format!("Failed to parse rules file {}: {e}", path.display()))?;
         let trust_counts = compute_trust_counts(&config.allowlists.commands);
         Ok(LoadedConfig {
@@ -540,12 +1013,14 @@ fn load_rules_manifest_with_info(
     manifest_path: &Path,
     content: &str,
 ) -> Result<LoadedConfig, String> {
-    let manifest: RulesManifestConfig = serde_norway::from_str(content)
+    let manifest: RulesManifestConfig = from_yaml_str(content)
         .map_err(|e| format!("Failed to parse manifest {}: {e}", manifest_path.display()))?;
 
     let manifest_dir = manifest_path.parent().unwrap_or(Path::new("."));
 
     let mut merged_allowlists: Vec<AllowlistEntry> = Vec::new();
+    let mut merged_paths: Vec<String> = Vec::new();
+    let mut merged_hosts: Vec<String> = Vec::new();
     let mut merged_rules: Vec<Rule> = Vec::new();
     let mut files: Vec<LoadedFileInfo> = Vec::new();
 
@@ -554,7 +1029,7 @@ fn load_rules_manifest_with_info(
         let file_content = fs::read_to_string(&file_path)
             .map_err(|e| format!("Failed to read included file {}: {e}", file_path.display()))?;
 
-        let partial: PartialRulesConfig = serde_norway::from_str(&file_content)
+        let partial: PartialRulesConfig = from_yaml_str(&file_content)
             .map_err(|e| format!("Failed to parse included file {}: {e}", file_path.display()))?;
 
         let trust_counts = compute_trust_counts(&partial.allowlists.commands);
@@ -566,6 +1041,8 @@ fn load_rules_manifest_with_info(
         });
 
         merged_allowlists.extend(partial.allowlists.commands);
+        merged_paths.extend(partial.allowlists.paths);
+        merged_hosts.extend(partial.allowlists.hosts);
         merged_rules.extend(partial.rules);
     }
 
@@ -575,9 +1052,11 @@ fn load_rules_manifest_with_info(
             default_decision: manifest.default_decision,
             safety_level: manifest.safety_level,
             trust_level: manifest.trust_level,
+            glob_expansion: manifest.glob_expansion,
             allowlists: Allowlists {
                 commands: merged_allowlists,
-                paths: Vec::new(),
+                paths: merged_paths,
+                hosts: merged_hosts,
             },
             rules: merged_rules,
         },
@@ -690,6 +1169,200 @@ rules:
         assert_eq!(config.rules[0].id, "write-to-dev");
     }
 
+    #[test]
+    fn test_path_matcher_deserialization() {
+        let yaml = r#"
+version: 1
+rules:
+  - id: path-pem
+    level: high
+    match:
+      path:
+        target:
+          any_of: ["**/*.pem", "secrets/**"]
+        tools: [read, search]
+    decision: ask
+    reason: "Accessing key material"
+"#;
+        let config: RulesConfig = serde_norway::from_str(yaml).unwrap();
+        match &config.rules[0].matcher {
+            Matcher::Path { path } => {
+                assert_eq!(path.tools, vec![PathTool::Read, PathTool::Search]);
+                assert!(matches!(&path.target, StringOrList::List { any_of } if any_of.len() == 2));
+            }
+            other => panic!("expected path matcher, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_path_matcher_rejects_unknown_tool() {
+        let yaml = r#"
+version: 1
+rules:
+  - id: path-pem
+    level: high
+    match:
+      path:
+        target: "**/*.pem"
+        tools: [execute]
+    decision: ask
+    reason: "Accessing key material"
+"#;
+        assert!(serde_norway::from_str::<RulesConfig>(yaml).is_err());
+    }
+
+    #[test]
+    fn test_mcp_matcher_deserialization() {
+        let yaml = r#"
+version: 1
+rules:
+  - id: mcp-db-drop
+    level: high
+    match:
+      mcp:
+        server: "postgres*"
+        tool:
+          any_of: [query, execute]
+        input:
+          - pointer: /sql
+            contains: { any_of: ["drop table", "truncate"] }
+          - pointer: /readonly
+            equals: false
+          - pointer: /dry_run
+            exists: false
+    decision: ask
+    reason: "Destructive SQL via MCP"
+"#;
+        let config: RulesConfig = serde_norway::from_str(yaml).unwrap();
+        match &config.rules[0].matcher {
+            Matcher::Mcp { mcp } => {
+                assert!(matches!(&mcp.server, Some(StringOrList::Single(s)) if s == "postgres*"));
+                assert_eq!(mcp.input.len(), 3);
+                assert_eq!(mcp.input[0].pointer.as_str(), "/sql");
+                assert_eq!(mcp.input[1].equals, Some(serde_json::Value::Bool(false)));
+                assert_eq!(mcp.input[2].exists, Some(false));
+            }
+            other => panic!("expected mcp matcher, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_mcp_matcher_rejects_invalid_pointer() {
+        let yaml = r#"
+version: 1
+rules:
+  - id: mcp-bad
+    level: high
+    match:
+      mcp:
+        input:
+          - pointer: sql
+    decision: ask
+    reason: "x"
+"#;
+        assert!(serde_norway::from_str::<RulesConfig>(yaml).is_err());
+    }
+
+    #[test]
+    fn test_url_matcher_deserialization() {
+        let yaml = r#"
+version: 1
+allowlists:
+  hosts: ["docs.rs"]
+rules:
+  - id: url-internal
+    level: high
+    match:
+      url:
+        scheme: https
+        host: { any_of: ["*.internal.test"] }
+        port: 8443
+        path: "/api/**"
+        query: "*token=*"
+    decision: ask
+    reason: "Internal API"
+"#;
+        let config: RulesConfig = serde_norway::from_str(yaml).unwrap();
+        assert_eq!(config.allowlists.hosts, vec!["docs.rs".to_string()]);
+        match &config.rules[0].matcher {
+            Matcher::Url { url } => {
+                assert!(url.port.as_ref().unwrap().contains(8443));
+                assert_eq!(url.host.as_ref().unwrap().patterns(), ["*.internal.test"]);
+            }
+            other => panic!("expected url matcher, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_url_matcher_rejects_unknown_field() {
+        let yaml = r#"
+version: 1
+rules:
+  - id: url-bad
+    level: high
+    match:
+      url:
+        domain: example.com
+    decision: ask
+    reason: "x"
+"#;
+        assert!(serde_norway::from_str::<RulesConfig>(yaml).is_err());
+    }
+
+    #[test]
+    fn test_composite_matcher_deserialization_and_scope() {
+        let matcher: Matcher = serde_norway::from_str(
+            r#"
+all:
+  - command: kubectl
+  - not:
+      any:
+        - redirect: { op: ">" }
+        - url: { host: example.com }
+"#,
+        )
+        .unwrap();
+        let Matcher::All { all } = &matcher else {
+            panic!("expected all matcher, got {matcher:?}");
+        };
+        assert!(matches!(all[1], Matcher::Not { .. }));
+        assert_eq!(matcher.scope(), Some(MatcherScope::Command));
+        assert_eq!(matcher.leaves().len(), 3);
+
+        let scope = |yaml: &str| serde_norway::from_str::<Matcher>(yaml).unwrap().scope();
+        assert_eq!(
+            scope("any: [{ url: { host: a } }]"),
+            Some(MatcherScope::Url)
+        );
+        assert_eq!(
+            scope("all: [{ pipeline: { stages: [{ command: ps }] } }, { command: grep }]"),
+            Some(MatcherScope::Pipeline)
+        );
+        assert_eq!(
+            scope("not: { path: { target: x } }"),
+            Some(MatcherScope::Path)
+        );
+        assert_eq!(
+            scope("all: [{ path: { target: x } }, { command: cat }]"),
+            None
+        );
+        assert_eq!(scope("any: []"), None);
+    }
+
+    #[test]
+    fn test_composite_matcher_rejects_unknown_field() {
+        for invalid in [
+            "all: [{ command: a }]\nnot: { command: b }\n",
+            "any: [{ command: a, bogus: 1 }]\n",
+            "not: [{ command: a }]\n",
+        ] {
+            assert!(
+                serde_norway::from_str::<Matcher>(invalid).is_err(),
+                "{invalid}"
+            );
+        }
+    }
+
     #[test]
     fn test_load_default_rules_file() {
         let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -797,6 +1470,8 @@ rules: []
 allowlists:
   commands:
     - { command: "git status", trust: standard }
+  paths:
+    - "secrets/**"
 rules:
   - id: git-force-push
     level: high
@@ -812,6 +1487,7 @@ rules:
 
         let config = load_rules(&manifest_path).unwrap();
         assert_eq!(config.allowlists.commands.len(), 3);
+        assert_eq!(config.allowlists.paths, vec!["secrets/**".to_string()]);
         assert_eq!(config.rules.len(), 1);
         assert_eq!(config.rules[0].id, "git-force-push");
     }
@@ -0,0 +1,74 @@
+//! JSON Schema for longline's YAML files, generated from the same types
+//! they are deserialized into, for `longline schema` and editor validation.
+
+use schemars::JsonSchema;
+
+use crate::ai_judge::AiJudgeConfig;
+use crate::config::overlays::ProjectConfig;
+use crate::config::rules::{PartialRulesConfig, RulesConfig, RulesManifestConfig};
+
+/// Which file a schema describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaKind {
+    /// `rules.yaml`: a manifest, a single rules file, or a file it includes.
+    Rules,
+    /// The global `longline.yaml` and a project's `.claude/longline.yaml`.
+    Overlay,
+    /// `ai-judge.yaml`.
+    AiJudge,
+}
+
+/// Any file the rules loader accepts.
+#[derive(JsonSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+enum RulesFile {
+    Manifest(RulesManifestConfig),
+    Rules(RulesConfig),
+    Included(PartialRulesConfig),
+}
+
+pub fn json_schema(kind: SchemaKind) -> serde_json::Value {
+    let (schema, title) = match kind {
+        SchemaKind::Rules => (schemars::schema_for!(RulesFile), "longline rules file"),
+        SchemaKind::Overlay => (schemars::schema_for!(ProjectConfig), "longline overlay"),
+        SchemaKind::AiJudge => (
+            schemars::schema_for!(AiJudgeConfig),
+            "longline AI judge config",
+        ),
+    };
+    let mut value = schema.to_value();
+    value["title"] = title.into();
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlay_schema_rejects_unknown_keys_and_lists_matchers() {
+        let schema = json_schema(SchemaKind::Overlay);
+        assert_eq!(schema["additionalProperties"], false);
+        assert!(schema["properties"]["disable_rules"].is_object());
+        let matcher = &schema["$defs"]["Matcher"]["anyOf"];
+        assert_eq!(matcher.as_array().unwrap().len(), 10);
+    }
+
+    #[test]
+    fn test_rules_schema_accepts_every_file_shape() {
+        let schema = json_schema(SchemaKind::Rules);
+        assert_eq!(schema["anyOf"].as_array().unwrap().len(), 3);
+        assert_eq!(schema["title"], "longline rules file");
+    }
+
+    #[test]
+    fn test_ai_judge_schema_uses_yaml_key_names() {
+        let schema = json_schema(SchemaKind::AiJudge);
+        let properties = schema["properties"].as_object().unwrap();
+        assert!(properties.contains_key("timeout"));
+        assert!(properties.contains_key("triggers"));
+        assert!(!properties.contains_key("timeout_raw"));
+        assert!(!properties.contains_key("total_budget_secs_raw"));
+    }
+}
@@ -0,0 +1,485 @@
+//! `longline doctor`: check the things that otherwise fail silently — rule
+//! and overlay configs, the runtime hook wiring, the audit log directories
+//! and the AI judge providers — and say how to fix each problem.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use longline::ai_judge;
+use longline::config::{self, RulesConfig};
+
+use crate::install::{self, Runtime, Scope};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// One health check and, when it did not pass, how to fix it.
+#[derive(Debug, Serialize)]
+pub(crate) struct Check {
+    pub section: &'static str,
+    pub status: Status,
+    pub name: String,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn pass(section: &'static str, name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Check {
+            section,
+            status: Status::Pass,
+            name: name.into(),
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warn(
+        section: &'static str,
+        name: impl Into<String>,
+        detail: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Check {
+            status: Status::Warn,
+            fix: Some(fix.into()),
+            ..Check::pass(section, name, detail)
+        }
+    }
+
+    fn fail(
+        section: &'static str,
+        name: impl Into<String>,
+        detail: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Check {
+            status: Status::Fail,
+            ..Check::warn(section, name, detail, fix)
+        }
+    }
+}
+
+/// Everything the checks read.
+pub(crate) struct DoctorContext<'a> {
+    pub home: &'a Path,
+    /// The directory project overlays and project hooks are looked up from.
+    pub project_dir: Option<&'a Path>,
+    /// The rules manifest as the hooks would load it, and where from.
+    pub rules: Result<RulesConfig, String>,
+    pub rules_source: String,
+}
+
+/// Run every check, in report order.
+pub(crate) fn run_checks(ctx: DoctorContext<'_>) -> Vec<Check> {
+    let mut checks = Vec::new();
+    check_configs(&ctx, &mut checks);
+    let project = ctx
+        .project_dir
+        .map(|dir| config::find_project_root(dir).unwrap_or_else(|| dir.to_path_buf()));
+    for runtime in [Runtime::Claude, Runtime::Codex] {
+        checks.push(check_hooks(runtime, ctx.home, project.as_deref()));
+    }
+    for (name, dir) in [
+        ("claude", crate::runtime::claude::audit_log_dir(ctx.home)),
+        ("codex", crate::runtime::codex::audit_log_dir(ctx.home)),
+    ] {
+        checks.push(check_log_dir(name, &dir));
+    }
+    check_ai_judge(&ai_judge::default_config_path(), &mut checks);
+    checks
+}
+
+const CONFIG: &str = "config";
+const HOOKS: &str = "hooks";
+const LOGS: &str = "logs";
+const AI_JUDGE: &str = "ai judge";
+
+/// Parse errors in any of these make every hook call exit 2, which blocks
+/// every Bash call until the file is fixed.
+fn check_configs(ctx: &DoctorContext<'_>, checks: &mut Vec<Check>) {
+    let rules = match &ctx.rules {
+        Ok(rules) => {
+            checks.push(Check::pass(CONFIG, "rules", &ctx.rules_source));
+            Some(rules)
+        }
+        Err(e) => {
+            checks.push(Check::fail(
+                CONFIG,
+                "rules",
+                e,
+                format!(
+                    "fix {} (unknown keys are rejected), or run `longline init --force` to restore the defaults",
+                    ctx.rules_source
+                ),
+            ));
+            None
+        }
+    };
+
+    let global_path = config::global_config_path(ctx.home);
+    let global = config::load_global_config(ctx.home);
+    checks.push(overlay_check("global overlay", &global_path, &global));
+    let project = ctx.project_dir.map(|dir| {
+        let path = config::find_project_root(dir)
+            .map(|root| config::project_config_path(&root))
+            .unwrap_or_else(|| config::project_config_path(dir));
+        let loaded = config::load_project_config(dir);
+        checks.push(overlay_check("project overlay", &path, &loaded));
+        loaded
+    });
+
+    // finalize_config reloads both overlays; only run it when they parsed
+    // so a typo is reported once, against the file that has it.
+    let (Some(rules), Ok(global)) = (rules, global) else {
+        return;
+    };
+    let project = match project {
+        Some(Ok(project)) => project,
+        Some(Err(_)) => return,
+        None => None,
+    };
+
+    let finalize = |runtime: &str, profile: Option<&str>| {
+        config::finalize_config(
+            rules.clone(),
+            ctx.home,
+            ctx.project_dir,
+            None,
+            None,
+            runtime,
+            profile,
+        )
+    };
+    let fix = "fix the profile or defaults entry named in the error";
+    for runtime in ["claude", "codex"] {
+        let name = format!("{runtime} runtime");
+        checks.push(match finalize(runtime, None) {
+            Ok(fc) => Check::pass(CONFIG, name, format!("profile `{}`", fc.resolved_profile)),
+            Err(e) => Check::fail(CONFIG, name, e, fix),
+        });
+    }
+
+    let mut profiles: Vec<String> = [global, project]
+        .into_iter()
+        .flatten()
+        .flat_map(|overlay| overlay.profiles.unwrap_or_default().into_keys())
+        .collect();
+    profiles.sort();
+    profiles.dedup();
+    for profile in profiles {
+        if let Err(e) = finalize("claude", Some(&profile)) {
+            checks.push(Check::fail(CONFIG, format!("profile `{profile}`"), e, fix));
+        }
+    }
+}
+
+fn overlay_check<T>(name: &str, path: &Path, loaded: &Result<Option<T>, String>) -> Check {
+    match loaded {
+        Ok(Some(_)) => Check::pass(CONFIG, name, path.display().to_string()),
+        Ok(None) => Check::pass(CONFIG, name, format!("{} (not present)", path.display())),
+        Err(e) => Check::fail(
+            CONFIG,
+            name,
+            e,
+            format!(
+                "fix the key or value named in the error in {}; until then every hook call exits 2",
+                path.display()
+            ),
+        ),
+    }
+}
+
+fn check_hooks(runtime: Runtime, home: &Path, project: Option<&Path>) -> Check {
+    let name = match runtime {
+        Runtime::Claude => "claude",
+        Runtime::Codex => "codex",
+    };
+    let install_fix = format!("run `longline install --runtime {name}`");
+
+    let mut files = install::hook_files(runtime, Scope::User, home, home);
+    if let Some(project) = project {
+        files.extend(install::hook_files(runtime, Scope::Project, home, project));
+    }
+    let mut wiring = install::Wiring::new();
+    let mut read_from = Vec::new();
+    for file in &files {
+        match install::read_wiring(file) {
+            Ok(w) if w.is_empty() => {}
+            Ok(w) => {
+                read_from.push(file.display().to_string());
+                for (event, tools) in w {
+                    wiring.entry(event).or_default().extend(tools);
+                }
+            }
+            Err(e) => {
+                return Check::fail(
+                    HOOKS,
+                    name,
+                    e,
+                    format!("fix the syntax of {}", file.display()),
+                )
+            }
+        }
+    }
+
+    if wiring.is_empty() {
+        return Check::warn(HOOKS, name, "longline is not wired as a hook", install_fix);
+    }
+    let missing = install::missing_hooks(runtime, &wiring);
+    if missing.is_empty() {
+        return Check::pass(HOOKS, name, read_from.join(", "));
+    }
+    if runtime == Runtime::Codex {
+        for (event, other, consequence) in [
+            (
+                "PermissionRequest",
+                "PreToolUse",
+                "denies go through in auto-executing modes",
+            ),
+            (
+                "PreToolUse",
+                "PermissionRequest",
+                "allowed commands still prompt",
+            ),
+        ] {
+            if !wiring.contains_key(event) {
+                return Check::fail(
+                    HOOKS,
+                    name,
+                    format!("only {other} runs longline: {consequence}"),
+                    install_fix,
+                );
+            }
+        }
+    }
+    let missing: Vec<String> = missing
+        .iter()
+        .map(|(event, tool)| format!("{event} {tool}"))
+        .collect();
+    Check::warn(
+        HOOKS,
+        name,
+        format!("not wired for {}", missing.join(", ")),
+        install_fix,
+    )
+}
+
+/// The hooks create the log directory on first use; a directory (or the
+/// ancestor it would be created in) that can't be written means decisions
+/// are silently not logged.
+fn check_log_dir(name: &str, dir: &Path) -> Check {
+    let Some(existing) = dir.ancestors().find(|d| d.exists()) else {
+        return Check::fail(
+            LOGS,
+            name,
+            format!("no existing ancestor of {}", dir.display()),
+            format!("create {}", dir.display()),
+        );
+    };
+    if !is_writable(existing) {
+        return Check::fail(
+            LOGS,
+            name,
+            format!("{} is not writable", existing.display()),
+            format!("make {} writable by this user", existing.display()),
+        );
+    }
+    if existing == dir {
+        Check::pass(LOGS, name, dir.display().to_string())
+    } else {
+        Check::pass(
+            LOGS,
+            name,
+            format!("{} (created on first decision)", dir.display()),
+        )
+    }
+}
+
+fn is_writable(path: &Path) -> bool {
+    use std::os::unix::ffi::OsStrExt;
+    let Ok(c_path) = std::ffi::CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    unsafe { libc::access(c_path.as_ptr(), libc::W_OK) == 0 }
+}
+
+fn check_ai_judge(config_path: &Path, checks: &mut Vec<Check>) {
+    let config = match ai_judge::try_load_config_from_path(config_path) {
+        Ok(config) => {
+            let detail = if config_path.exists() {
+                config_path.display().to_string()
+            } else {
+                format!("{} (not present, using defaults)", config_path.display())
+            };
+            checks.push(Check::pass(AI_JUDGE, "config", detail));
+            config
+        }
+        Err(e) => {
+            checks.push(Check::fail(
+                AI_JUDGE,
+                "config",
+                format!("{e}; the defaults are used instead"),
+                format!("fix {}", config_path.display()),
+            ));
+            return;
+        }
+    };
+
+    let set = ai_judge::resolve_provider_set(&config.command, &config.fallback_command);
+    for warning in &set.warnings {
+        checks.push(Check::fail(
+            AI_JUDGE,
+            "providers",
+            warning.trim_start_matches("longline: "),
+            format!("fix the shell quoting in {}", config_path.display()),
+        ));
+    }
+    if set.empty {
+        checks.push(Check::warn(
+            AI_JUDGE,
+            "providers",
+            "no providers configured: --ask-ai asks instead of judging",
+            format!(
+                "set `command` or `fallback_command` in {}",
+                config_path.display()
+            ),
+        ));
+        return;
+    }
+
+    for provider in &set.providers {
+        let name = format!("{} provider", provider.name);
+        let program = &provider.argv[0];
+        let role = if provider.name == "claude" {
+            "the hedge is disabled"
+        } else {
+            "the primary judge is disabled"
+        };
+        match find_program(program) {
+            Some(path) => checks.push(Check::pass(AI_JUDGE, name, path.display().to_string())),
+            None => checks.push(Check::warn(
+                AI_JUDGE,
+                name,
+                format!("`{program}` is not on PATH: {role}"),
+                format!(
+                    "install `{program}`, or set its command to \"\" in {} to disable it",
+                    config_path.display()
+                ),
+            )),
+        }
+        if provider.name == "claude" {
+            if let Some((settings, _)) = ai_judge::settings_arg(&provider.argv) {
+                checks.push(check_judge_settings(&settings));
+            }
+        }
+    }
+}
+
+/// `invoke` repairs a missing or unsafe settings file from the embedded
+/// copy before each judge call; it only degrades when that write fails.
+fn check_judge_settings(path: &Path) -> Check {
+    const NAME: &str = "claude settings";
+    match std::fs::read_to_string(path) {
+        Ok(content) if ai_judge::content_is_safe(&content) => {
+            return Check::pass(AI_JUDGE, NAME, path.display().to_string())
+        }
+        Ok(_) | Err(_) => {}
+    }
+    let detail = if path.exists() {
+        format!("{} is corrupt or unsafe", path.display())
+    } else {
+        format!("{} is missing", path.display())
+    };
+    let parent = path.parent().unwrap_or(Path::new("/"));
+    let repairable = parent
+        .ancestors()
+        .find(|d| d.exists())
+        .is_some_and(is_writable);
+    if repairable {
+        Check::warn(
+            AI_JUDGE,
+            NAME,
+            format!("{detail}; it is rewritten from the embedded copy on the next judge call"),
+            "run `longline init` to write it now",
+        )
+    } else {
+        Check::fail(
+            AI_JUDGE,
+            NAME,
+            format!("{detail} and cannot be rewritten: claude runs without --settings"),
+            format!("make {} writable by this user", parent.display()),
+        )
+    }
+}
+
+/// Resolve a program the way `Command::new` would: a path containing `/`
+/// as is, anything else against `PATH`.
+fn find_program(program: &str) -> Option<PathBuf> {
+    use std::os::unix::fs::PermissionsExt;
+    let is_executable = |path: &Path| {
+        path.metadata()
+            .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    };
+    if program.contains('/') {
+        let path = PathBuf::from(program);
+        return is_executable(&path).then_some(path);
+    }
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|path| is_executable(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codex_wired_for_one_event_fails() {
+        let home = tempfile::tempdir().unwrap();
+        let hooks = home.path().join(".codex/hooks.json");
+        std::fs::create_dir_all(hooks.parent().unwrap()).unwrap();
+        std::fs::write(
+            &hooks,
+            r#"{"hooks":{"PermissionRequest":[{"matcher":"Bash","hooks":[{"type":"command","command":"longline hook codex"}]}]}}"#,
+        )
+        .unwrap();
+        let check = check_hooks(Runtime::Codex, home.path(), None);
+        assert_eq!(check.status, Status::Fail);
+        assert!(
+            check.detail.contains("only PermissionRequest"),
+            "{}",
+            check.detail
+        );
+
+        let check = check_hooks(Runtime::Claude, home.path(), None);
+        assert_eq!(check.status, Status::Warn);
+    }
+
+    #[test]
+    fn test_judge_settings_missing_or_corrupt_warns() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("judge-claude-settings.json");
+        assert_eq!(check_judge_settings(&path).status, Status::Warn);
+        std::fs::write(&path, "{ not json").unwrap();
+        let check = check_judge_settings(&path);
+        assert_eq!(check.status, Status::Warn);
+        assert!(check.detail.contains("corrupt"));
+        std::fs::write(&path, ai_judge::JUDGE_CLAUDE_SETTINGS).unwrap();
+        assert_eq!(check_judge_settings(&path).status, Status::Pass);
+    }
+
+    #[test]
+    fn test_find_program() {
+        assert!(find_program("sh").is_some());
+        assert!(find_program("/bin/sh").is_some());
+        assert!(find_program("longline-no-such-program").is_none());
+    }
+}
@@ -2,16 +2,14 @@ use std::path::{Path, PathBuf};
 
 use crate::logger;
 use longline::ai_judge;
+use longline::apply_patch::{self, PatchOp};
 use longline::domain::Decision;
 use longline::domain::PolicyResult;
 use longline::parser;
 use longline::parser::{ArgMeta, ListOp, Statement};
+use longline::paths;
 use longline::policy;
-
-#[cfg_attr(not(test), allow(dead_code))]
-const SENSITIVE_PATH_PATTERNS: &[&str] = &["/.ssh/", "/.aws/", "/.gnupg/"];
-#[cfg_attr(not(test), allow(dead_code))]
-const SENSITIVE_EXACT_PATHS: &[&str] = &["/etc/shadow"];
+use longline::urls;
 
 #[derive(Debug, Clone)]
 #[cfg_attr(not(test), allow(dead_code))]
@@ -36,6 +34,36 @@ pub(crate) enum Invocation {
         #[allow(dead_code)]
         session_id: Option<String>,
     },
+    WritePath {
+        tool_name: String,
+        path: Option<String>,
+        cwd: Option<String>,
+        session_id: Option<String>,
+    },
+    /// Codex `apply_patch`: every file the envelope touches is held to the
+    /// write path policy.
+    Patch {
+        tool_name: String,
+        patch: Option<String>,
+        cwd: Option<String>,
+        session_id: Option<String>,
+    },
+    /// MCP tool call (`mcp__<server>__<tool>`), evaluated against `mcp`
+    /// rules.
+    Mcp {
+        tool_name: String,
+        input: serde_json::Value,
+        cwd: Option<String>,
+        session_id: Option<String>,
+    },
+    /// URL fetched by a tool (Claude's WebFetch), evaluated against `url`
+    /// rules.
+    Url {
+        tool_name: String,
+        url: Option<String>,
+        cwd: Option<String>,
+        session_id: Option<String>,
+    },
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -58,6 +86,10 @@ pub(crate) struct EvaluationOutcome {
     pub parse_ok: bool,
     pub original_decision: Option<Decision>,
     pub overridden: bool,
+    /// No rule had an opinion: the adapter should defer to the runtime's own
+    /// permission flow rather than emit `decision`. Only MCP calls and
+    /// fetched URLs that no rule matched take this path.
+    pub passthrough: bool,
 }
 
 #[cfg_attr(not(test), allow(dead_code))]
@@ -102,16 +134,47 @@ pub(crate) fn evaluate_invocation(
         Invocation::ReadPath {
             tool_name,
             path: Some(path),
-            cwd: _,
-            session_id: _,
-        }
-        | Invocation::SearchPath {
+            cwd,
+            session_id,
+        } => evaluate_path(PathEvaluationRequest {
+            rules: &final_config.rules,
+            audit_log_path,
+            cwd: cwd.as_deref().unwrap_or(""),
+            access: policy::PathTool::Read,
+            tool_name: &tool_name,
+            path: &path,
+            session_id,
+            ask_on_deny: options.ask_on_deny,
+            runtime,
+            profile: final_config.resolved_profile.clone(),
+        }),
+        Invocation::SearchPath {
             tool_name,
             path: Some(path),
+            cwd,
+            session_id,
+        } => evaluate_path(PathEvaluationRequest {
+            rules: &final_config.rules,
+            audit_log_path,
+            cwd: cwd.as_deref().unwrap_or(""),
+            access: policy::PathTool::Search,
+            tool_name: &tool_name,
+            path: &path,
+            session_id,
+            ask_on_deny: options.ask_on_deny,
+            runtime,
+            profile: final_config.resolved_profile.clone(),
+        }),
+        Invocation::SearchPath {
+            tool_name,
+            path: None,
             cwd: _,
             session_id: _,
-        } => evaluate_path(&tool_name, &path),
-        Invocation::SearchPath {
+        } => EvaluationOutcome::simple(
+            Decision::Allow,
+            format!("longline: {tool_name} allowed (no path)"),
+        ),
+        Invocation::WritePath {
             tool_name,
             path: None,
             cwd: _,
@@ -120,6 +183,77 @@ pub(crate) fn evaluate_invocation(
             Decision::Allow,
             format!("longline: {tool_name} allowed (no path)"),
         ),
+        Invocation::WritePath {
+            tool_name,
+            path: Some(path),
+            cwd,
+            session_id,
+        } => evaluate_path(PathEvaluationRequest {
+            rules: &final_config.rules,
+            audit_log_path,
+            cwd: cwd.as_deref().unwrap_or(""),
+            access: policy::PathTool::Write,
+            tool_name: &tool_name,
+            path: &path,
+            session_id,
+            ask_on_deny: options.ask_on_deny,
+            runtime,
+            profile: final_config.resolved_profile.clone(),
+        }),
+        Invocation::Patch {
+            tool_name,
+            patch,
+            cwd,
+            session_id,
+        } => evaluate_patch(PatchEvaluationRequest {
+            rules: &final_config.rules,
+            audit_log_path,
+            cwd: cwd.as_deref().unwrap_or(""),
+            tool_name: &tool_name,
+            patch: patch.as_deref(),
+            session_id,
+            ask_on_deny: options.ask_on_deny,
+            runtime,
+            profile: final_config.resolved_profile.clone(),
+        }),
+        Invocation::Mcp {
+            tool_name,
+            input,
+            cwd,
+            session_id,
+        } => evaluate_mcp(McpEvaluationRequest {
+            rules: &final_config.rules,
+            audit_log_path,
+            cwd: cwd.as_deref().unwrap_or(""),
+            tool_name: &tool_name,
+            input: &input,
+            session_id,
+            ask_on_deny: options.ask_on_deny,
+            runtime,
+            profile: final_config.resolved_profile.clone(),
+        }),
+        Invocation::Url {
+            tool_name,
+            url: None,
+            cwd: _,
+            session_id: _,
+        } => EvaluationOutcome::passthrough(format!("longline: {tool_name} (no url)")),
+        Invocation::Url {
+            tool_name,
+            url: Some(url),
+            cwd,
+            session_id,
+        } => evaluate_url(UrlEvaluationRequest {
+            rules: &final_config.rules,
+            audit_log_path,
+            cwd: cwd.as_deref().unwrap_or(""),
+            tool_name: &tool_name,
+            url: &url,
+            session_id,
+            ask_on_deny: options.ask_on_deny,
+            runtime,
+            profile: final_config.resolved_profile.clone(),
+        }),
     }
 }
 
@@ -131,9 +265,13 @@ impl Invocation {
     /// consumer (project-config discovery, etc.).
     pub(crate) fn cwd(&self) -> Option<&str> {
         match self {
-            Self::Shell { cwd, .. } | Self::ReadPath { cwd, .. } | Self::SearchPath { cwd, .. } => {
-                cwd.as_deref().filter(|s| !s.is_empty())
-            }
+            Self::Shell { cwd, .. }
+            | Self::ReadPath { cwd, .. }
+            | Self::SearchPath { cwd, .. }
+            | Self::WritePath { cwd, .. }
+            | Self::Patch { cwd, .. }
+            | Self::Mcp { cwd, .. }
+            | Self::Url { cwd, .. } => cwd.as_deref().filter(|s| !s.is_empty()),
         }
     }
 
@@ -141,7 +279,12 @@ impl Invocation {
     pub(crate) fn tool_name(&self) -> &str {
         match self {
             Self::Shell { .. } => "Bash",
-            Self::ReadPath { tool_name, .. } | Self::SearchPath { tool_name, .. } => tool_name,
+            Self::ReadPath { tool_name, .. }
+            | Self::SearchPath { tool_name, .. }
+            | Self::WritePath { tool_name, .. }
+            | Self::Patch { tool_name, .. }
+            | Self::Mcp { tool_name, .. }
+            | Self::Url { tool_name, .. } => tool_name,
         }
     }
 
@@ -149,6 +292,8 @@ impl Invocation {
     pub(crate) fn command_or_empty(&self) -> &str {
         match self {
             Self::Shell { command, .. } => command.as_deref().unwrap_or(""),
+            Self::Patch { patch, .. } => patch.as_deref().unwrap_or(""),
+            Self::Url { url, .. } => url.as_deref().unwrap_or(""),
             _ => "",
         }
     }
@@ -158,7 +303,11 @@ impl Invocation {
         match self {
             Self::Shell { session_id, .. }
             | Self::ReadPath { session_id, .. }
-            | Self::SearchPath { session_id, .. } => session_id.as_deref(),
+            | Self::SearchPath { session_id, .. }
+            | Self::WritePath { session_id, .. }
+            | Self::Patch { session_id, .. }
+            | Self::Mcp { session_id, .. }
+            | Self::Url { session_id, .. } => session_id.as_deref(),
         }
     }
 }
@@ -174,36 +323,494 @@ impl EvaluationOutcome {
             parse_ok: true,
             original_decision: None,
             overridden: false,
+            passthrough: false,
+        }
+    }
+
+    fn passthrough(reason: String) -> Self {
+        Self {
+            passthrough: true,
+            ..Self::simple(Decision::Allow, reason)
         }
     }
 }
 
 #[cfg_attr(not(test), allow(dead_code))]
-fn evaluate_path(tool_name: &str, path: &str) -> EvaluationOutcome {
-    for pattern in SENSITIVE_PATH_PATTERNS {
-        if path.contains(pattern) {
-            return EvaluationOutcome::simple(
-                Decision::Ask,
-                format!("longline: {tool_name} sensitive path ({pattern}): {path}"),
-            );
+struct PathEvaluationRequest<'a> {
+    rules: &'a policy::RulesConfig,
+    audit_log_path: &'a Path,
+    cwd: &'a str,
+    access: policy::PathTool,
+    tool_name: &'a str,
+    path: &'a str,
+    session_id: Option<String>,
+    ask_on_deny: bool,
+    runtime: &'static str,
+    profile: String,
+}
+
+/// Evaluate a file tool's target path against the `path` rules (and, for
+/// writes, the redirect rules, so `Edit ~/.bashrc` is held to the same
+/// policy as `echo ... >> ~/.bashrc`). The path is resolved against the
+/// hook's cwd first, so `../../.ssh/id_rsa` and symlinked spellings match
+/// like their canonical form. Write decisions are audit-logged with the
+/// target path in the `command` field, mirroring the Bash flow, plus the
+/// raw and resolved paths; read/search decisions are not logged.
+#[cfg_attr(not(test), allow(dead_code))]
+fn evaluate_path(request: PathEvaluationRequest<'_>) -> EvaluationOutcome {
+    let cwd = Some(request.cwd).filter(|cwd| !cwd.is_empty());
+    let resolved = paths::resolve(request.path, cwd);
+    let result = policy::evaluate_path_access(request.rules, request.access, &resolved);
+    let overridden = request.ask_on_deny && result.decision == Decision::Deny;
+    let final_decision = if overridden {
+        Decision::Ask
+    } else {
+        result.decision
+    };
+
+    // Ask/deny reasons name the resolved path when it differs, so a prompt
+    // for `../../.ssh/id_rsa` shows what the rule actually matched.
+    let shown_path = if resolved.is_rewritten() {
+        format!("{} -> {}", resolved.raw, resolved.resolved)
+    } else {
+        resolved.raw.clone()
+    };
+    let reason = match final_decision {
+        Decision::Allow => format!("longline: {} allowed: {}", request.tool_name, request.path),
+        Decision::Ask | Decision::Deny if overridden => format!(
+            "[overridden] {} ({}: {})",
+            format_reason(&result),
+            request.tool_name,
+            shown_path
+        ),
+        Decision::Ask | Decision::Deny => format!(
+            "{} ({}: {})",
+            format_reason(&result),
+            request.tool_name,
+            shown_path
+        ),
+    };
+    let log_reason = if result.reason.is_empty() {
+        None
+    } else {
+        Some(result.reason.clone())
+    };
+    let matched_rules: Vec<String> = result.rule_id.clone().into_iter().collect();
+    if request.access == policy::PathTool::Write {
+        let ctx = logger::EntryContext {
+            runtime: request.runtime,
+            profile: request.profile,
+        };
+        let mut entry = logger::make_entry(
+            &ctx,
+            request.tool_name,
+            request.cwd,
+            request.path,
+            final_decision,
+            matched_rules.clone(),
+            log_reason.clone(),
+            true,
+            request.session_id,
+        );
+        entry.path = Some(resolved.raw.clone());
+        entry.resolved_path = Some(resolved.resolved.clone());
+        if overridden {
+            entry.original_decision = Some(result.decision);
+            entry.overridden = true;
         }
+        logger::log_decision_to(&entry, request.audit_log_path);
     }
 
-    for exact in SENSITIVE_EXACT_PATHS {
-        if path == *exact {
-            return EvaluationOutcome::simple(
-                Decision::Ask,
-                format!("longline: {tool_name} sensitive path: {path}"),
-            );
+    EvaluationOutcome {
+        decision: final_decision,
+        reason,
+        log_reason,
+        matched_rules,
+        parse_ok: true,
+        original_decision: overridden.then_some(result.decision),
+        overridden,
+        passthrough: false,
+    }
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+struct PatchEvaluationRequest<'a> {
+    rules: &'a policy::RulesConfig,
+    audit_log_path: &'a Path,
+    cwd: &'a str,
+    tool_name: &'a str,
+    patch: Option<&'a str>,
+    session_id: Option<String>,
+    ask_on_deny: bool,
+    runtime: &'static str,
+    profile: String,
+}
+
+/// One path an `apply_patch` envelope touches, with the operation that
+/// touches it.
+struct PatchTarget<'a> {
+    op: PatchOp,
+    path: &'a str,
+    /// The patch removes the file at `path`: a delete, or the source of an
+    /// update-with-rename.
+    removes: bool,
+}
+
+/// Evaluate a Codex `apply_patch` envelope. Every touched path is resolved
+/// against the cwd and held to the write path policy (path rules plus
+/// redirect rules); on top of that, paths outside the project root and
+/// deletes or moves of git-tracked files ask. The strongest finding wins. A payload
+/// that is missing or does not parse asks rather than passing through.
+/// One audit entry is logged per patch, with a one-line summary of the
+/// touched files in the `command` field.
+#[cfg_attr(not(test), allow(dead_code))]
+fn evaluate_patch(request: PatchEvaluationRequest<'_>) -> EvaluationOutcome {
+    let parsed = match request.patch {
+        Some(patch) => apply_patch::parse(patch),
+        None => Err("no patch payload".to_string()),
+    };
+    let files = match parsed {
+        Ok(files) => files,
+        Err(e) => {
+            let result = PolicyResult::ask(&format!("Unparseable apply_patch envelope: {e}"));
+            let command = request.patch.unwrap_or("").to_string();
+            return finish_patch(request, result, command, None, false);
+        }
+    };
+
+    let targets: Vec<PatchTarget<'_>> = files
+        .iter()
+        .flat_map(|file| {
+            let dest = file.move_to.as_deref().map(|dest| PatchTarget {
+                op: PatchOp::MoveTo,
+                path: dest,
+                removes: false,
+            });
+            std::iter::once(PatchTarget {
+                op: file.op,
+                path: &file.path,
+                removes: file.op == PatchOp::Delete || file.move_to.is_some(),
+            })
+            .chain(dest)
+        })
+        .collect();
+    let summary = targets
+        .iter()
+        .map(|t| format!("{} {}", t.op, t.path))
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    let cwd = Some(request.cwd).filter(|cwd| Path::new(cwd).is_absolute());
+    let project_root = cwd.map(|cwd| {
+        let cwd = Path::new(cwd);
+        let root = longline::config::find_project_root(cwd).unwrap_or_else(|| cwd.to_path_buf());
+        std::fs::canonicalize(&root).unwrap_or(root)
+    });
+
+    let mut strongest: Option<(PolicyResult, &PatchTarget<'_>, paths::ResolvedPath)> = None;
+    for target in &targets {
+        let resolved = paths::resolve(target.path, cwd);
+        let mut result =
+            policy::evaluate_path_access(request.rules, policy::PathTool::Write, &resolved);
+        if result.decision == Decision::Allow {
+            let inside_root = project_root
+                .as_deref()
+                .is_some_and(|root| Path::new(&resolved.resolved).starts_with(root));
+            if !inside_root {
+                result = PolicyResult::ask("Patch touches a path outside the project root");
+            } else if target.removes && is_git_tracked(Path::new(&resolved.resolved)) {
+                result = PolicyResult::ask(match target.op {
+                    PatchOp::Delete => "Patch deletes a git-tracked file",
+                    _ => "Patch moves a git-tracked file",
+                });
+            }
+        }
+        let stronger = strongest
+            .as_ref()
+            .is_none_or(|(best, _, _)| result.decision > best.decision);
+        if stronger {
+            strongest = Some((result, target, resolved));
+        }
+    }
+
+    let (result, target, resolved) = strongest.expect("parsed patch touches at least one file");
+    let finding = format!("{} {}", target.op, target.path);
+    let resolved = (targets.len() == 1).then_some(resolved);
+    finish_patch(request, result, summary, Some((finding, resolved)), true)
+}
+
+/// Apply the `--ask-on-deny` override, build the hook reason and write the
+/// audit entry for a patch decision. `finding` names the touched path that
+/// decided the outcome, plus its resolution when the patch touches only
+/// that one path.
+fn finish_patch(
+    request: PatchEvaluationRequest<'_>,
+    result: PolicyResult,
+    command: String,
+    finding: Option<(String, Option<paths::ResolvedPath>)>,
+    parse_ok: bool,
+) -> EvaluationOutcome {
+    let overridden = request.ask_on_deny && result.decision == Decision::Deny;
+    let final_decision = if overridden {
+        Decision::Ask
+    } else {
+        result.decision
+    };
+    let prefix = if overridden { "[overridden] " } else { "" };
+    let reason = match (final_decision, &finding) {
+        (Decision::Allow, _) => format!("longline: {} allowed: {command}", request.tool_name),
+        (_, Some((finding, _))) => format!(
+            "{prefix}{} ({}: {finding})",
+            format_reason(&result),
+            request.tool_name
+        ),
+        (_, None) => format!("{prefix}{}", format_reason(&result)),
+    };
+    let log_reason = if result.reason.is_empty() {
+        None
+    } else {
+        Some(result.reason.clone())
+    };
+    let matched_rules: Vec<String> = result.rule_id.clone().into_iter().collect();
+
+    let ctx = logger::EntryContext {
+        runtime: request.runtime,
+        profile: request.profile,
+    };
+    let mut entry = logger::make_entry(
+        &ctx,
+        request.tool_name,
+        request.cwd,
+        &command,
+        final_decision,
+        matched_rules.clone(),
+        log_reason.clone(),
+        parse_ok,
+        request.session_id,
+    );
+    if let Some((_, Some(resolved))) = finding {
+        entry.path = Some(resolved.raw);
+        entry.resolved_path = Some(resolved.resolved);
+    }
+    if overridden {
+        entry.original_decision = Some(result.decision);
+        entry.overridden = true;
+    }
+    logger::log_decision_to(&entry, request.audit_log_path);
+
+    EvaluationOutcome {
+        decision: final_decision,
+        reason,
+        log_reason,
+        matched_rules,
+        parse_ok,
+        original_decision: overridden.then_some(result.decision),
+        overridden,
+        passthrough: false,
+    }
+}
+
+/// Whether git tracks `path`. Asked of `git ls-files` in the file's parent
+/// directory; a file that does not exist, or is not inside a work tree, is
+/// untracked. If git itself cannot be run the file is assumed tracked, so
+/// the delete asks instead of slipping through.
+fn is_git_tracked(path: &Path) -> bool {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        return false;
+    };
+    if !path.exists() {
+        return false;
+    }
+    match std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["ls-files", "--error-unmatch", "--"])
+        .arg(name)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+    {
+        Ok(status) => status.success(),
+        Err(_) => true,
+    }
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+struct McpEvaluationRequest<'a> {
+    rules: &'a policy::RulesConfig,
+    audit_log_path: &'a Path,
+    cwd: &'a str,
+    tool_name: &'a str,
+    input: &'a serde_json::Value,
+    session_id: Option<String>,
+    ask_on_deny: bool,
+    runtime: &'static str,
+    profile: String,
+}
+
+/// Evaluate an MCP tool call against the `mcp` rules. A call no rule
+/// matches passes through to the runtime's own permission flow (and is not
+/// logged), so adding longline does not change how unconfigured MCP
+/// servers behave. Matched calls are logged with the compact JSON
+/// `tool_input` in the `command` field.
+#[cfg_attr(not(test), allow(dead_code))]
+fn evaluate_mcp(request: McpEvaluationRequest<'_>) -> EvaluationOutcome {
+    let Some((server, tool)) = split_mcp_tool_name(request.tool_name) else {
+        return EvaluationOutcome::passthrough(format!(
+            "longline: {} is not an MCP tool name",
+            request.tool_name
+        ));
+    };
+    let result = policy::evaluate_mcp_call(request.rules, server, tool, request.input);
+    if result.rule_id.is_none() {
+        return EvaluationOutcome::passthrough(format!(
+            "longline: no mcp rule for {}",
+            request.tool_name
+        ));
+    }
+
+    let overridden = request.ask_on_deny && result.decision == Decision::Deny;
+    let final_decision = if overridden {
+        Decision::Ask
+    } else {
+        result.decision
+    };
+    let prefix = if overridden { "[overridden] " } else { "" };
+    let reason = format!("{prefix}{} ({})", format_reason(&result), request.tool_name);
+    let log_reason = Some(result.reason.clone()).filter(|r| !r.is_empty());
+    let matched_rules: Vec<String> = result.rule_id.clone().into_iter().collect();
+
+    let ctx = logger::EntryContext {
+        runtime: request.runtime,
+        profile: request.profile,
+    };
+    let mut entry = logger::make_entry(
+        &ctx,
+        request.tool_name,
+        request.cwd,
+        &request.input.to_string(),
+        final_decision,
+        matched_rules.clone(),
+        log_reason.clone(),
+        true,
+        request.session_id,
+    );
+    if overridden {
+        entry.original_decision = Some(result.decision);
+        entry.overridden = true;
+    }
+    logger::log_decision_to(&entry, request.audit_log_path);
+
+    EvaluationOutcome {
+        decision: final_decision,
+        reason,
+        log_reason,
+        matched_rules,
+        parse_ok: true,
+        original_decision: overridden.then_some(result.decision),
+        overridden,
+        passthrough: false,
+    }
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+struct UrlEvaluationRequest<'a> {
+    rules: &'a policy::RulesConfig,
+    audit_log_path: &'a Path,
+    cwd: &'a str,
+    tool_name: &'a str,
+    url: &'a str,
+    session_id: Option<String>,
+    ask_on_deny: bool,
+    runtime: &'static str,
+    profile: String,
+}
+
+/// Evaluate a fetched URL against the `url` rules and `allowlists.hosts`.
+/// A URL that neither a rule nor the host allowlist covers passes through
+/// to the runtime's own permission flow (and is not logged); a URL that
+/// does not parse asks. Every decision longline does make is logged with
+/// the URL as the command.
+#[cfg_attr(not(test), allow(dead_code))]
+fn evaluate_url(request: UrlEvaluationRequest<'_>) -> EvaluationOutcome {
+    let (result, parse_ok) = match urls::parse(request.url, None) {
+        Some(url) => {
+            let result = policy::evaluate_url_access(request.rules, &url);
+            if result.rule_id.is_none() && result.reason.is_empty() {
+                return EvaluationOutcome::passthrough(format!(
+                    "longline: no url rule for {}",
+                    url.host
+                ));
+            }
+            (result, true)
         }
+        None => (
+            PolicyResult {
+                decision: Decision::Ask,
+                rule_id: None,
+                reason: "Unparseable URL".to_string(),
+            },
+            false,
+        ),
+    };
+
+    let overridden = request.ask_on_deny && result.decision == Decision::Deny;
+    let final_decision = if overridden {
+        Decision::Ask
+    } else {
+        result.decision
+    };
+    let prefix = if overridden { "[overridden] " } else { "" };
+    let reason = match final_decision {
+        Decision::Allow if result.rule_id.is_none() => format!(
+            "longline: {} allowed: {} ({})",
+            request.tool_name, request.url, result.reason
+        ),
+        _ => format!(
+            "{prefix}{} ({}: {})",
+            format_reason(&result),
+            request.tool_name,
+            request.url
+        ),
+    };
+    let log_reason = Some(result.reason.clone()).filter(|r| !r.is_empty());
+    let matched_rules: Vec<String> = result.rule_id.clone().into_iter().collect();
+
+    let ctx = logger::EntryContext {
+        runtime: request.runtime,
+        profile: request.profile,
+    };
+    let mut entry = logger::make_entry(
+        &ctx,
+        request.tool_name,
+        request.cwd,
+        request.url,
+        final_decision,
+        matched_rules.clone(),
+        log_reason.clone(),
+        parse_ok,
+        request.session_id,
+    );
+    if overridden {
+        entry.original_decision = Some(result.decision);
+        entry.overridden = true;
     }
+    logger::log_decision_to(&entry, request.audit_log_path);
 
-    EvaluationOutcome::simple(
-        Decision::Allow,
-        format!("longline: {tool_name} allowed: {path}"),
-    )
+    EvaluationOutcome {
+        decision: final_decision,
+        reason,
+        log_reason,
+        matched_rules,
+        parse_ok,
+        original_decision: overridden.then_some(result.decision),
+        overridden,
+        passthrough: false,
+    }
 }
 
+pub(crate) use policy::split_mcp_tool_name;
+
 #[cfg_attr(not(test), allow(dead_code))]
 struct ShellEvaluationRequest<'a> {
     rules: &'a policy::RulesConfig,
@@ -259,11 +866,20 @@ fn evaluate_shell_command_with_parse_result(
                 parse_ok: false,
                 original_decision: None,
                 overridden: false,
+                passthrough: false,
             };
         }
     };
 
-    let result = policy::evaluate(request.rules, &stmt);
+    let policy::Evaluation {
+        result,
+        findings,
+        deciding_leaf,
+    } = policy::evaluate_with_findings(
+        request.rules,
+        &stmt,
+        Some(request.cwd).filter(|cwd| Path::new(cwd).is_absolute()),
+    );
     let overridden = request.ask_on_deny && result.decision == Decision::Deny;
     let final_decision = if overridden {
         Decision::Ask
@@ -380,6 +996,8 @@ fn evaluate_shell_command_with_parse_result(
         true,
         request.session_id,
     );
+    entry.findings = findings;
+    entry.deciding_leaf = deciding_leaf;
     if overridden {
         entry.original_decision = Some(result.decision);
         entry.overridden = true;
@@ -399,6 +1017,7 @@ fn evaluate_shell_command_with_parse_result(
         parse_ok: true,
         original_decision: overridden.then_some(result.decision),
         overridden,
+        passthrough: false,
     }
 }
 
@@ -536,7 +1155,7 @@ fn resolve_cd_target(arg: &parser::Arg, cwd: &str) -> Option<PathBuf> {
     ) {
         return None;
     }
-    let expanded = expand_tilde(&arg.text)?;
+    let expanded = paths::expand_tilde(&arg.text)?;
     let candidate = if Path::new(&expanded).is_absolute() {
         PathBuf::from(expanded)
     } else {
@@ -545,22 +1164,6 @@ fn resolve_cd_target(arg: &parser::Arg, cwd: &str) -> Option<PathBuf> {
     std::fs::canonicalize(candidate).ok()
 }
 
-/// Local copy of `expand_tilde` from `ai_judge::extract::fs` — that
-/// helper is `pub(super)` and exposing it through the lib's public
-/// surface for one consumer in the binary crate is more churn than
-/// duplicating six lines.
-#[cfg_attr(not(test), allow(dead_code))]
-fn expand_tilde(path: &str) -> Option<String> {
-    if path == "~" {
-        return std::env::var("HOME").ok();
-    }
-    if let Some(rest) = path.strip_prefix("~/") {
-        let home = std::env::var("HOME").ok()?;
-        return Some(Path::new(&home).join(rest).to_string_lossy().to_string());
-    }
-    Some(path.to_string())
-}
-
 #[cfg_attr(not(test), allow(dead_code))]
 fn is_under_safe_root(path: &Path) -> bool {
     if let Ok(home) = std::env::var("HOME") {
@@ -1178,7 +1781,7 @@ mod tests {
         assert_eq!(outcome.decision, Decision::Ask);
         assert_eq!(
             outcome.reason,
-            "longline: Read sensitive path (/.ssh/): /home/user/.ssh/id_rsa"
+            "[path-ssh-dir] Accessing SSH credential store (Read: /home/user/.ssh/id_rsa)"
         );
         assert!(!log_path.exists());
     }
@@ -1222,6 +1825,342 @@ mod tests {
         assert_eq!(outcome.reason, "longline: Grep allowed (no path)");
     }
 
+    fn write_invocation(tool_name: &str, path: &str) -> Invocation {
+        Invocation::WritePath {
+            tool_name: tool_name.to_string(),
+            path: Some(path.to_string()),
+            cwd: Some("/repo".to_string()),
+            session_id: Some("session-w".to_string()),
+        }
+    }
+
+    fn evaluate_write_with_options(
+        home: &tempfile::TempDir,
+        invocation: Invocation,
+        options: EvaluationOptions,
+    ) -> EvaluationOutcome {
+        evaluate_invocation(
+            final_config(base_config()),
+            &log_path(home),
+            invocation,
+            options,
+            "claude",
+        )
+    }
+
+    #[test]
+    fn test_evaluate_write_shell_profile_asks_and_logs_current_fields() {
+        let home = tempfile::TempDir::new().unwrap();
+        let outcome = evaluate_write_with_options(
+            &home,
+            write_invocation("Edit", "/home/user/.bashrc"),
+            EvaluationOptions::default(),
+        );
+
+        assert_eq!(outcome.decision, Decision::Ask);
+        assert_eq!(
+            outcome.reason,
+            "[redirect-write-shell-profile] Redirect write to shell profile (Edit: /home/user/.bashrc)"
+        );
+        assert_eq!(outcome.matched_rules, vec!["redirect-write-shell-profile"]);
+
+        let entry = last_log_entry(&home);
+        assert_eq!(entry["tool"], "Edit");
+        assert_eq!(entry["cwd"], "/repo");
+        assert_eq!(entry["command"], "/home/user/.bashrc");
+        assert_eq!(entry["decision"], "ask");
+        assert_eq!(entry["matched_rules"][0], "redirect-write-shell-profile");
+        assert_eq!(entry["reason"], "Redirect write to shell profile");
+        assert_eq!(entry["session_id"], "session-w");
+    }
+
+    #[test]
+    fn test_evaluate_write_safe_path_allows_and_logs() {
+        let home = tempfile::TempDir::new().unwrap();
+        let outcome = evaluate_write_with_options(
+            &home,
+            write_invocation("Write", "/repo/src/main.rs"),
+            EvaluationOptions::default(),
+        );
+
+        assert_eq!(outcome.decision, Decision::Allow);
+        assert_eq!(outcome.reason, "longline: Write allowed: /repo/src/main.rs");
+        let entry = last_log_entry(&home);
+        assert_eq!(entry["tool"], "Write");
+        assert_eq!(entry["decision"], "allow");
+        assert_eq!(entry["path"], "/repo/src/main.rs");
+        assert_eq!(entry["resolved_path"], "/repo/src/main.rs");
+        assert!(entry["matched_rules"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_evaluate_write_git_hook_ask_on_deny_logs_override_fields() {
+        let home = tempfile::TempDir::new().unwrap();
+        let outcome = evaluate_write_with_options(
+            &home,
+            write_invocation("Write", "/repo/.git/hooks/post-checkout"),
+            EvaluationOptions {
+                ask_on_deny: true,
+                ..EvaluationOptions::default()
+            },
+        );
+
+        assert_eq!(outcome.decision, Decision::Ask);
+        assert!(outcome.overridden);
+        assert_eq!(outcome.original_decision, Some(Decision::Deny));
+        assert!(outcome
+            .reason
+            .starts_with("[overridden] [redirect-write-git-admin]"));
+        let entry = last_log_entry(&home);
+        assert_eq!(entry["original_decision"], "deny");
+        assert_eq!(entry["overridden"], true);
+    }
+
+    #[test]
+    fn test_evaluate_write_home_relative_pattern_matches_absolute_path() {
+        let _lock = env_lock().lock().unwrap();
+        let fake_home = tempfile::TempDir::new().unwrap();
+        let _home = HomeEnvGuard::set(fake_home.path());
+        let key = fake_home.path().join(".ssh").join("id_ed25519");
+
+        let outcome = eval(write_invocation("Write", &key.display().to_string()));
+
+        assert_eq!(outcome.decision, Decision::Ask);
+        assert_eq!(
+            outcome.matched_rules,
+            vec!["redirect-write-ssh-private-key"]
+        );
+    }
+
+    #[test]
+    fn test_evaluate_write_relative_path_resolves_against_cwd() {
+        let _lock = env_lock().lock().unwrap();
+        let fake_home = tempfile::TempDir::new().unwrap();
+        let _home = HomeEnvGuard::set(fake_home.path());
+        let repo = fake_home.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        let resolved = std::fs::canonicalize(fake_home.path())
+            .unwrap()
+            .join(".bashrc");
+
+        let outcome = evaluate_write_with_options(
+            &fake_home,
+            Invocation::WritePath {
+                tool_name: "Edit".to_string(),
+                path: Some("./sub/../../.bashrc".to_string()),
+                cwd: Some(repo.display().to_string()),
+                session_id: None,
+            },
+            EvaluationOptions::default(),
+        );
+
+        assert_eq!(outcome.decision, Decision::Ask);
+        assert_eq!(outcome.matched_rules, vec!["redirect-write-shell-profile"]);
+        assert!(outcome.reason.ends_with(&format!(
+            "(Edit: ./sub/../../.bashrc -> {})",
+            resolved.display()
+        )));
+        let entry = last_log_entry(&fake_home);
+        assert_eq!(entry["command"], "./sub/../../.bashrc");
+        assert_eq!(entry["path"], "./sub/../../.bashrc");
+        assert_eq!(entry["resolved_path"], resolved.display().to_string());
+    }
+
+    #[test]
+    fn test_evaluate_write_through_symlink_matches_target() {
+        let _lock = env_lock().lock().unwrap();
+        let fake_home = tempfile::TempDir::new().unwrap();
+        let _home = HomeEnvGuard::set(fake_home.path());
+        let profile = fake_home.path().join(".zshrc");
+        std::fs::write(&profile, "").unwrap();
+        let repo = tempfile::TempDir::new().unwrap();
+        std::os::unix::fs::symlink(&profile, repo.path().join("notes.txt")).unwrap();
+
+        let outcome = eval(Invocation::WritePath {
+            tool_name: "Write".to_string(),
+            path: Some("notes.txt".to_string()),
+            cwd: Some(repo.path().display().to_string()),
+            session_id: None,
+        });
+
+        assert_eq!(outcome.decision, Decision::Ask);
+        assert_eq!(outcome.matched_rules, vec!["redirect-write-shell-profile"]);
+    }
+
+    fn patch_invocation(patch: &str, cwd: Option<&str>) -> Invocation {
+        Invocation::Patch {
+            tool_name: "apply_patch".to_string(),
+            patch: Some(patch.to_string()),
+            cwd: cwd.map(String::from),
+            session_id: None,
+        }
+    }
+
+    #[test]
+    fn test_evaluate_patch_strongest_finding_wins_and_ask_on_deny_overrides() {
+        let home = tempfile::TempDir::new().unwrap();
+        let project = tempfile::TempDir::new().unwrap();
+        let cwd = project.path().display().to_string();
+        let patch = "*** Begin Patch\n*** Add File: .github/workflows/ci.yml\n+x\n*** Add File: .git/hooks/post-merge\n+x\n*** End Patch\n";
+
+        let outcome = evaluate_write_with_options(
+            &home,
+            patch_invocation(patch, Some(&cwd)),
+            EvaluationOptions {
+                ask_on_deny: true,
+                ..EvaluationOptions::default()
+            },
+        );
+
+        assert_eq!(outcome.decision, Decision::Ask);
+        assert_eq!(outcome.original_decision, Some(Decision::Deny));
+        assert_eq!(outcome.matched_rules, vec!["redirect-write-git-admin"]);
+        assert!(outcome
+            .reason
+            .ends_with("(apply_patch: add .git/hooks/post-merge)"));
+        let entry = last_log_entry(&home);
+        assert_eq!(
+            entry["command"],
+            "add .github/workflows/ci.yml; add .git/hooks/post-merge"
+        );
+        assert_eq!(entry["overridden"], true);
+        assert!(entry.get("path").is_none());
+    }
+
+    #[test]
+    fn test_evaluate_patch_without_cwd_asks() {
+        let outcome = eval(patch_invocation(
+            "*** Begin Patch\n*** Add File: src/a.rs\n+x\n*** End Patch\n",
+            None,
+        ));
+
+        assert_eq!(outcome.decision, Decision::Ask);
+        assert_eq!(
+            outcome.reason,
+            "Patch touches a path outside the project root (apply_patch: add src/a.rs)"
+        );
+    }
+
+    #[test]
+    fn test_evaluate_patch_inside_project_allows_with_summary() {
+        let project = tempfile::TempDir::new().unwrap();
+        let cwd = project.path().display().to_string();
+        let outcome = eval(patch_invocation(
+            "*** Begin Patch\n*** Update File: a.rs\n*** Move to: b.rs\n@@\n-x\n+y\n*** End Patch\n",
+            Some(&cwd),
+        ));
+
+        assert_eq!(outcome.decision, Decision::Allow);
+        assert_eq!(
+            outcome.reason,
+            "longline: apply_patch allowed: update a.rs; move to b.rs"
+        );
+    }
+
+    fn url_invocation(url: &str) -> Invocation {
+        Invocation::Url {
+            tool_name: "WebFetch".to_string(),
+            url: Some(url.to_string()),
+            cwd: Some("/tmp".to_string()),
+            session_id: None,
+        }
+    }
+
+    fn url_rules_config() -> policy::RulesConfig {
+        let mut config = base_config();
+        config.allowlists.hosts.push("docs.rs".to_string());
+        config.rules.push(
+            serde_norway::from_str(
+                r#"
+id: url-unlisted-host
+level: high
+match:
+  url: {}
+decision: ask
+reason: "Host not on the allowlist"
+"#,
+            )
+            .unwrap(),
+        );
+        config
+    }
+
+    #[test]
+    fn test_evaluate_url_without_rules_passes_through_unlogged() {
+        let home = tempfile::TempDir::new().unwrap();
+        let outcome = evaluate_write_with_options(
+            &home,
+            url_invocation("https://example.com/"),
+            EvaluationOptions::default(),
+        );
+
+        assert!(outcome.passthrough);
+        assert!(!log_path(&home).exists());
+    }
+
+    #[test]
+    fn test_evaluate_url_rule_and_host_allowlist_are_logged() {
+        let home = tempfile::TempDir::new().unwrap();
+        let evaluate = |url: &str| {
+            evaluate_invocation(
+                final_config(url_rules_config()),
+                &log_path(&home),
+                url_invocation(url),
+                EvaluationOptions::default(),
+                "claude",
+            )
+        };
+
+        let unlisted = evaluate("https://example.com/page");
+        assert_eq!(unlisted.decision, Decision::Ask);
+        assert_eq!(
+            unlisted.reason,
+            "[url-unlisted-host] Host not on the allowlist (WebFetch: https://example.com/page)"
+        );
+        assert_eq!(last_log_entry(&home)["command"], "https://example.com/page");
+
+        let listed = evaluate("https://docs.rs/serde");
+        assert!(!listed.passthrough);
+        assert_eq!(listed.decision, Decision::Allow);
+        assert!(listed.reason.contains("Allowlisted host (docs.rs)"));
+        let entry = last_log_entry(&home);
+        assert_eq!(entry["command"], "https://docs.rs/serde");
+        assert_eq!(entry["decision"], "allow");
+    }
+
+    #[test]
+    fn test_evaluate_unparseable_url_asks() {
+        let home = tempfile::TempDir::new().unwrap();
+        let outcome = evaluate_write_with_options(
+            &home,
+            url_invocation("not a url"),
+            EvaluationOptions::default(),
+        );
+
+        assert_eq!(outcome.decision, Decision::Ask);
+        assert!(!outcome.parse_ok);
+        assert_eq!(last_log_entry(&home)["parse_ok"], false);
+    }
+
+    #[test]
+    fn test_evaluate_write_no_path_allows_without_log() {
+        let home = tempfile::TempDir::new().unwrap();
+        let outcome = evaluate_write_with_options(
+            &home,
+            Invocation::WritePath {
+                tool_name: "NotebookEdit".to_string(),
+                path: None,
+                cwd: Some("/repo".to_string()),
+                session_id: None,
+            },
+            EvaluationOptions::default(),
+        );
+
+        assert_eq!(outcome.decision, Decision::Allow);
+        assert_eq!(outcome.reason, "longline: NotebookEdit allowed (no path)");
+        assert!(!log_path(&home).exists());
+    }
+
     // ============================================================
     // effective_cwd_for_extract — `cd <literal> && cmd` cwd-following
     // (spec 2026-05-02)
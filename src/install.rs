@@ -0,0 +1,838 @@
+//! `longline install` / `uninstall`: merge longline's hook entries into the
+//! Claude or Codex settings file, or take them out again, leaving every
+//! other hook and setting as it was.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Runtime {
+    Claude,
+    Codex,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Scope {
+    /// `~/.claude` or `~/.codex`.
+    User,
+    /// `<project>/.claude` or `<project>/.codex`.
+    Project,
+}
+
+/// One hook event and tool matcher longline must be wired to.
+struct HookSpec {
+    event: &'static str,
+    matcher: &'static str,
+}
+
+const CLAUDE_HOOKS: &[HookSpec] = &[
+    HookSpec {
+        event: "PreToolUse",
+        matcher: "Bash",
+    },
+    HookSpec {
+        event: "PreToolUse",
+        matcher: "Read",
+    },
+    HookSpec {
+        event: "PreToolUse",
+        matcher: "Grep",
+    },
+    HookSpec {
+        event: "PreToolUse",
+        matcher: "Glob",
+    },
+    HookSpec {
+        event: "PreToolUse",
+        matcher: "Write|Edit|MultiEdit|NotebookEdit",
+    },
+    HookSpec {
+        event: "PreToolUse",
+        matcher: "WebFetch",
+    },
+    HookSpec {
+        event: "PreToolUse",
+        matcher: "mcp__.*",
+    },
+];
+
+/// Codex needs both events: `PreToolUse` alone turns allows into prompts,
+/// `PermissionRequest` alone lets denies through in auto-executing modes.
+const CODEX_HOOKS: &[HookSpec] = &[
+    HookSpec {
+        event: "PreToolUse",
+        matcher: "Bash|apply_patch|mcp__.*",
+    },
+    HookSpec {
+        event: "PermissionRequest",
+        matcher: "Bash|apply_patch|mcp__.*",
+    },
+];
+
+const CODEX_TIMEOUT_SECS: i64 = 30;
+
+impl Runtime {
+    fn hooks(self) -> &'static [HookSpec] {
+        match self {
+            Runtime::Claude => CLAUDE_HOOKS,
+            Runtime::Codex => CODEX_HOOKS,
+        }
+    }
+
+    fn command(self, ask_ai_lenient: bool) -> String {
+        let flags = if ask_ai_lenient {
+            " --ask-ai-lenient"
+        } else {
+            ""
+        };
+        match self {
+            Runtime::Claude => format!("longline{flags}"),
+            Runtime::Codex => format!("longline{flags} hook codex"),
+        }
+    }
+
+    fn timeout(self) -> Option<i64> {
+        match self {
+            Runtime::Claude => None,
+            Runtime::Codex => Some(CODEX_TIMEOUT_SECS),
+        }
+    }
+}
+
+/// A settings file before and after an install or uninstall.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Change {
+    pub path: PathBuf,
+    pub before: String,
+    pub after: String,
+}
+
+impl Change {
+    pub fn is_noop(&self) -> bool {
+        self.before == self.after
+    }
+}
+
+/// The settings file longline's hooks go in. Codex reads hooks from
+/// `hooks.json` or inline from `config.toml`; `config.toml` is used only
+/// when it already has a `[hooks]` table and there is no `hooks.json`.
+pub(crate) fn settings_path(
+    runtime: Runtime,
+    scope: Scope,
+    home: &Path,
+    project: &Path,
+) -> PathBuf {
+    let base = match scope {
+        Scope::User => home,
+        Scope::Project => project,
+    };
+    match runtime {
+        Runtime::Claude => base.join(".claude").join("settings.json"),
+        Runtime::Codex => {
+            let dir = base.join(".codex");
+            let hooks_json = dir.join("hooks.json");
+            let config_toml = dir.join("config.toml");
+            let toml_has_hooks = std::fs::read_to_string(&config_toml)
+                .ok()
+                .and_then(|content| content.parse::<DocumentMut>().ok())
+                .is_some_and(|doc| doc.contains_key("hooks"));
+            if !hooks_json.exists() && toml_has_hooks {
+                config_toml
+            } else {
+                hooks_json
+            }
+        }
+    }
+}
+
+/// Every file the runtime reads hooks from at `scope`, whether or not it
+/// exists.
+pub(crate) fn hook_files(
+    runtime: Runtime,
+    scope: Scope,
+    home: &Path,
+    project: &Path,
+) -> Vec<PathBuf> {
+    let base = match scope {
+        Scope::User => home,
+        Scope::Project => project,
+    };
+    let names: &[&str] = match (runtime, scope) {
+        (Runtime::Claude, Scope::User) => &["settings.json"],
+        (Runtime::Claude, Scope::Project) => &["settings.json", "settings.local.json"],
+        (Runtime::Codex, _) => &["hooks.json", "config.toml"],
+    };
+    let dir = match runtime {
+        Runtime::Claude => base.join(".claude"),
+        Runtime::Codex => base.join(".codex"),
+    };
+    names.iter().map(|name| dir.join(name)).collect()
+}
+
+/// The tools each hook event runs longline for: every `|`-separated
+/// alternative of a matcher whose group has a longline command. A missing
+/// or empty matcher is recorded as `*`.
+pub(crate) type Wiring = BTreeMap<String, BTreeSet<String>>;
+
+/// Read the longline hook wiring from `path`. A missing file has none.
+pub(crate) fn read_wiring(path: &Path) -> Result<Wiring, String> {
+    let content = read_settings(path)?;
+    let groups = if is_toml(path) {
+        toml_groups(&content)
+    } else {
+        json_groups(&content)
+    }
+    .map_err(|e| format!("{}: {e}", path.display()))?;
+
+    let mut wiring = Wiring::new();
+    for (event, matcher, commands) in groups {
+        if !commands.iter().any(|c| is_longline_command(c)) {
+            continue;
+        }
+        let tools = wiring.entry(event).or_default();
+        match matcher.as_deref().map(str::trim) {
+            None | Some("") | Some("*") => {
+                tools.insert("*".to_string());
+            }
+            Some(matcher) => tools.extend(matcher.split('|').map(|t| t.trim().to_string())),
+        }
+    }
+    Ok(wiring)
+}
+
+/// The `(event, tool)` pairs longline needs for `runtime` that `wiring`
+/// leaves out.
+pub(crate) fn missing_hooks(
+    runtime: Runtime,
+    wiring: &Wiring,
+) -> Vec<(&'static str, &'static str)> {
+    runtime
+        .hooks()
+        .iter()
+        .flat_map(|spec| spec.matcher.split('|').map(move |tool| (spec.event, tool)))
+        .filter(|(event, tool)| {
+            wiring
+                .get(*event)
+                .is_none_or(|tools| !tools.contains(*tool) && !tools.contains("*"))
+        })
+        .collect()
+}
+
+/// A hook matcher group: its event, matcher and hook commands.
+type MatcherGroup = (String, Option<String>, Vec<String>);
+
+/// Every matcher group in a JSON settings
+/// file.
+fn json_groups(content: &str) -> Result<Vec<MatcherGroup>, String> {
+    let root = parse_json(content)?;
+    let mut out = Vec::new();
+    let Some(hooks) = root.get("hooks").and_then(Value::as_object) else {
+        return Ok(out);
+    };
+    for (event, groups) in hooks {
+        for group in groups.as_array().into_iter().flatten() {
+            let matcher = group
+                .get("matcher")
+                .and_then(Value::as_str)
+                .map(String::from);
+            let commands = group
+                .get("hooks")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|h| h.get("command").and_then(Value::as_str).map(String::from))
+                .collect();
+            out.push((event.clone(), matcher, commands));
+        }
+    }
+    Ok(out)
+}
+
+/// Every matcher group in a Codex
+/// `config.toml`.
+fn toml_groups(content: &str) -> Result<Vec<MatcherGroup>, String> {
+    let doc = parse_toml(content)?;
+    let mut out = Vec::new();
+    let Some(hooks) = doc.get("hooks").and_then(Item::as_table) else {
+        return Ok(out);
+    };
+    for (event, groups) in hooks.iter() {
+        for group in groups.as_array_of_tables().into_iter().flatten() {
+            let matcher = group
+                .get("matcher")
+                .and_then(Item::as_str)
+                .map(String::from);
+            let commands = group
+                .get("hooks")
+                .and_then(Item::as_array_of_tables)
+                .into_iter()
+                .flatten()
+                .filter_map(|h| h.get("command").and_then(Item::as_str).map(String::from))
+                .collect();
+            out.push((event.to_string(), matcher, commands));
+        }
+    }
+    Ok(out)
+}
+
+/// Add longline's hook entries to `path`. Entries already pointing at
+/// longline are updated in place, so running it twice changes nothing.
+pub(crate) fn install(
+    runtime: Runtime,
+    path: &Path,
+    ask_ai_lenient: bool,
+) -> Result<Change, String> {
+    let before = read_settings(path)?;
+    let command = runtime.command(ask_ai_lenient);
+    let after = if is_toml(path) {
+        install_toml(&before, runtime.hooks(), &command, runtime.timeout())
+    } else {
+        install_json(&before, runtime.hooks(), &command, runtime.timeout())
+    }
+    .map_err(|e| format!("{}: {e}", path.display()))?;
+    Ok(Change {
+        path: path.to_path_buf(),
+        before,
+        after,
+    })
+}
+
+/// Remove every hook entry that runs longline from `path`, along with the
+/// matcher groups and events it leaves empty.
+pub(crate) fn uninstall(path: &Path) -> Result<Change, String> {
+    let before = read_settings(path)?;
+    let after = if is_toml(path) {
+        uninstall_toml(&before)
+    } else {
+        uninstall_json(&before)
+    }
+    .map_err(|e| format!("{}: {e}", path.display()))?;
+    Ok(Change {
+        path: path.to_path_buf(),
+        before,
+        after,
+    })
+}
+
+/// Write `change` to disk, creating the parent directory if needed.
+pub(crate) fn apply(change: &Change) -> Result<(), String> {
+    if let Some(parent) = change.path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    std::fs::write(&change.path, &change.after)
+        .map_err(|e| format!("Failed to write {}: {e}", change.path.display()))
+}
+
+fn read_settings(path: &Path) -> Result<String, String> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(format!("Failed to read {}: {e}", path.display())),
+    }
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "toml")
+}
+
+/// Whether a hook command runs longline (any path to the binary, any flags).
+fn is_longline_command(command: &str) -> bool {
+    command
+        .split_whitespace()
+        .next()
+        .is_some_and(|program| program.rsplit('/').next() == Some("longline"))
+}
+
+fn install_json(
+    content: &str,
+    specs: &[HookSpec],
+    command: &str,
+    timeout: Option<i64>,
+) -> Result<String, String> {
+    let original = parse_json(content)?;
+    let mut root = original.clone();
+    let Some(root_obj) = root.as_object_mut() else {
+        return Err("expected a JSON object".to_string());
+    };
+    let hooks = root_obj.entry("hooks").or_insert_with(|| json!({}));
+    let Some(hooks) = hooks.as_object_mut() else {
+        return Err("`hooks` is not an object".to_string());
+    };
+
+    let mut entry = json!({ "type": "command", "command": command });
+    if let Some(timeout) = timeout {
+        entry["timeout"] = json!(timeout);
+    }
+    for spec in specs {
+        let groups = hooks.entry(spec.event).or_insert_with(|| json!([]));
+        let Some(groups) = groups.as_array_mut() else {
+            return Err(format!("`hooks.{}` is not an array", spec.event));
+        };
+        let group = groups
+            .iter_mut()
+            .find(|g| g.get("matcher").and_then(Value::as_str) == Some(spec.matcher));
+        let Some(group) = group else {
+            groups.push(json!({ "matcher": spec.matcher, "hooks": [entry.clone()] }));
+            continue;
+        };
+        let Some(group_hooks) = group
+            .as_object_mut()
+            .map(|g| g.entry("hooks").or_insert_with(|| json!([])))
+            .and_then(Value::as_array_mut)
+        else {
+            return Err(format!(
+                "`hooks.{}` group `{}` has no hooks array",
+                spec.event, spec.matcher
+            ));
+        };
+        let existing = group_hooks.iter_mut().find(|h| {
+            h.get("command")
+                .and_then(Value::as_str)
+                .is_some_and(is_longline_command)
+        });
+        match existing {
+            Some(hook) => *hook = entry.clone(),
+            None => group_hooks.push(entry.clone()),
+        }
+    }
+    render_json(content, &original, &root)
+}
+
+fn uninstall_json(content: &str) -> Result<String, String> {
+    let original = parse_json(content)?;
+    let mut root = original.clone();
+    let Some(root_obj) = root.as_object_mut() else {
+        return Err("expected a JSON object".to_string());
+    };
+    if let Some(hooks) = root_obj.get_mut("hooks").and_then(Value::as_object_mut) {
+        let had_events = !hooks.is_empty();
+        for groups in hooks.values_mut().filter_map(Value::as_array_mut) {
+            groups.retain_mut(|group| {
+                let Some(group_hooks) = group.get_mut("hooks").and_then(Value::as_array_mut) else {
+                    return true;
+                };
+                let had_hooks = !group_hooks.is_empty();
+                group_hooks.retain(|h| {
+                    !h.get("command")
+                        .and_then(Value::as_str)
+                        .is_some_and(is_longline_command)
+                });
+                !(had_hooks && group_hooks.is_empty())
+            });
+        }
+        hooks.retain(|_, groups| groups.as_array().is_none_or(|g| !g.is_empty()));
+        if had_events && hooks.is_empty() {
+            root_obj.remove("hooks");
+        }
+    }
+    render_json(content, &original, &root)
+}
+
+fn parse_json(content: &str) -> Result<Value, String> {
+    if content.trim().is_empty() {
+        return Ok(json!({}));
+    }
+    serde_json::from_str(content).map_err(|e| format!("invalid JSON: {e}"))
+}
+
+/// Keep the file byte-for-byte when nothing changed.
+fn render_json(content: &str, original: &Value, updated: &Value) -> Result<String, String> {
+    if original == updated {
+        return Ok(content.to_string());
+    }
+    serde_json::to_string_pretty(updated)
+        .map(|s| s + "\n")
+        .map_err(|e| e.to_string())
+}
+
+fn install_toml(
+    content: &str,
+    specs: &[HookSpec],
+    command: &str,
+    timeout: Option<i64>,
+) -> Result<String, String> {
+    let mut doc = parse_toml(content)?;
+    let hooks = doc.entry("hooks").or_insert_with(|| {
+        let mut table = Table::new();
+        table.set_implicit(true);
+        Item::Table(table)
+    });
+    let Some(hooks) = hooks.as_table_mut() else {
+        return Err("`hooks` is not a table".to_string());
+    };
+
+    let mut entry = Table::new();
+    entry.insert("type", toml_edit::value("command"));
+    entry.insert("command", toml_edit::value(command));
+    if let Some(timeout) = timeout {
+        entry.insert("timeout", toml_edit::value(timeout));
+    }
+    for spec in specs {
+        let groups = hooks
+            .entry(spec.event)
+            .or_insert_with(|| Item::ArrayOfTables(ArrayOfTables::new()));
+        let Some(groups) = groups.as_array_of_tables_mut() else {
+            return Err(format!("`hooks.{}` is not an array of tables", spec.event));
+        };
+        let group = groups
+            .iter_mut()
+            .find(|g| g.get("matcher").and_then(Item::as_str) == Some(spec.matcher));
+        let Some(group) = group else {
+            let mut group = Table::new();
+            group.insert("matcher", toml_edit::value(spec.matcher));
+            let mut group_hooks = ArrayOfTables::new();
+            group_hooks.push(entry.clone());
+            group.insert("hooks", Item::ArrayOfTables(group_hooks));
+            groups.push(group);
+            continue;
+        };
+        let Some(group_hooks) = group
+            .entry("hooks")
+            .or_insert_with(|| Item::ArrayOfTables(ArrayOfTables::new()))
+            .as_array_of_tables_mut()
+        else {
+            return Err(format!(
+                "`hooks.{}` group `{}` has no hooks array",
+                spec.event, spec.matcher
+            ));
+        };
+        let existing = group_hooks.iter_mut().find(|h| {
+            h.get("command")
+                .and_then(Item::as_str)
+                .is_some_and(is_longline_command)
+        });
+        match existing {
+            Some(hook) => {
+                // Rewrite only the keys that differ so an up-to-date entry
+                // keeps its formatting.
+                for (key, value) in entry.iter() {
+                    let current = hook.get(key).and_then(Item::as_value);
+                    if current
+                        .map(toml_edit::Value::to_string)
+                        .as_deref()
+                        .map(str::trim)
+                        != value
+                            .as_value()
+                            .map(|v| v.to_string())
+                            .as_deref()
+                            .map(str::trim)
+                    {
+                        hook.insert(key, value.clone());
+                    }
+                }
+            }
+            None => group_hooks.push(entry.clone()),
+        }
+    }
+    Ok(doc.to_string())
+}
+
+fn uninstall_toml(content: &str) -> Result<String, String> {
+    let mut doc = parse_toml(content)?;
+    let Some(hooks) = doc.get_mut("hooks").and_then(Item::as_table_mut) else {
+        return Ok(content.to_string());
+    };
+    let had_events = !hooks.is_empty();
+    for (_, groups) in hooks.iter_mut() {
+        let Some(groups) = groups.as_array_of_tables_mut() else {
+            continue;
+        };
+        groups.retain(|group| {
+            let Some(group_hooks) = group.get("hooks").and_then(Item::as_array_of_tables) else {
+                return true;
+            };
+            let remaining = group_hooks
+                .iter()
+                .filter(|h| {
+                    !h.get("command")
+                        .and_then(Item::as_str)
+                        .is_some_and(is_longline_command)
+                })
+                .count();
+            remaining > 0 || group_hooks.is_empty()
+        });
+        for group in groups.iter_mut() {
+            if let Some(group_hooks) = group
+                .get_mut("hooks")
+                .and_then(Item::as_array_of_tables_mut)
+            {
+                group_hooks.retain(|h| {
+                    !h.get("command")
+                        .and_then(Item::as_str)
+                        .is_some_and(is_longline_command)
+                });
+            }
+        }
+    }
+    hooks.retain(|_, groups| {
+        groups
+            .as_array_of_tables()
+            .is_none_or(|groups| !groups.is_empty())
+    });
+    if had_events && hooks.is_empty() {
+        doc.remove("hooks");
+    }
+    Ok(doc.to_string())
+}
+
+fn parse_toml(content: &str) -> Result<DocumentMut, String> {
+    content
+        .parse::<DocumentMut>()
+        .map_err(|e| format!("invalid TOML: {e}"))
+}
+
+/// A unified diff of `change`, with three lines of context.
+pub(crate) fn unified_diff(change: &Change) -> String {
+    const CONTEXT: usize = 3;
+    let old: Vec<&str> = change.before.lines().collect();
+    let new: Vec<&str> = change.after.lines().collect();
+
+    // Longest common subsequence table, filled from the end.
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    // (tag, old line index, new line index, text)
+    let mut ops: Vec<(char, usize, usize, &str)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            ops.push((' ', i, j, old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(('-', i, j, old[i]));
+            i += 1;
+        } else {
+            ops.push(('+', i, j, new[j]));
+            j += 1;
+        }
+    }
+
+    let path = change.path.display();
+    let mut out = format!("--- {path}\n+++ {path}\n");
+    let changed: Vec<usize> = (0..ops.len()).filter(|&k| ops[k].0 != ' ').collect();
+    let mut k = 0;
+    while k < changed.len() {
+        let start = changed[k].saturating_sub(CONTEXT);
+        let mut end = changed[k];
+        while k < changed.len() && changed[k] <= end + 2 * CONTEXT {
+            end = changed[k];
+            k += 1;
+        }
+        let end = (end + CONTEXT + 1).min(ops.len());
+        let hunk = &ops[start..end];
+        let old_len = hunk.iter().filter(|op| op.0 != '+').count();
+        let new_len = hunk.iter().filter(|op| op.0 != '-').count();
+        let old_start = hunk[0].1 + usize::from(old_len > 0);
+        let new_start = hunk[0].2 + usize::from(new_len > 0);
+        out.push_str(&format!(
+            "@@ -{old_start},{old_len} +{new_start},{new_len} @@\n"
+        ));
+        for (tag, _, _, text) in hunk {
+            out.push_str(&format!("{tag}{text}\n"));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn longline_commands(settings: &Value, event: &str) -> Vec<(String, String)> {
+        settings["hooks"][event]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .flat_map(|group| {
+                let matcher = group["matcher"].as_str().unwrap_or_default().to_string();
+                group["hooks"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(move |h| {
+                        let command = h["command"].as_str()?;
+                        is_longline_command(command).then(|| (matcher.clone(), command.to_string()))
+                    })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_install_claude_merges_and_is_idempotent() {
+        let existing = r#"{
+  "model": "opus",
+  "hooks": {
+    "PreToolUse": [
+      { "matcher": "Bash", "hooks": [{ "type": "command", "command": "other-hook" }] }
+    ],
+    "Stop": [{ "hooks": [{ "type": "command", "command": "notify" }] }]
+  }
+}"#;
+        let once = install_json(
+            existing,
+            CLAUDE_HOOKS,
+            &Runtime::Claude.command(false),
+            None,
+        )
+        .unwrap();
+        let settings: Value = serde_json::from_str(&once).unwrap();
+        assert_eq!(settings["model"], "opus");
+        assert_eq!(
+            settings["hooks"]["Stop"][0]["hooks"][0]["command"],
+            "notify"
+        );
+        let bash = &settings["hooks"]["PreToolUse"][0]["hooks"];
+        assert_eq!(bash[0]["command"], "other-hook");
+        assert_eq!(bash[1]["command"], "longline");
+        assert_eq!(longline_commands(&settings, "PreToolUse").len(), 7);
+
+        let twice = install_json(&once, CLAUDE_HOOKS, "longline", None).unwrap();
+        assert_eq!(twice, once);
+
+        let lenient = install_json(&once, CLAUDE_HOOKS, "longline --ask-ai-lenient", None).unwrap();
+        let settings: Value = serde_json::from_str(&lenient).unwrap();
+        let commands = longline_commands(&settings, "PreToolUse");
+        assert_eq!(commands.len(), 7);
+        assert!(commands
+            .iter()
+            .all(|(_, c)| c == "longline --ask-ai-lenient"));
+    }
+
+    #[test]
+    fn test_install_json_keeps_key_order() {
+        let existing = r#"{"model":"opus","permissions":{"deny":[]},"env":{"B":"1","A":"2"}}"#;
+        let installed = install_json(existing, CLAUDE_HOOKS, "longline", None).unwrap();
+        let at = |key: &str| installed.find(key).unwrap();
+        assert!(at("\"model\"") < at("\"permissions\""));
+        assert!(at("\"permissions\"") < at("\"env\""));
+        assert!(at("\"B\"") < at("\"A\""));
+    }
+
+    #[test]
+    fn test_uninstall_json_reverses_install() {
+        let existing = r#"{"hooks":{"PreToolUse":[{"matcher":"Bash","hooks":[{"type":"command","command":"other-hook"}]}]}}"#;
+        let installed = install_json(
+            existing,
+            CODEX_HOOKS,
+            &Runtime::Codex.command(false),
+            Runtime::Codex.timeout(),
+        )
+        .unwrap();
+        let settings: Value = serde_json::from_str(&installed).unwrap();
+        assert_eq!(
+            longline_commands(&settings, "PermissionRequest"),
+            vec![(
+                "Bash|apply_patch|mcp__.*".to_string(),
+                "longline hook codex".to_string()
+            )]
+        );
+        assert_eq!(
+            settings["hooks"]["PreToolUse"][1]["hooks"][0]["timeout"],
+            30
+        );
+
+        let removed: Value = serde_json::from_str(&uninstall_json(&installed).unwrap()).unwrap();
+        let original: Value = serde_json::from_str(existing).unwrap();
+        assert_eq!(removed, original);
+        assert_eq!(uninstall_json("").unwrap(), "");
+        assert_eq!(
+            uninstall_json(&install_json("", CLAUDE_HOOKS, "longline", None).unwrap()).unwrap(),
+            "{}\n"
+        );
+    }
+
+    #[test]
+    fn test_toml_install_and_uninstall_preserve_other_config() {
+        let existing = "# my codex config\nmodel = \"o3\"\n\n[[hooks.PreToolUse]]\nmatcher = \"Bash\"\n\n[[hooks.PreToolUse.hooks]]\ntype = \"command\"\ncommand = \"other-hook\"\n";
+        let installed = install_toml(
+            existing,
+            CODEX_HOOKS,
+            &Runtime::Codex.command(false),
+            Runtime::Codex.timeout(),
+        )
+        .unwrap();
+        assert!(installed.starts_with("# my codex config\nmodel = \"o3\"\n"));
+        let doc = installed.parse::<DocumentMut>().unwrap();
+        let permission = doc["hooks"]["PermissionRequest"]
+            .as_array_of_tables()
+            .unwrap();
+        assert_eq!(
+            permission.get(0).unwrap()["hooks"][0]["command"].as_str(),
+            Some("longline hook codex")
+        );
+        assert_eq!(
+            install_toml(&installed, CODEX_HOOKS, "longline hook codex", Some(30)).unwrap(),
+            installed
+        );
+
+        let removed = uninstall_toml(&installed).unwrap();
+        assert_eq!(
+            removed.parse::<DocumentMut>().unwrap().to_string(),
+            existing.parse::<DocumentMut>().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn test_missing_hooks_reports_unwired_event_and_tools() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hooks.json");
+        std::fs::write(
+            &path,
+            r#"{"hooks":{"PreToolUse":[{"matcher":"Bash|apply_patch|mcp__.*","hooks":[{"type":"command","command":"longline hook codex"}]}]}}"#,
+        )
+        .unwrap();
+        let wiring = read_wiring(&path).unwrap();
+        assert_eq!(
+            missing_hooks(Runtime::Codex, &wiring),
+            vec![
+                ("PermissionRequest", "Bash"),
+                ("PermissionRequest", "apply_patch"),
+                ("PermissionRequest", "mcp__.*"),
+            ]
+        );
+
+        let claude = dir.path().join("settings.json");
+        std::fs::write(
+            &claude,
+            r#"{"hooks":{"PreToolUse":[{"hooks":[{"type":"command","command":"longline"}]}]}}"#,
+        )
+        .unwrap();
+        assert!(missing_hooks(Runtime::Claude, &read_wiring(&claude).unwrap()).is_empty());
+        assert!(read_wiring(&dir.path().join("absent.json"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_is_longline_command() {
+        assert!(is_longline_command("longline"));
+        assert!(is_longline_command("/usr/local/bin/longline hook codex"));
+        assert!(!is_longline_command("longline-notify"));
+        assert!(!is_longline_command("echo longline"));
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let change = Change {
+            path: PathBuf::from("settings.json"),
+            before: "a\nb\nc\n".to_string(),
+            after: "a\nB\nc\nd\n".to_string(),
+        };
+        assert_eq!(
+            unified_diff(&change),
+            "--- settings.json\n+++ settings.json\n@@ -1,3 +1,4 @@\n a\n-b\n+B\n c\n+d\n"
+        );
+    }
+}
@@ -1,6 +1,9 @@
 pub mod ai_judge;
+pub mod apply_patch;
 pub mod config;
 pub mod domain;
 pub mod embedded_rules;
 pub mod parser;
+pub mod paths;
 pub mod policy;
+pub mod urls;
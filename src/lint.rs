@@ -0,0 +1,801 @@
+//! `longline lint`: static checks for rules that load fine but do not do
+//! what their author meant, located by file and line.
+//!
+//! Matcher checks run on the finalized configuration, so they see the
+//! active profile and the effective safety level. Duplicate ids and
+//! `disable_rules` entries are checked against the files themselves, since
+//! merging drops the disabled rules and the file each rule came from.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use longline::config;
+use longline::domain::Decision;
+use longline::policy::{
+    self, AllowlistEntry, Matcher, Rule, RuleSource, RulesConfig, ValuePattern,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Severity {
+    Warning,
+    Error,
+}
+
+/// One finding, at the line that defines the rule, entry or id it is about
+/// when that line can be found.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Issue {
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+}
+
+/// A rule or overlay file as written, and the layer it feeds.
+pub(crate) struct SourceFile {
+    pub label: String,
+    pub source: RuleSource,
+    pub content: String,
+}
+
+/// The files behind the configuration: the rules manifest's includes (or a
+/// single rules file) from `rules_path`, or the embedded rules when it is
+/// `None`, then the global and project overlays that exist.
+pub(crate) fn source_files(
+    rules_path: Option<&Path>,
+    home: &Path,
+    project_overlay: Option<&Path>,
+) -> Result<Vec<SourceFile>, String> {
+    let mut files = Vec::new();
+    match rules_path {
+        Some(path) => {
+            let content = read(path)?;
+            match manifest_includes(&content) {
+                Some(includes) => {
+                    let dir = path.parent().unwrap_or(Path::new("."));
+                    for name in includes {
+                        let path = dir.join(name);
+                        files.push(SourceFile {
+                            label: path.display().to_string(),
+                            source: RuleSource::BuiltIn,
+                            content: read(&path)?,
+                        });
+                    }
+                }
+                None => files.push(SourceFile {
+                    label: path.display().to_string(),
+                    source: RuleSource::BuiltIn,
+                    content,
+                }),
+            }
+        }
+        None => {
+            let manifest = longline::embedded_rules::get("rules.yaml").unwrap_or_default();
+            for name in manifest_includes(manifest).unwrap_or_default() {
+                let content = longline::embedded_rules::get(&name)
+                    .ok_or_else(|| format!("Embedded file '{name}' not found"))?;
+                files.push(SourceFile {
+                    label: format!("embedded:{name}"),
+                    source: RuleSource::BuiltIn,
+                    content: content.to_string(),
+                });
+            }
+        }
+    }
+    let global = config::global_config_path(home);
+    let overlays = [
+        (Some(global.as_path()), RuleSource::Global),
+        (project_overlay, RuleSource::Project),
+    ];
+    for (path, source) in overlays {
+        if let Some(path) = path.filter(|p| p.exists()) {
+            files.push(SourceFile {
+                label: path.display().to_string(),
+                source,
+                content: read(path)?,
+            });
+        }
+    }
+    Ok(files)
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))
+}
+
+fn manifest_includes(content: &str) -> Option<Vec<String>> {
+    #[derive(Deserialize)]
+    struct Manifest {
+        include: Vec<String>,
+    }
+    serde_norway::from_str::<Manifest>(content)
+        .ok()
+        .map(|m| m.include)
+}
+
+/// Run every check; errors first, then in file order.
+pub(crate) fn lint(config: &RulesConfig, files: &[SourceFile]) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    for rule in &config.rules {
+        check_inactive(config, rule, files, &mut issues);
+        check_argv_first_not(rule, files, &mut issues);
+        check_composition(rule, files, &mut issues);
+        check_globstars(rule, files, &mut issues);
+    }
+    for entry in &config.allowlists.commands {
+        check_shadowed(config, entry, files, &mut issues);
+    }
+    for pattern in &config.allowlists.paths {
+        if let Some(message) = midpattern_globstar(pattern) {
+            let (file, line) = locate_value(files, None, pattern);
+            issues.push(Issue {
+                severity: Severity::Warning,
+                code: "midpattern-globstar",
+                message: format!("allowlists.paths {message}"),
+                file,
+                line,
+            });
+        }
+    }
+    check_ids(files, &mut issues);
+    issues.sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+    issues
+}
+
+/// A rule above the safety level never fires. Built-in rules are exempt:
+/// their `strict` tier is meant to sit idle until a user raises the level.
+/// A rule whose `when` doesn't hold here doesn't fire here either.
+fn check_inactive(
+    config: &RulesConfig,
+    rule: &Rule,
+    files: &[SourceFile],
+    issues: &mut Vec<Issue>,
+) {
+    if !rule.applies {
+        issues.push(rule_issue(
+            files,
+            rule,
+            Severity::Warning,
+            "inactive-rule",
+            format!(
+                "rule `{}` is skipped here because its `when` doesn't hold",
+                rule.id
+            ),
+        ));
+        return;
+    }
+    if rule.source == RuleSource::BuiltIn || rule.level <= config.safety_level {
+        return;
+    }
+    issues.push(rule_issue(
+        files,
+        rule,
+        Severity::Warning,
+        "inactive-rule",
+        format!(
+            "rule `{}` has level {} but the safety level is {}, so it never fires",
+            rule.id, rule.level, config.safety_level
+        ),
+    ));
+}
+
+/// `argv_first_not` with nothing else to match on fires on every other
+/// subcommand, which is usually a `subcommand` pin written backwards.
+fn check_argv_first_not(rule: &Rule, files: &[SourceFile], issues: &mut Vec<Issue>) {
+    let Matcher::Command {
+        command,
+        flags,
+        args: Some(args),
+        ..
+    } = &rule.matcher
+    else {
+        return;
+    };
+    let flags_empty = flags
+        .as_ref()
+        .is_none_or(|f| f.any_of.is_empty() && f.all_of.is_empty() && f.starts_with.is_empty());
+    if args.argv_first_not.is_empty()
+        || !args.subcommand.is_empty()
+        || !args.any_of.is_empty()
+        || !args.all_of.is_empty()
+        || !args.regex.is_empty()
+        || !args.positional.is_empty()
+        || !args.option_values.is_empty()
+        || !flags_empty
+    {
+        return;
+    }
+    issues.push(rule_issue(
+        files,
+        rule,
+        Severity::Warning,
+        "argv-first-not",
+        format!(
+            "rule `{}` only has `argv_first_not: [{}]`, so it fires on every `{}` invocation whose first argument differs; use `subcommand` to pin the rule to a subcommand",
+            rule.id,
+            args.argv_first_not.join(", "),
+            command.patterns().join("|")
+        ),
+    ));
+}
+
+/// An `all`/`any`/`not` matcher with no scope never fires: it is empty, or
+/// it mixes leaves no single evaluation sees together.
+fn check_composition(rule: &Rule, files: &[SourceFile], issues: &mut Vec<Issue>) {
+    if !rule.matcher.is_composite() || rule.matcher.scope().is_some() {
+        return;
+    }
+    let (code, message) = if has_empty_combinator(&rule.matcher) {
+        (
+            "empty-combinator",
+            format!(
+                "rule `{}` has an `all` or `any` with no matchers, so it never fires",
+                rule.id
+            ),
+        )
+    } else {
+        let mut kinds: Vec<&str> = Vec::new();
+        for kind in rule.matcher.leaves().into_iter().map(matcher_kind) {
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+        }
+        (
+            "mixed-matcher",
+            format!(
+                "rule `{}` combines {} matchers, which are never evaluated together, so it never fires",
+                rule.id,
+                kinds.iter().map(|k| format!("`{k}`")).collect::<Vec<_>>().join(" and ")
+            ),
+        )
+    };
+    issues.push(rule_issue(files, rule, Severity::Error, code, message));
+}
+
+fn has_empty_combinator(matcher: &Matcher) -> bool {
+    match matcher {
+        Matcher::All { all: children } | Matcher::Any { any: children } => {
+            children.is_empty() || children.iter().any(has_empty_combinator)
+        }
+        Matcher::Not { not } => has_empty_combinator(not),
+        _ => false,
+    }
+}
+
+fn matcher_kind(matcher: &Matcher) -> &'static str {
+    match matcher {
+        Matcher::All { .. } => "all",
+        Matcher::Any { .. } => "any",
+        Matcher::Not { .. } => "not",
+        Matcher::Pipeline { .. } => "pipeline",
+        Matcher::Redirect { .. } => "redirect",
+        Matcher::GitConfig { .. } => "git_config",
+        Matcher::Path { .. } => "path",
+        Matcher::Mcp { .. } => "mcp",
+        Matcher::Url { .. } => "url",
+        Matcher::Command { .. } => "command",
+    }
+}
+
+fn check_globstars(rule: &Rule, files: &[SourceFile], issues: &mut Vec<Issue>) {
+    let mut patterns: Vec<(&str, &String)> = Vec::new();
+    for leaf in rule.matcher.leaves() {
+        collect_glob_patterns(leaf, &mut patterns);
+    }
+    for (field, pattern) in patterns {
+        if let Some(message) = midpattern_globstar(pattern) {
+            issues.push(rule_issue(
+                files,
+                rule,
+                Severity::Warning,
+                "midpattern-globstar",
+                format!("rule `{}` {field} {message}", rule.id),
+            ));
+        }
+    }
+}
+
+fn collect_glob_patterns<'a>(matcher: &'a Matcher, patterns: &mut Vec<(&'static str, &'a String)>) {
+    match matcher {
+        Matcher::Command {
+            args: Some(args), ..
+        } => {
+            for (field, list) in [
+                ("args.any_of", &args.any_of),
+                ("args.all_of", &args.all_of),
+                ("args.none_of", &args.none_of),
+            ] {
+                patterns.extend(list.iter().map(|p| (field, p)));
+            }
+            let positional = args.positional.values().map(|p| ("args.positional", p));
+            let option_values = args.option_values.values();
+            let value_patterns = positional.chain(option_values.map(|p| ("args.option_values", p)));
+            for (field, pattern) in value_patterns {
+                if let ValuePattern::Glob(glob) = pattern {
+                    patterns.push((field, glob));
+                }
+            }
+        }
+        Matcher::Redirect { redirect } => {
+            if let Some(target) = &redirect.target {
+                patterns.extend(target.patterns().iter().map(|p| ("redirect.target", p)));
+            }
+        }
+        Matcher::Path { path } => {
+            patterns.extend(path.target.patterns().iter().map(|p| ("path.target", p)));
+        }
+        Matcher::Url { url } => {
+            if let Some(paths) = &url.path {
+                patterns.extend(paths.patterns().iter().map(|p| ("url.path", p)));
+            }
+        }
+        Matcher::Mcp { mcp } => {
+            for condition in &mcp.input {
+                if let Some(matches) = &condition.matches {
+                    patterns.extend(matches.patterns().iter().map(|p| ("mcp.input.matches", p)));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `**` crosses `/` only as a whole path segment or at the end of the
+/// pattern; anywhere else it behaves like `*`.
+fn midpattern_globstar(pattern: &str) -> Option<String> {
+    let bytes = pattern.as_bytes();
+    let stuck = pattern.match_indices("**").any(|(i, _)| {
+        let end = i + 2;
+        let segment_start = i == 0 || bytes[i - 1] == b'/';
+        let segment_end = end == bytes.len() || bytes[end] == b'/';
+        end != bytes.len() && !(segment_start && segment_end)
+    });
+    stuck.then(|| {
+        format!(
+            "pattern `{pattern}` has `**` inside a path segment, where it does not cross `/`; write it as its own segment (`/**/`) or at the end"
+        )
+    })
+}
+
+/// An allowlist entry is dead when an active deny rule fires on the
+/// entry's own command: rules are checked first, so nothing it covers is
+/// ever allowed.
+fn check_shadowed(
+    config: &RulesConfig,
+    entry: &AllowlistEntry,
+    files: &[SourceFile],
+    issues: &mut Vec<Issue>,
+) {
+    let Ok(trace) = policy::explain(config, &entry.command, None) else {
+        return;
+    };
+    let Some(hit) = trace
+        .leaves
+        .iter()
+        .flat_map(|leaf| &leaf.rules)
+        .find(|hit| hit.active && hit.decision == Decision::Deny)
+    else {
+        return;
+    };
+    let (file, line) = locate(files, entry.source, "command", &entry.command);
+    issues.push(Issue {
+        severity: Severity::Warning,
+        code: "shadowed-allowlist",
+        message: format!(
+            "allowlist entry `{}` is shadowed by deny rule `{}`, which fires on the entry's own command",
+            entry.command, hit.id
+        ),
+        file,
+        line,
+    });
+}
+
+/// One top-level rule definition as written.
+struct Definition<'a> {
+    id: String,
+    file: &'a SourceFile,
+    line: Option<usize>,
+}
+
+/// Duplicate rule ids, and `disable_rules` ids that match no rule loaded
+/// before their overlay (so they disable nothing).
+fn check_ids(files: &[SourceFile], issues: &mut Vec<Issue>) {
+    let mut seen: Vec<Definition> = Vec::new();
+    for file in files {
+        let Ok(doc) = serde_norway::from_str::<serde_norway::Value>(&file.content) else {
+            continue;
+        };
+        let disabled: Vec<&str> = doc
+            .get("disable_rules")
+            .and_then(|v| v.as_sequence())
+            .map(|ids| ids.iter().filter_map(|id| id.as_str()).collect())
+            .unwrap_or_default();
+        for id in &disabled {
+            if !seen.iter().any(|d| d.id == *id) {
+                issues.push(Issue {
+                    severity: Severity::Error,
+                    code: "unknown-disable-rule",
+                    message: format!(
+                        "disable_rules entry `{id}` matches no rule loaded before this file"
+                    ),
+                    file: Some(file.label.clone()),
+                    line: list_item_line(&file.content, id)
+                        .or_else(|| key_line(&file.content, "disable_rules")),
+                });
+            }
+        }
+
+        let mut nth: HashMap<String, usize> = HashMap::new();
+        let ids = doc
+            .get("rules")
+            .and_then(|v| v.as_sequence())
+            .into_iter()
+            .flatten()
+            .filter_map(|rule| rule.get("id")?.as_str());
+        for id in ids {
+            let n = nth.entry(id.to_string()).or_default();
+            let line = find_lines(&file.content, "id", id).get(*n).copied();
+            *n += 1;
+            if let Some(first) = seen.iter().find(|d| d.id == id) {
+                let same_layer = first.file.source == file.source;
+                if same_layer || !disabled.contains(&id) {
+                    issues.push(Issue {
+                        severity: if same_layer {
+                            Severity::Error
+                        } else {
+                            Severity::Warning
+                        },
+                        code: "duplicate-id",
+                        message: duplicate_message(id, first, same_layer),
+                        file: Some(file.label.clone()),
+                        line,
+                    });
+                }
+            }
+            seen.push(Definition {
+                id: id.to_string(),
+                file,
+                line,
+            });
+        }
+    }
+}
+
+fn duplicate_message(id: &str, first: &Definition, same_layer: bool) -> String {
+    let at = match first.line {
+        Some(line) => format!("{}:{line}", first.file.label),
+        None => first.file.label.clone(),
+    };
+    if same_layer {
+        format!("duplicate rule id `{id}` (first defined at {at})")
+    } else {
+        format!(
+            "rule id `{id}` is also defined at {at}; both rules apply. Add it to `disable_rules` to replace that rule"
+        )
+    }
+}
+
+fn rule_issue(
+    files: &[SourceFile],
+    rule: &Rule,
+    severity: Severity,
+    code: &'static str,
+    message: String,
+) -> Issue {
+    let (file, line) = locate(files, rule.source, "id", &rule.id);
+    Issue {
+        severity,
+        code,
+        message,
+        file,
+        line,
+    }
+}
+
+/// The first `key: value` line in the files of `source`.
+fn locate(
+    files: &[SourceFile],
+    source: RuleSource,
+    key: &str,
+    value: &str,
+) -> (Option<String>, Option<usize>) {
+    files
+        .iter()
+        .filter(|f| f.source == source)
+        .find_map(|f| {
+            let line = *find_lines(&f.content, key, value).first()?;
+            Some((Some(f.label.clone()), Some(line)))
+        })
+        .unwrap_or((None, None))
+}
+
+/// The first line listing `value` as a sequence item, in any file.
+fn locate_value(
+    files: &[SourceFile],
+    source: Option<RuleSource>,
+    value: &str,
+) -> (Option<String>, Option<usize>) {
+    files
+        .iter()
+        .filter(|f| source.is_none_or(|s| f.source == s))
+        .find_map(|f| {
+            let line = list_item_line(&f.content, value)?;
+            Some((Some(f.label.clone()), Some(line)))
+        })
+        .unwrap_or((None, None))
+}
+
+/// 1-based lines holding `key: value`, block or flow style, with the value
+/// bare or quoted.
+fn find_lines(content: &str, key: &str, value: &str) -> Vec<usize> {
+    let needles: Vec<String> = quoted_forms(value)
+        .iter()
+        .map(|v| format!("{key}: {v}"))
+        .collect();
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            needles.iter().any(|needle| {
+                line.match_indices(needle.as_str()).any(|(i, _)| {
+                    let key_start = line[..i]
+                        .chars()
+                        .last()
+                        .is_none_or(|c| !c.is_alphanumeric() && c != '_');
+                    let rest = line[i + needle.len()..].trim_start();
+                    key_start && (rest.is_empty() || rest.starts_with([',', '}', '#']))
+                })
+            })
+        })
+        .map(|(i, _)| i + 1)
+        .collect()
+}
+
+fn list_item_line(content: &str, value: &str) -> Option<usize> {
+    let forms = quoted_forms(value);
+    content
+        .lines()
+        .position(|line| {
+            let item = line.trim().strip_prefix("- ").map(str::trim);
+            item.is_some_and(|item| forms.iter().any(|f| f == item))
+        })
+        .map(|i| i + 1)
+}
+
+fn key_line(content: &str, key: &str) -> Option<usize> {
+    let prefix = format!("{key}:");
+    content
+        .lines()
+        .position(|line| line.starts_with(&prefix))
+        .map(|i| i + 1)
+}
+
+fn quoted_forms(value: &str) -> [String; 3] {
+    [
+        value.to_string(),
+        format!("\"{value}\""),
+        format!("'{value}'"),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(label: &str, source: RuleSource, content: &str) -> SourceFile {
+        SourceFile {
+            label: label.to_string(),
+            source,
+            content: content.to_string(),
+        }
+    }
+
+    fn codes(issues: &[Issue]) -> Vec<(&'static str, Option<usize>)> {
+        issues.iter().map(|i| (i.code, i.line)).collect()
+    }
+
+    #[test]
+    fn test_embedded_rules_lint_clean() {
+        let config = longline::config::load_embedded_rules().unwrap();
+        let files = source_files(None, Path::new("/nonexistent"), None).unwrap();
+        assert!(files.len() > 1);
+        let issues = lint(&config, &files);
+        assert!(issues.is_empty(), "{issues:#?}");
+    }
+
+    #[test]
+    fn test_midpattern_globstar() {
+        for ok in [
+            "**/.env",
+            "/etc/**",
+            "/a/**/b",
+            "core.sshcommand=**",
+            "*.**",
+            "x",
+        ] {
+            assert!(midpattern_globstar(ok).is_none(), "{ok}");
+        }
+        for stuck in ["a/**.txt", "--p=**/x", "/a**/b", "**=x"] {
+            assert!(midpattern_globstar(stuck).is_some(), "{stuck}");
+        }
+    }
+
+    #[test]
+    fn test_find_lines_block_and_flow() {
+        let content = "rules:\n  - id: a\n  - { id: \"ab\", level: high }\n  - id: abc # note\n";
+        assert_eq!(find_lines(content, "id", "a"), vec![2]);
+        assert_eq!(find_lines(content, "id", "ab"), vec![3]);
+        assert_eq!(find_lines(content, "id", "abc"), vec![4]);
+        assert!(find_lines(content, "xid", "a").is_empty());
+    }
+
+    #[test]
+    fn test_rule_checks_are_located() {
+        let overlay = r#"rules:
+  - id: never-fires
+    level: strict
+    match: { command: curl }
+    decision: ask
+    reason: r
+  - id: pin-backwards
+    level: high
+    match:
+      command: kubectl
+      args:
+        argv_first_not: [get]
+    decision: ask
+    reason: r
+  - id: stuck-glob
+    level: high
+    match:
+      path:
+        target: "/repo/**.pem"
+    decision: ask
+    reason: r
+allowlists:
+  commands:
+    - { command: "shred disk", trust: standard }
+"#;
+        let mut config: RulesConfig = serde_norway::from_str(
+            r#"
+version: 1
+safety_level: high
+rules:
+  - id: no-shred
+    level: critical
+    match: { command: shred }
+    decision: deny
+    reason: r
+"#,
+        )
+        .unwrap();
+        let project: longline::config::ProjectConfig = serde_norway::from_str(overlay).unwrap();
+        longline::config::merge_overlay_config(&mut config, project, RuleSource::Project);
+        let files = [file("project.yaml", RuleSource::Project, overlay)];
+        let issues = lint(&config, &files);
+        assert_eq!(
+            codes(&issues),
+            vec![
+                ("inactive-rule", Some(2)),
+                ("argv-first-not", Some(7)),
+                ("midpattern-globstar", Some(15)),
+                ("shadowed-allowlist", Some(24)),
+            ]
+        );
+        assert!(issues[3].message.contains("no-shred"));
+    }
+
+    #[test]
+    fn test_rules_whose_when_fails_are_reported() {
+        let overlay = r#"rules:
+  - id: codex-curl
+    level: high
+    match: { command: curl }
+    decision: deny
+    reason: r
+    when: { runtime: codex }
+"#;
+        let mut config: RulesConfig = serde_norway::from_str("version: 1\n").unwrap();
+        let project: longline::config::ProjectConfig = serde_norway::from_str(overlay).unwrap();
+        longline::config::merge_overlay_config(&mut config, project, RuleSource::Project);
+        config.rules[0].applies = false;
+        let files = [file("project.yaml", RuleSource::Project, overlay)];
+        let issues = lint(&config, &files);
+        assert_eq!(codes(&issues), vec![("inactive-rule", Some(2))]);
+        assert!(issues[0].message.contains("`when`"));
+    }
+
+    #[test]
+    fn test_composition_checks() {
+        let overlay = r#"rules:
+  - id: mixed
+    level: high
+    match:
+      all:
+        - path: { target: "**/.env" }
+        - command: cat
+    decision: ask
+    reason: r
+  - id: empty
+    level: high
+    match: { any: [] }
+    decision: ask
+    reason: r
+  - id: nested-glob
+    level: high
+    match:
+      not: { path: { target: "/repo/**.key" } }
+    decision: ask
+    reason: r
+"#;
+        let mut config: RulesConfig =
+            serde_norway::from_str("version: 1\nsafety_level: high\nrules: []\n").unwrap();
+        let project: longline::config::ProjectConfig = serde_norway::from_str(overlay).unwrap();
+        longline::config::merge_overlay_config(&mut config, project, RuleSource::Project);
+        let files = [file("project.yaml", RuleSource::Project, overlay)];
+        let issues = lint(&config, &files);
+        assert_eq!(
+            codes(&issues),
+            vec![
+                ("mixed-matcher", Some(2)),
+                ("empty-combinator", Some(10)),
+                ("midpattern-globstar", Some(15)),
+            ]
+        );
+        assert!(issues[0].message.contains("`path` and `command`"));
+    }
+
+    #[test]
+    fn test_duplicate_and_unknown_disabled_ids() {
+        let rule = |id: &str| {
+            format!("  - id: {id}\n    level: high\n    match: {{ command: x }}\n    decision: ask\n    reason: r\n")
+        };
+        let files = [
+            file(
+                "a.yaml",
+                RuleSource::BuiltIn,
+                &format!("rules:\n{}", rule("one")),
+            ),
+            file(
+                "b.yaml",
+                RuleSource::BuiltIn,
+                &format!("rules:\n{}{}", rule("two"), rule("one")),
+            ),
+            file(
+                "global.yaml",
+                RuleSource::Global,
+                &format!(
+                    "disable_rules:\n  - two\n  - missing\nrules:\n{}{}",
+                    rule("two"),
+                    rule("one")
+                ),
+            ),
+        ];
+        let mut issues = Vec::new();
+        check_ids(&files, &mut issues);
+        let found: Vec<_> = issues
+            .iter()
+            .map(|i| (i.severity, i.code, i.file.as_deref().unwrap(), i.line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (Severity::Error, "duplicate-id", "b.yaml", Some(7)),
+                (
+                    Severity::Error,
+                    "unknown-disable-rule",
+                    "global.yaml",
+                    Some(3)
+                ),
+                (Severity::Warning, "duplicate-id", "global.yaml", Some(10)),
+            ]
+        );
+        assert!(issues[0].message.contains("a.yaml:2"));
+    }
+}
@@ -7,7 +7,7 @@ use std::path::{Path, PathBuf};
 use longline::domain::Decision;
 
 const DEFAULT_MAX_LOG_FILE_BYTES: u64 = 25 * 1024 * 1024;
-const MAX_ROTATED_LOG_FILES: usize = 10;
+pub(crate) const MAX_ROTATED_LOG_FILES: usize = 10;
 const LOG_MAX_BYTES_ENV: &str = "LONGLINE_LOG_MAX_BYTES";
 
 #[derive(Debug, Serialize)]
@@ -19,6 +19,13 @@ pub struct LogEntry {
     pub tool: String,
     pub cwd: String,
     pub command: String,
+    /// Raw target path of a file-tool write, as the tool supplied it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// `path` joined with `cwd`, `~`-expanded, `..`-normalized and
+    /// symlink-resolved.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_path: Option<String>,
     pub decision: Decision,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub original_decision: Option<Decision>,
@@ -27,6 +34,13 @@ pub struct LogEntry {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub judge: Option<longline::ai_judge::JudgeReport>,
     pub matched_rules: Vec<String>,
+    /// Every rule and classifier id that matched a shell command, with the
+    /// leaf it matched on and that leaf's bucket.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub findings: Vec<longline::policy::Finding>,
+    /// The leaf or pipeline whose result became `decision`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deciding_leaf: Option<longline::policy::DecidingLeaf>,
     pub reason: Option<String>,
     pub parse_ok: bool,
     pub session_id: Option<String>,
@@ -58,7 +72,7 @@ fn configured_max_log_file_bytes() -> u64 {
         .unwrap_or(DEFAULT_MAX_LOG_FILE_BYTES)
 }
 
-fn rotated_log_path(path: &Path, index: usize) -> PathBuf {
+pub(crate) fn rotated_log_path(path: &Path, index: usize) -> PathBuf {
     let mut name = path.as_os_str().to_os_string();
     name.push(format!(".{index}"));
     PathBuf::from(name)
@@ -181,11 +195,15 @@ pub fn make_entry(
         tool: tool.to_string(),
         cwd: cwd.to_string(),
         command: command.to_string(),
+        path: None,
+        resolved_path: None,
         decision,
         original_decision: None,
         overridden: false,
         judge: None,
         matched_rules,
+        findings: Vec::new(),
+        deciding_leaf: None,
         reason,
         parse_ok,
         session_id,
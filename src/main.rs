@@ -1,9 +1,15 @@
 mod adapters;
+mod audit;
 mod cli;
+mod doctor;
 mod evaluator;
+mod install;
+mod lint;
 mod logger;
 mod output;
+mod replay;
 mod runtime;
+mod suites;
 
 fn main() {
     std::process::exit(cli::run());
@@ -15,14 +15,17 @@ fn source_color(s: policy::RuleSource) -> Color {
     }
 }
 
-/// Create a colored Cell for a RuleSource value.
-fn source_cell(s: policy::RuleSource) -> Cell {
-    let label = match s {
+fn source_label(s: policy::RuleSource) -> &'static str {
+    match s {
         policy::RuleSource::BuiltIn => "builtin",
         policy::RuleSource::Global => "global",
         policy::RuleSource::Project => "project",
-    };
-    Cell::new(label).fg(source_color(s))
+    }
+}
+
+/// Create a colored Cell for a RuleSource value.
+fn source_cell(s: policy::RuleSource) -> Cell {
+    Cell::new(source_label(s)).fg(source_color(s))
 }
 
 /// Map a Decision to its display color.
@@ -102,7 +105,20 @@ pub fn rules_table_verbose(rules: &[&policy::Rule]) -> Table {
 
 /// Return (match_type, pattern) for a matcher, used in the verbose table.
 fn format_matcher(matcher: &policy::Matcher) -> (String, String) {
+    let nested = |children: &[policy::Matcher], joiner: &str| {
+        children
+            .iter()
+            .map(|child| {
+                let (match_type, pattern) = format_matcher(child);
+                format!("{match_type}[{pattern}]")
+            })
+            .collect::<Vec<_>>()
+            .join(joiner)
+    };
     match matcher {
+        policy::Matcher::All { all } => ("all".to_string(), nested(all, " AND ")),
+        policy::Matcher::Any { any } => ("any".to_string(), nested(any, " OR ")),
+        policy::Matcher::Not { not } => ("not".to_string(), nested(std::slice::from_ref(not), "")),
         policy::Matcher::Command {
             command,
             flags,
@@ -126,6 +142,32 @@ fn format_matcher(matcher: &policy::Matcher) -> (String, String) {
                 if !a.any_of.is_empty() {
                     parts.push(format!("args={{{}}}", a.any_of.join(", ")));
                 }
+                if !a.regex.is_empty() {
+                    let regexes: Vec<String> = a
+                        .regex
+                        .iter()
+                        .map(|r| format!("/{}/", r.as_str()))
+                        .collect();
+                    parts.push(format!("args_regex={{{}}}", regexes.join(", ")));
+                }
+                if !a.positional.is_empty() {
+                    let positions: Vec<String> = a
+                        .positional
+                        .iter()
+                        .map(|(index, pattern)| {
+                            format!("{index}={}", format_value_pattern(pattern))
+                        })
+                        .collect();
+                    parts.push(format!("positional={{{}}}", positions.join(", ")));
+                }
+                if !a.option_values.is_empty() {
+                    let options: Vec<String> = a
+                        .option_values
+                        .iter()
+                        .map(|(flag, pattern)| format!("{flag}={}", format_value_pattern(pattern)))
+                        .collect();
+                    parts.push(format!("option_values={{{}}}", options.join(", ")));
+                }
             }
             if let Some(e) = env {
                 parts.push(format!("env={{{}}}", e.any_of.join(", ")));
@@ -178,6 +220,57 @@ fn format_matcher(matcher: &policy::Matcher) -> (String, String) {
             }
             ("redirect".to_string(), parts.join(" "))
         }
+        policy::Matcher::Path { path } => {
+            let mut parts = vec![format!("target={}", format_string_or_list(&path.target))];
+            if !path.tools.is_empty() {
+                let tools: Vec<String> = path.tools.iter().map(|t| t.to_string()).collect();
+                parts.push(format!("tools={{{}}}", tools.join(", ")));
+            }
+            ("path".to_string(), parts.join(" "))
+        }
+        policy::Matcher::Mcp { mcp } => {
+            let mut parts = Vec::new();
+            if let Some(server) = &mcp.server {
+                parts.push(format!("server={}", format_string_or_list(server)));
+            }
+            if let Some(tool) = &mcp.tool {
+                parts.push(format!("tool={}", format_string_or_list(tool)));
+            }
+            if !mcp.input.is_empty() {
+                let conditions: Vec<String> = mcp.input.iter().map(format_mcp_condition).collect();
+                parts.push(format!("input={{{}}}", conditions.join(", ")));
+            }
+            ("mcp".to_string(), parts.join(" "))
+        }
+        policy::Matcher::Url { url } => {
+            let mut parts = Vec::new();
+            if let Some(scheme) = &url.scheme {
+                parts.push(format!("scheme={}", format_string_or_list(scheme)));
+            }
+            if let Some(host) = &url.host {
+                parts.push(format!("host={}", format_string_or_list(host)));
+            }
+            if let Some(port) = &url.port {
+                let ports = match port {
+                    policy::PortList::Single(p) => p.to_string(),
+                    policy::PortList::List { any_of } => {
+                        let ports: Vec<String> = any_of.iter().map(u16::to_string).collect();
+                        format!("{{{}}}", ports.join(", "))
+                    }
+                };
+                parts.push(format!("port={ports}"));
+            }
+            if let Some(path) = &url.path {
+                parts.push(format!("path={}", format_string_or_list(path)));
+            }
+            if let Some(query) = &url.query {
+                parts.push(format!("query={}", format_string_or_list(query)));
+            }
+            if parts.is_empty() {
+                parts.push("*".to_string());
+            }
+            ("url".to_string(), parts.join(" "))
+        }
         policy::Matcher::GitConfig { git_config } => (
             "git_config".to_string(),
             format!(
@@ -196,6 +289,32 @@ fn format_matcher(matcher: &policy::Matcher) -> (String, String) {
     }
 }
 
+/// A glob as written, a regex as `/expr/`.
+fn format_value_pattern(pattern: &policy::ValuePattern) -> String {
+    match pattern {
+        policy::ValuePattern::Glob(glob) => glob.clone(),
+        policy::ValuePattern::Regex(regex) => format!("/{}/", regex.regex.as_str()),
+    }
+}
+
+/// Format one mcp input condition as "pointer op=value ...".
+fn format_mcp_condition(condition: &policy::McpInputCondition) -> String {
+    let mut parts = vec![condition.pointer.as_str().to_string()];
+    if let Some(exists) = condition.exists {
+        parts.push(format!("exists={exists}"));
+    }
+    if let Some(equals) = &condition.equals {
+        parts.push(format!("equals={equals}"));
+    }
+    if let Some(matches) = &condition.matches {
+        parts.push(format!("matches={}", format_string_or_list(matches)));
+    }
+    if let Some(contains) = &condition.contains {
+        parts.push(format!("contains={}", format_string_or_list(contains)));
+    }
+    parts.join(" ")
+}
+
 /// Format a StringOrList as "value" or "{a, b, c}".
 fn format_string_or_list(sol: &policy::StringOrList) -> String {
     match sol {
@@ -308,6 +427,13 @@ pub fn print_allowlist_summary(commands: &[policy::AllowlistEntry]) {
     println!("Allowlist: {}{}", display.join(", "), suffix);
 }
 
+/// Print allowlisted path globs (exempt from `ask` path rules), if any.
+pub fn print_path_allowlist(paths: &[String]) {
+    if !paths.is_empty() {
+        println!("Allowlisted paths: {}", paths.join(", "));
+    }
+}
+
 /// Print rules grouped by safety level (Critical, High, Strict) with bold section headers.
 pub fn print_rules_grouped_by_level(rules: &[&policy::Rule], verbose: bool) {
     for level_val in &[
@@ -405,6 +531,447 @@ pub fn print_profiles_json(
     println!("{}", serde_json::to_string_pretty(&out).unwrap());
 }
 
+/// Print a `longline audit` report: a summary line, then one table per
+/// breakdown.
+pub fn print_audit_report(report: &crate::audit::AuditReport) {
+    if report.files.is_empty() {
+        println!("No decision logs found.");
+        return;
+    }
+    println!(
+        "{} entries from {} log file(s): {} ask, {} deny{}",
+        report.entries,
+        report.files.len(),
+        report.ask,
+        report.deny,
+        if report.skipped_lines > 0 {
+            format!(" ({} unreadable lines skipped)", report.skipped_lines)
+        } else {
+            String::new()
+        }
+    );
+    let sections = [
+        ("COMMAND", &report.by_family),
+        ("PROJECT", &report.by_project),
+        ("RULE", &report.by_rule),
+        ("PROFILE", &report.by_profile),
+        ("RUNTIME", &report.by_runtime),
+    ];
+    for (label, tallies) in sections {
+        if tallies.is_empty() {
+            continue;
+        }
+        println!();
+        println!("{}", audit_table(label, tallies));
+    }
+}
+
+/// Print a `longline replay` report: a summary line, then a table of the
+/// commands whose decision flips.
+pub fn print_replay_report(report: &crate::replay::ReplayReport) {
+    if report.files.is_empty() {
+        println!("No decision logs found.");
+        return;
+    }
+    println!(
+        "{} shell entries replayed from {} log file(s): {} unchanged, {} tightened, {} loosened{}",
+        report.replayed,
+        report.files.len(),
+        report.unchanged,
+        report.tightened,
+        report.loosened,
+        if report.skipped_lines > 0 {
+            format!(" ({} unreadable lines skipped)", report.skipped_lines)
+        } else {
+            String::new()
+        }
+    );
+    if report.flips.is_empty() {
+        return;
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("WAS").add_attribute(Attribute::Bold),
+            Cell::new("NOW").add_attribute(Attribute::Bold),
+            Cell::new("COUNT").add_attribute(Attribute::Bold),
+            Cell::new("RULE").add_attribute(Attribute::Bold),
+            Cell::new("COMMAND").add_attribute(Attribute::Bold),
+            Cell::new("CWD").add_attribute(Attribute::Bold),
+        ]);
+    for flip in &report.flips {
+        table.add_row(vec![
+            decision_cell(flip.from),
+            decision_cell(flip.to),
+            Cell::new(flip.count),
+            Cell::new(flip.rule_id.as_deref().unwrap_or("(default)")),
+            Cell::new(&flip.command),
+            Cell::new(&flip.cwd),
+        ]);
+    }
+    println!();
+    println!("{table}");
+}
+
+/// Print `longline suggest` results: a summary line, then the snippet.
+pub fn print_suggestions(
+    root: &std::path::Path,
+    asked: usize,
+    suggestions: &[policy::Suggestion],
+    snippet: &str,
+) {
+    if suggestions.is_empty() {
+        println!(
+            "No allowlist suggestions for {} ({asked} asked command(s) in the logs).",
+            root.display()
+        );
+        return;
+    }
+    println!(
+        "{} suggestion(s) from {asked} asked command(s) in {}.",
+        suggestions.len(),
+        root.display()
+    );
+    println!(
+        "Review, then paste into {}:",
+        root.join(".claude").join("longline.yaml").display()
+    );
+    println!();
+    print!("{snippet}");
+}
+
+/// Print `longline test` results: every failing example, then a summary.
+pub fn print_example_results(results: &[policy::ExampleResult]) {
+    if results.is_empty() {
+        println!("No examples found.");
+        return;
+    }
+    for r in results.iter().filter(|r| !r.passed()) {
+        let owner = match r.owner {
+            policy::ExampleOwner::Rule => "rule",
+            policy::ExampleOwner::Allowlist => "allowlist",
+        };
+        let expected = if r.expect_match {
+            "to match"
+        } else {
+            "not to match"
+        };
+        println!(
+            "{} {owner} {} ({}): expected `{}` {expected}",
+            yansi::Paint::red("FAIL").bold(),
+            r.id,
+            source_label(r.source),
+            r.example
+        );
+        if let Some(error) = &r.error {
+            println!("       {error}");
+        }
+    }
+    let failed = results.iter().filter(|r| !r.passed()).count();
+    println!(
+        "{} example(s) checked: {} passed, {failed} failed",
+        results.len(),
+        results.len() - failed
+    );
+}
+
+/// Print each failing suite case, then a pass/fail line per suite file and
+/// a total.
+pub fn print_suite_reports(reports: &[crate::suites::SuiteReport]) {
+    for report in reports {
+        if let Some(error) = &report.error {
+            println!("{} {error}", yansi::Paint::red("ERROR").bold());
+        }
+        for case in report.cases.iter().filter(|c| !c.passed()) {
+            println!(
+                "{} {} [{}]: {}",
+                yansi::Paint::red("FAIL").bold(),
+                report.file,
+                case.id,
+                case.subject
+            );
+            match &case.error {
+                Some(error) => println!("       {error}"),
+                None => println!("       {}", crate::suites::mismatch(case)),
+            }
+        }
+    }
+    let mut total = 0;
+    let mut failed = 0;
+    for report in reports {
+        let status = if report.passed() {
+            yansi::Paint::green("ok")
+        } else {
+            yansi::Paint::red("FAILED")
+        };
+        let suite_failed = report.failed();
+        println!(
+            "{status:>6}  {}: {} passed, {suite_failed} failed",
+            report.file,
+            report.cases.len() - suite_failed
+        );
+        total += report.cases.len();
+        failed += suite_failed;
+    }
+    println!(
+        "{total} case(s) in {} file(s): {} passed, {failed} failed",
+        reports.len(),
+        total - failed
+    );
+}
+
+/// Print lint issues as `file:line: severity[code]: message`, then a
+/// summary.
+pub fn print_lint_issues(issues: &[crate::lint::Issue]) {
+    use crate::lint::Severity;
+
+    for issue in issues {
+        let location = match (&issue.file, issue.line) {
+            (Some(file), Some(line)) => format!("{file}:{line}: "),
+            (Some(file), None) => format!("{file}: "),
+            _ => String::new(),
+        };
+        let severity = match issue.severity {
+            Severity::Error => yansi::Paint::red("error").bold(),
+            Severity::Warning => yansi::Paint::yellow("warning").bold(),
+        };
+        println!("{location}{severity}[{}]: {}", issue.code, issue.message);
+    }
+    let errors = issues
+        .iter()
+        .filter(|i| i.severity == Severity::Error)
+        .count();
+    if issues.is_empty() {
+        println!("No issues found.");
+    } else {
+        println!("{errors} error(s), {} warning(s)", issues.len() - errors);
+    }
+}
+
+/// Print `longline doctor` checks grouped by section, each non-passing
+/// check followed by its fix, then a one-line summary.
+pub fn print_doctor_report(checks: &[crate::doctor::Check]) {
+    use crate::doctor::Status;
+
+    let mut section = "";
+    for check in checks {
+        if check.section != section {
+            if !section.is_empty() {
+                println!();
+            }
+            section = check.section;
+            println!("{}", yansi::Paint::new(section.to_uppercase()).bold());
+        }
+        let status = match check.status {
+            Status::Pass => yansi::Paint::green("PASS"),
+            Status::Warn => yansi::Paint::yellow("WARN"),
+            Status::Fail => yansi::Paint::red("FAIL"),
+        };
+        println!("  {} {}: {}", status.bold(), check.name, check.detail);
+        if let Some(fix) = &check.fix {
+            println!("       fix: {fix}");
+        }
+    }
+    let count = |status: Status| checks.iter().filter(|c| c.status == status).count();
+    println!();
+    println!(
+        "{} passed, {} warning(s), {} failed",
+        count(Status::Pass),
+        count(Status::Warn),
+        count(Status::Fail)
+    );
+}
+
+fn audit_table(label: &str, tallies: &[crate::audit::Tally]) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new(label).add_attribute(Attribute::Bold),
+            Cell::new("ASK").add_attribute(Attribute::Bold),
+            Cell::new("DENY").add_attribute(Attribute::Bold),
+            Cell::new("TOTAL").add_attribute(Attribute::Bold),
+        ]);
+    for tally in tallies {
+        table.add_row(vec![
+            Cell::new(&tally.key),
+            Cell::new(tally.ask).fg(decision_color(Decision::Ask)),
+            Cell::new(tally.deny).fg(decision_color(Decision::Deny)),
+            Cell::new(tally.total()),
+        ]);
+    }
+    table
+}
+
+/// Print a `longline explain` trace as an indented tree.
+pub fn print_explanation(e: &policy::Explanation) {
+    println!("Command:  {}", e.command);
+    println!(
+        "Config:   safety={} trust={} default={}",
+        e.safety_level, e.trust_level, e.default_decision
+    );
+
+    println!();
+    println!("Statement");
+    let mut lines = Vec::new();
+    push_statement_tree(&mut lines, &e.statement, "", "", true);
+    for line in lines {
+        println!("{line}");
+    }
+
+    println!();
+    println!("Pipelines");
+    if e.pipelines.is_empty() {
+        println!("  (none)");
+    }
+    for pipe in &e.pipelines {
+        let extra = if pipe.is_extra { " (extracted)" } else { "" };
+        println!("  {}{extra}", pipe.text);
+        println!("     rules:       {}", format_rule_hits(&pipe.rules));
+    }
+
+    println!();
+    println!("Leaves");
+    for (i, leaf) in e.leaves.iter().enumerate() {
+        println!("  {}. [{}] {}", i + 1, leaf.bucket, leaf.text);
+        println!("     rules:       {}", format_rule_hits(&leaf.rules));
+        println!(
+            "     allowlist:   {}",
+            leaf.allowlist.as_deref().unwrap_or("(none)")
+        );
+        let classifiers: Vec<String> = leaf
+            .classifiers
+            .iter()
+            .map(|c| format!("{} \u{2192} {} ({})", c.name, c.decision, c.reason))
+            .collect();
+        println!("     classifiers: {}", join_or_none(&classifiers));
+        println!("     result:      {}", format_policy_result(&leaf.result));
+        let covered_by: Vec<String> = leaf.covered_by.iter().map(|c| c.to_string()).collect();
+        println!("     covered by:  {}", join_or_none(&covered_by));
+    }
+
+    println!();
+    let gate = if !e.gate.applied {
+        "skipped (a leaf or pipeline rule already decided)".to_string()
+    } else if e.gate.all_covered {
+        "applied; every leaf covered".to_string()
+    } else {
+        format!(
+            "applied; uncovered leaf \u{2192} {}: {}",
+            e.default_decision,
+            e.gate.uncovered_reason.as_deref().unwrap_or_default()
+        )
+    };
+    println!("Gate:     {gate}");
+    println!("Decision: {}", format_policy_result(&e.result));
+}
+
+fn push_statement_tree(
+    lines: &mut Vec<String>,
+    node: &policy::StatementNode,
+    label_prefix: &str,
+    indent: &str,
+    last: bool,
+) {
+    use policy::StatementNode as N;
+    let label = match node {
+        N::Command { text, .. } => format!("command: {text}"),
+        N::Pipeline { negated: true, .. } => "pipeline (negated)".to_string(),
+        N::Pipeline { .. } => "pipeline".to_string(),
+        N::List { .. } => "list".to_string(),
+        N::Subshell { .. } => "subshell".to_string(),
+        N::CommandSubstitution { .. } => "command substitution".to_string(),
+        N::Opaque { text } => format!("opaque: {text}"),
+        N::Empty => "empty".to_string(),
+    };
+    let branch = if last {
+        "\u{2514}\u{2500} "
+    } else {
+        "\u{251c}\u{2500} "
+    };
+    lines.push(format!("{indent}{branch}{label_prefix}{label}"));
+
+    let children: Vec<(String, &policy::StatementNode)> = match node {
+        N::Command { substitutions, .. } => substitutions
+            .iter()
+            .map(|s| ("$(\u{2026}) ".to_string(), s))
+            .collect(),
+        N::Pipeline { stages, .. } => stages.iter().map(|s| (String::new(), s)).collect(),
+        N::List { items } => items
+            .iter()
+            .map(|item| {
+                let op = item.op.map(|op| format!("{op} ")).unwrap_or_default();
+                (op, &item.statement)
+            })
+            .collect(),
+        N::Subshell { body } | N::CommandSubstitution { body } => {
+            vec![(String::new(), body.as_ref())]
+        }
+        N::Opaque { .. } | N::Empty => vec![],
+    };
+    let child_indent = format!("{indent}{}", if last { "   " } else { "\u{2502}  " });
+    for (i, (prefix, child)) in children.iter().enumerate() {
+        push_statement_tree(lines, child, prefix, &child_indent, i + 1 == children.len());
+    }
+}
+
+fn format_rule_hits(hits: &[policy::RuleHit]) -> String {
+    let hits: Vec<String> = hits
+        .iter()
+        .map(|h| {
+            let inactive = if h.active {
+                ""
+            } else if !h.applies {
+                ", inactive: `when` doesn't hold"
+            } else {
+                ", inactive: above safety level"
+            };
+            let source = match h.source {
+                policy::RuleSource::BuiltIn => "builtin",
+                policy::RuleSource::Global => "global",
+                policy::RuleSource::Project => "project",
+            };
+            format!(
+                "{} \u{2192} {} ({}, {source}{inactive})",
+                h.id, h.decision, h.level
+            )
+        })
+        .collect();
+    join_or_none(&hits)
+}
+
+fn format_policy_result(result: &longline::domain::PolicyResult) -> String {
+    let decision = yansi::Paint::new(result.decision.to_string()).fg(match result.decision {
+        Decision::Allow => yansi::Color::Green,
+        Decision::Ask => yansi::Color::Yellow,
+        Decision::Deny => yansi::Color::Red,
+    });
+    let rule = result
+        .rule_id
+        .as_deref()
+        .map(|id| format!(" [{id}]"))
+        .unwrap_or_default();
+    if result.reason.is_empty() {
+        format!("{decision}{rule}")
+    } else {
+        format!("{decision}{rule} {}", result.reason)
+    }
+}
+
+fn join_or_none(items: &[String]) -> String {
+    if items.is_empty() {
+        "(none)".to_string()
+    } else {
+        items.join("; ")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::format_matcher;
@@ -462,4 +1029,37 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn verbose_composite_matcher_formats_nested_matchers() {
+        let matcher: Matcher = serde_norway::from_str(
+            "all:\n  - command: kubectl\n  - not: { redirect: { op: \">\" } }\n",
+        )
+        .unwrap();
+        assert_eq!(
+            format_matcher(&matcher),
+            (
+                "all".to_string(),
+                "command[cmd=kubectl] AND not[redirect[op=>]]".to_string(),
+            )
+        );
+    }
+
+    #[test]
+    fn verbose_command_matcher_formats_regex_positional_and_option_values() {
+        let matcher: Matcher = serde_norway::from_str(
+            r#"
+command: kubectl
+args:
+  regex: ["^prod-"]
+  positional: { 0: delete, 2: { regex: "^ns-[0-9]+$" } }
+  option_values: { --namespace: "prod*" }
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            format_matcher(&matcher).1,
+            "cmd=kubectl args_regex={/^prod-/} positional={0=delete, 2=/^ns-[0-9]+$/} option_values={--namespace=prod*}"
+        );
+    }
 }
@@ -1,11 +1,18 @@
 //! Functions to convert tree-sitter nodes to Statement AST.
 
+use std::collections::HashMap;
+
 use tree_sitter::Node;
 
 use super::helpers::{
-    classify_arg_node, node_text, parse_assignment, parse_redirect, resolve_node_text,
+    classify_arg_node, heredoc_body_text, node_text, parse_assignment, parse_heredoc_delimiter,
+    parse_redirect, resolve_node_text,
+};
+use super::wrappers::MAX_UNWRAP_DEPTH;
+use super::{
+    Arg, ArgMeta, Assignment, HereDoc, List, ListOp, Pipeline, Redirect, RedirectOp, SimpleCommand,
+    Statement,
 };
-use super::{Arg, ArgMeta, Assignment, List, ListOp, Pipeline, Redirect, SimpleCommand, Statement};
 
 /// Convert an argv-position tree-sitter node into an `Arg` carrying both the
 /// resolved text and an `ArgMeta` classification.
@@ -22,8 +29,13 @@ pub(crate) fn convert_arg_node(node: Node, source: &str) -> Arg {
 }
 
 /// Handle the top-level "program" node.
+///
+/// Functions defined at the top level are recorded as they are reached, and
+/// later calls to them are replaced by their bodies (see
+/// `resolve_function_calls`).
 pub fn convert_program(node: Node, source: &str) -> Statement {
     let mut children: Vec<Statement> = Vec::new();
+    let mut functions: HashMap<String, Statement> = HashMap::new();
 
     let mut cursor = node.walk();
     let named: Vec<Node> = node.named_children(&mut cursor).collect();
@@ -36,14 +48,29 @@ pub fn convert_program(node: Node, source: &str) -> Statement {
             // This handles tree-sitter parse failures from backticks inside
             // double-quoted strings (e.g. grep -oE "Host\(`[^`]+`\)") where
             // the parser splits the command at the backtick boundary.
-            if let Some(prev) = children.last_mut() {
+            //
+            // A heredoc the grammar gave up on (`cat <<EOF; rm x`) is not
+            // merged: the commands after the delimiter would vanish into
+            // the argument text, so it fails closed instead.
+            let mut cursor = child.walk();
+            let has_heredoc = child
+                .children(&mut cursor)
+                .any(|c| c.kind() == "heredoc_start");
+            if has_heredoc {
+                children.push(Statement::Opaque(node_text(child, source).to_string()));
+            } else if let Some(prev) = children.last_mut() {
                 merge_error_into_statement(prev, child, source);
             } else {
                 // No preceding statement to merge into
                 children.push(Statement::Opaque(node_text(child, source).to_string()));
             }
         } else {
-            children.push(convert_node(child, source));
+            if let Some((name, body)) = leading_function_definition(child, source) {
+                functions.insert(name, body);
+            }
+            let mut stmt = convert_node(child, source);
+            resolve_function_calls(&mut stmt, &functions, &mut Vec::new());
+            children.push(stmt);
         }
         i += 1;
     }
@@ -61,6 +88,226 @@ pub fn convert_program(node: Node, source: &str) -> Statement {
     }
 }
 
+/// The function a top-level statement defines unconditionally: a
+/// `function_definition` itself, or one leading a `&&`/`||` list. Definitions
+/// nested in conditionals, loops or subshells may not be in effect when a
+/// later call runs, so they are not recorded.
+fn leading_function_definition(node: Node, source: &str) -> Option<(String, Statement)> {
+    match node.kind() {
+        "function_definition" => {
+            let name = node.child_by_field_name("name")?;
+            Some((
+                node_text(name, source).to_string(),
+                convert_function_definition(node, source),
+            ))
+        }
+        "list" => leading_function_definition(node.named_child(0)?, source),
+        _ => None,
+    }
+}
+
+/// Replace calls to recorded functions with the function body, so
+/// `f() { git status; }; f` is judged as `git status` rather than an unknown
+/// `f`. The call's arguments are bound to the positional parameters the
+/// body reads (`f() { cat "$1"; }; f x` is `cat x`), redirects on the call
+/// move onto the body's leaves and substitutions in its arguments are kept.
+/// A body that can rebind its parameters (`shift`, `set`) is replaced by an
+/// opaque call instead. A function already being expanded, or one nested
+/// deeper than `MAX_UNWRAP_DEPTH`, stays an unresolved call.
+fn resolve_function_calls(
+    stmt: &mut Statement,
+    functions: &HashMap<String, Statement>,
+    active: &mut Vec<String>,
+) {
+    match stmt {
+        Statement::SimpleCommand(cmd) => {
+            for sub in &mut cmd.embedded_substitutions {
+                resolve_function_calls(sub, functions, active);
+            }
+            let Some(name) = cmd.name.as_deref() else {
+                return;
+            };
+            let Some(body) = functions.get(name) else {
+                return;
+            };
+            if active.len() >= MAX_UNWRAP_DEPTH || active.iter().any(|a| a == name) {
+                return;
+            }
+            let mut body = body.clone();
+            if rebinds_positional(&body) {
+                let text = std::iter::once(name)
+                    .chain(cmd.argv.iter().map(|arg| arg.text.as_str()))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let mut parts = vec![Statement::Opaque(text)];
+                parts.append(&mut cmd.embedded_substitutions);
+                *stmt = wrap_as_list(parts);
+                return;
+            }
+            bind_positional(&mut body, &cmd.argv);
+            active.push(name.to_string());
+            resolve_function_calls(&mut body, functions, active);
+            active.pop();
+            inject_redirects_into_leaves(&mut body, &cmd.redirects);
+            let mut parts = vec![body];
+            parts.append(&mut cmd.embedded_substitutions);
+            *stmt = wrap_as_list(parts);
+        }
+        Statement::Pipeline(p) => {
+            for stage in &mut p.stages {
+                resolve_function_calls(stage, functions, active);
+            }
+        }
+        Statement::List(l) => {
+            resolve_function_calls(&mut l.first, functions, active);
+            for (_, s) in &mut l.rest {
+                resolve_function_calls(s, functions, active);
+            }
+        }
+        Statement::Subshell(inner) | Statement::CommandSubstitution(inner) => {
+            resolve_function_calls(inner, functions, active);
+        }
+        Statement::Opaque(_) | Statement::Empty => {}
+    }
+}
+
+/// Commands that reassign the positional parameters of the function body
+/// they run in.
+const POSITIONAL_WRITERS: &[&str] = &["shift", "set", "eval", "source", "."];
+
+/// Whether `body` runs a command that can change what `$1`... read.
+fn rebinds_positional(body: &Statement) -> bool {
+    super::flatten(body).into_iter().any(|leaf| match leaf {
+        Statement::SimpleCommand(cmd) => cmd
+            .name
+            .as_deref()
+            .is_some_and(|name| POSITIONAL_WRITERS.contains(&name)),
+        _ => false,
+    })
+}
+
+/// Substitute a call's arguments for the positional parameters in `body`.
+/// A word that is exactly `$@` or `$*` becomes the arguments themselves (a
+/// command name too: `"$@"` runs them); `$1`, `${12}` and `$#` are spliced
+/// into the words that read them.
+fn bind_positional(body: &mut Statement, args: &[Arg]) {
+    match body {
+        Statement::SimpleCommand(cmd) => {
+            for sub in &mut cmd.embedded_substitutions {
+                bind_positional(sub, args);
+            }
+            let mut argv = Vec::with_capacity(cmd.argv.len());
+            if let Some(name) = cmd.name.take() {
+                match (is_all_args(&name), args.split_first()) {
+                    (true, Some((first, rest))) => {
+                        cmd.name = Some(first.text.clone());
+                        argv.extend_from_slice(rest);
+                    }
+                    _ => cmd.name = Some(splice_positional(&name, args)),
+                }
+            }
+            for arg in std::mem::take(&mut cmd.argv) {
+                match positional_index(&arg.text) {
+                    _ if is_all_args(&arg.text) => argv.extend_from_slice(args),
+                    Some(i) => argv.push(args.get(i).cloned().unwrap_or(Arg {
+                        text: String::new(),
+                        meta: ArgMeta::SafeString,
+                    })),
+                    None => argv.push(Arg {
+                        text: splice_positional(&arg.text, args),
+                        meta: arg.meta,
+                    }),
+                }
+            }
+            cmd.argv = argv;
+            for redirect in &mut cmd.redirects {
+                redirect.target = splice_positional(&redirect.target, args);
+            }
+            for assignment in &mut cmd.assignments {
+                assignment.value = splice_positional(&assignment.value, args);
+            }
+        }
+        Statement::Pipeline(p) => {
+            for stage in &mut p.stages {
+                bind_positional(stage, args);
+            }
+        }
+        Statement::List(l) => {
+            bind_positional(&mut l.first, args);
+            for (_, s) in &mut l.rest {
+                bind_positional(s, args);
+            }
+        }
+        Statement::Subshell(inner) | Statement::CommandSubstitution(inner) => {
+            bind_positional(inner, args);
+        }
+        Statement::Opaque(_) | Statement::Empty => {}
+    }
+}
+
+fn is_all_args(word: &str) -> bool {
+    matches!(word, "$@" | "$*" | "${@}" | "${*}")
+}
+
+/// The argument index a word that is exactly `$N` or `${N}` reads.
+fn positional_index(word: &str) -> Option<usize> {
+    let digits = word.strip_prefix('$')?;
+    let digits = match digits.strip_prefix('{') {
+        Some(braced) => braced.strip_suffix('}')?,
+        None if digits.len() == 1 => digits,
+        None => return None,
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse::<usize>().ok()?.checked_sub(1)
+}
+
+/// `text` with every positional expansion replaced by the argument text it
+/// reads; `$@` and `$*` join them with spaces.
+fn splice_positional(text: &str, args: &[Arg]) -> String {
+    let joined = || {
+        args.iter()
+            .map(|a| a.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find('$') {
+        out.push_str(&rest[..at]);
+        let after = &rest[at + 1..];
+        let (name, len) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            },
+            None => match after.chars().next() {
+                Some(c @ ('1'..='9' | '@' | '*' | '#')) => (&after[..c.len_utf8()], 1),
+                _ => ("", 0),
+            },
+        };
+        let value = match name {
+            "@" | "*" => Some(joined()),
+            "#" => Some(args.len().to_string()),
+            _ => positional_index(&format!("${{{name}}}"))
+                .map(|i| args.get(i).map(|a| a.text.clone()).unwrap_or_default()),
+        };
+        match value {
+            Some(value) if len > 0 => {
+                out.push_str(&value);
+                rest = &after[len..];
+            }
+            _ => {
+                out.push('$');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
 /// Merge an ERROR node's text into the last SimpleCommand of a statement.
 /// This recovers command identity when tree-sitter fails on argument content
 /// (e.g. backticks in regex patterns) but successfully parsed the command name.
@@ -144,6 +391,7 @@ fn convert_command(node: Node, source: &str) -> Statement {
     let mut redirects: Vec<Redirect> = Vec::new();
     let mut assignments: Vec<Assignment> = Vec::new();
     let mut embedded_substitutions: Vec<Statement> = Vec::new();
+    let mut continuations: Vec<Continuation> = Vec::new();
 
     let mut cursor = node.walk();
     for child in node.named_children(&mut cursor) {
@@ -169,7 +417,15 @@ fn convert_command(node: Node, source: &str) -> Statement {
                 assignments.push(assignment);
                 embedded_substitutions.extend(subs);
             }
-            "file_redirect" | "heredoc_redirect" | "herestring_redirect" => {
+            "heredoc_redirect" => {
+                let Some(heredoc) = convert_heredoc_redirect(child, source) else {
+                    return Statement::Opaque(node_text(node, source).to_string());
+                };
+                redirects.extend(heredoc.redirects);
+                embedded_substitutions.extend(heredoc.substitutions);
+                continuations.extend(heredoc.continuations);
+            }
+            "file_redirect" | "herestring_redirect" => {
                 let (redirect, subs) = parse_redirect(child, source);
                 redirects.push(redirect);
                 embedded_substitutions.extend(subs);
@@ -182,13 +438,15 @@ fn convert_command(node: Node, source: &str) -> Statement {
         }
     }
 
-    Statement::SimpleCommand(SimpleCommand {
+    let stmt = Statement::SimpleCommand(SimpleCommand {
         name,
         argv,
         redirects,
         assignments,
         embedded_substitutions,
-    })
+        resolution: None,
+    });
+    apply_continuations(stmt, continuations)
 }
 
 /// Convert a "pipeline" node.
@@ -267,11 +525,20 @@ fn convert_redirected_statement(node: Node, source: &str) -> Statement {
     let mut body: Option<Statement> = None;
     let mut redirects: Vec<Redirect> = Vec::new();
     let mut redirect_substitutions: Vec<Statement> = Vec::new();
+    let mut continuations: Vec<Continuation> = Vec::new();
 
     let mut cursor = node.walk();
     for child in node.named_children(&mut cursor) {
         match child.kind() {
-            "file_redirect" | "heredoc_redirect" | "herestring_redirect" => {
+            "heredoc_redirect" => {
+                let Some(heredoc) = convert_heredoc_redirect(child, source) else {
+                    return Statement::Opaque(node_text(node, source).to_string());
+                };
+                redirects.extend(heredoc.redirects);
+                redirect_substitutions.extend(heredoc.substitutions);
+                continuations.extend(heredoc.continuations);
+            }
+            "file_redirect" | "herestring_redirect" => {
                 let (redirect, subs) = parse_redirect(child, source);
                 redirects.push(redirect);
                 redirect_substitutions.extend(subs);
@@ -303,11 +570,168 @@ fn convert_redirected_statement(node: Node, source: &str) -> Statement {
         if !redirect_substitutions.is_empty() {
             let mut parts = vec![stmt];
             parts.extend(redirect_substitutions);
-            return wrap_as_list(parts);
+            stmt = wrap_as_list(parts);
         }
     }
 
-    stmt
+    apply_continuations(stmt, continuations)
+}
+
+/// A command tree-sitter nested inside a "heredoc_redirect" because it
+/// follows the delimiter on the same line, and how it joins the command the
+/// heredoc belongs to.
+enum Continuation {
+    Pipe(Statement),
+    List(ListOp, Statement),
+}
+
+/// The pieces of a "heredoc_redirect" node.
+struct HeredocRedirect {
+    /// The heredoc itself, then any redirects written after its delimiter.
+    redirects: Vec<Redirect>,
+    substitutions: Vec<Statement>,
+    continuations: Vec<Continuation>,
+}
+
+/// Convert a "heredoc_redirect" node. Besides the heredoc, tree-sitter nests
+/// everything written after the delimiter on its line in this node: more
+/// redirects (`<<EOF > out`), a pipeline (`<<EOF | bash`) or a list
+/// (`<<EOF && rm x`). They come back as extra redirects and continuations so
+/// none of them escapes evaluation. Returns None for shapes the grammar does
+/// not parse reliably, such as `<<EOF; cmd` (the rest of the input is
+/// swallowed into the body) or `<<EOF > out | cmd` (the pipe lands in an
+/// ERROR inside the file redirect); callers fail closed with an Opaque
+/// statement.
+fn convert_heredoc_redirect(node: Node, source: &str) -> Option<HeredocRedirect> {
+    if node.has_error() {
+        return None;
+    }
+    let mut fd: Option<u32> = None;
+    let mut strip_tabs = false;
+    let (mut start, mut body, mut end) = (None, None, None);
+    let mut redirects: Vec<Redirect> = Vec::new();
+    let mut substitutions: Vec<Statement> = Vec::new();
+    let mut continuations: Vec<Continuation> = Vec::new();
+    let mut pending_op: Option<ListOp> = None;
+
+    let child_count = node.child_count();
+    for i in 0..child_count as u32 {
+        let child = node.child(i).unwrap();
+        match child.kind() {
+            "file_descriptor" => fd = node_text(child, source).parse::<u32>().ok(),
+            "<<" => {}
+            "<<-" => strip_tabs = true,
+            "heredoc_start" => start = Some(child),
+            "heredoc_body" => body = Some(child),
+            "heredoc_end" => end = Some(child),
+            "file_redirect" | "herestring_redirect" => {
+                let (redirect, subs) = parse_redirect(child, source);
+                redirects.push(redirect);
+                substitutions.extend(subs);
+            }
+            "&&" => pending_op = Some(ListOp::And),
+            "||" => pending_op = Some(ListOp::Or),
+            "pipeline" if pending_op.is_none() => {
+                // `| rest`: the node holds the `|` token and the stages after it.
+                let mut cursor = child.walk();
+                let mut stages: Vec<Statement> = child
+                    .named_children(&mut cursor)
+                    .map(|stage| convert_node(stage, source))
+                    .collect();
+                let tail = match stages.len() {
+                    0 => return None,
+                    1 => stages.pop().unwrap(),
+                    _ => Statement::Pipeline(Pipeline {
+                        stages,
+                        negated: false,
+                    }),
+                };
+                continuations.push(Continuation::Pipe(tail));
+            }
+            _ if child.is_named() => {
+                let op = pending_op.take()?;
+                continuations.push(Continuation::List(op, convert_node(child, source)));
+            }
+            _ => return None,
+        }
+    }
+
+    let (start, end) = (start?, end?);
+    let (delimiter, quoted) = parse_heredoc_delimiter(node_text(start, source));
+    if node_text(end, source) != delimiter || pending_op.is_some() {
+        return None;
+    }
+    let mut body_substitutions: Vec<Statement> = Vec::new();
+    if let (Some(body), false) = (body, quoted) {
+        collect_descendant_substitutions(body, source, &mut body_substitutions);
+    }
+    substitutions.splice(0..0, body_substitutions.iter().cloned());
+    redirects.insert(
+        0,
+        Redirect {
+            fd,
+            op: RedirectOp::HereDoc,
+            target: delimiter.clone(),
+            heredoc: Some(HereDoc {
+                delimiter,
+                quoted,
+                strip_tabs,
+                body: heredoc_body_text(start, body, end, source, strip_tabs),
+                substitutions: body_substitutions,
+            }),
+        },
+    );
+
+    Some(HeredocRedirect {
+        redirects,
+        substitutions,
+        continuations,
+    })
+}
+
+/// Join a statement with the commands that followed its heredoc delimiter.
+fn apply_continuations(stmt: Statement, continuations: Vec<Continuation>) -> Statement {
+    continuations
+        .into_iter()
+        .fold(stmt, |head, continuation| match continuation {
+            Continuation::Pipe(tail) => join_pipe(head, tail),
+            Continuation::List(op, tail) => match head {
+                Statement::List(mut list) => {
+                    list.rest.push((op, tail));
+                    Statement::List(list)
+                }
+                head => Statement::List(List {
+                    first: Box::new(head),
+                    rest: vec![(op, tail)],
+                }),
+            },
+        })
+}
+
+/// `head | tail`. The tail was parsed on its own, so when it is a list
+/// (`| grep a && echo done`) `head` joins the list's first pipeline: `|`
+/// binds tighter than `&&` and `||`.
+fn join_pipe(head: Statement, tail: Statement) -> Statement {
+    match tail {
+        Statement::List(List { first, rest }) => Statement::List(List {
+            first: Box::new(join_pipe(head, *first)),
+            rest,
+        }),
+        Statement::Pipeline(Pipeline {
+            mut stages,
+            negated: false,
+        }) => {
+            stages.insert(0, head);
+            Statement::Pipeline(Pipeline {
+                stages,
+                negated: false,
+            })
+        }
+        tail => Statement::Pipeline(Pipeline {
+            stages: vec![head, tail],
+            negated: false,
+        }),
+    }
 }
 
 /// Inject redirects into all SimpleCommand leaves of a statement tree.
@@ -382,6 +806,7 @@ fn convert_bare_assignment(node: Node, source: &str) -> Statement {
         redirects: vec![],
         assignments,
         embedded_substitutions,
+        resolution: None,
     })
 }
 
@@ -420,6 +845,7 @@ fn convert_declaration_command(node: Node, source: &str) -> Statement {
         redirects: vec![],
         assignments,
         embedded_substitutions,
+        resolution: None,
     })
 }
 
@@ -448,6 +874,7 @@ fn convert_unset_command(node: Node, source: &str) -> Statement {
         redirects: vec![],
         assignments: vec![],
         embedded_substitutions,
+        resolution: None,
     })
 }
 
@@ -558,14 +985,13 @@ fn convert_compound_statement(node: Node, source: &str) -> Statement {
 /// Convert a "function_definition" node.
 /// Note: Defining a function doesn't execute it, but we still parse the body
 /// in case the function is called later in the same command.
+/// Bodies other than `{ ... }` (`f() ( ... )`, `f() if ...; fi`) go through
+/// `convert_node` like any other statement.
 fn convert_function_definition(node: Node, source: &str) -> Statement {
-    let mut cursor = node.walk();
-    for child in node.named_children(&mut cursor) {
-        if child.kind() == "compound_statement" {
-            return convert_compound_statement(child, source);
-        }
+    match node.child_by_field_name("body") {
+        Some(body) => convert_node(body, source),
+        None => Statement::Opaque(node_text(node, source).to_string()),
     }
-    Statement::Empty
 }
 
 /// Convert a "test_command" node ([[ ... ]] or [ ... ]).
@@ -290,7 +290,61 @@ pub fn parse_redirect(node: Node, source: &str) -> (Redirect, Vec<super::Stateme
         }
     }
 
-    (Redirect { fd, op, target }, substitutions)
+    (
+        Redirect {
+            fd,
+            op,
+            target,
+            heredoc: None,
+        },
+        substitutions,
+    )
+}
+
+/// Split a `heredoc_start` token into the delimiter bash compares lines
+/// against and whether any part of it was quoted (`'EOF'`, `"EOF"`, `\EOF`,
+/// `E"O"F`), which turns off expansion in the body.
+pub fn parse_heredoc_delimiter(start: &str) -> (String, bool) {
+    let quoted = start.contains(['\'', '"', '\\']);
+    let delimiter = start
+        .chars()
+        .filter(|c| !matches!(c, '\'' | '"' | '\\'))
+        .collect();
+    (delimiter, quoted)
+}
+
+/// The body of a heredoc, sliced by line from the source: tree-sitter starts
+/// `heredoc_body` after leading blank lines and, for `<<-`, after leading
+/// tabs. Runs from the line after `start` (or the first content line, when
+/// something else such as an earlier heredoc sits in between) up to the line
+/// holding the closing delimiter `end`; leading tabs are removed for `<<-`.
+pub fn heredoc_body_text(
+    start: Node,
+    body: Option<Node>,
+    end: Node,
+    source: &str,
+    strip_tabs: bool,
+) -> String {
+    let line_start = |byte: usize| source[..byte].rfind('\n').map_or(0, |i| i + 1);
+    let end_line = line_start(end.start_byte());
+    let first_line = source[start.end_byte()..]
+        .find('\n')
+        .map_or(source.len(), |i| start.end_byte() + i + 1);
+    let content_line = body.map_or(end_line, |body| line_start(body.start_byte()));
+    let from = match source.get(first_line..content_line) {
+        Some(gap) if gap.trim().is_empty() => first_line,
+        _ => content_line,
+    };
+    let text = source.get(from..end_line.max(from)).unwrap_or_default();
+    let text = text.strip_suffix('\n').unwrap_or(text);
+    if strip_tabs {
+        text.split('\n')
+            .map(|line| line.trim_start_matches('\t'))
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else {
+        text.to_string()
+    }
 }
 
 /// Classify an argv-position tree-sitter node into an `ArgMeta`.
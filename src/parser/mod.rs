@@ -1,6 +1,7 @@
 mod convert;
 mod helpers;
 pub(crate) mod shell_c;
+mod variables;
 pub mod wrappers;
 
 use std::fmt;
@@ -86,6 +87,43 @@ pub struct SimpleCommand {
     pub redirects: Vec<Redirect>,
     pub assignments: Vec<Assignment>,
     pub embedded_substitutions: Vec<Statement>,
+    /// The command with variables assigned earlier in the same command line
+    /// substituted, when any of its words use one.
+    pub resolution: Option<Box<Resolution>>,
+}
+
+/// A command as it runs once statically known variables are substituted:
+/// `DIR=/tmp/build; rm -rf "$DIR"` resolves `rm -rf "$DIR"` to
+/// `rm -rf "/tmp/build"`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolution {
+    /// The substituted command. Its substitutions are left empty; each
+    /// command substitution carries its own resolution.
+    pub command: SimpleCommand,
+    /// The assignments whose values were substituted.
+    pub bindings: Vec<Assignment>,
+}
+
+impl SimpleCommand {
+    /// The heredoc this command reads on stdin: the last redirect of fd 0,
+    /// when that is a heredoc.
+    pub fn stdin_heredoc(&self) -> Option<&HereDoc> {
+        self.redirects
+            .iter()
+            .rev()
+            .find(|r| {
+                r.fd.unwrap_or(0) == 0
+                    && matches!(
+                        r.op,
+                        RedirectOp::Read
+                            | RedirectOp::ReadWrite
+                            | RedirectOp::DupInput
+                            | RedirectOp::HereDoc
+                    )
+            })?
+            .heredoc
+            .as_ref()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -111,7 +149,28 @@ pub enum ListOp {
 pub struct Redirect {
     pub fd: Option<u32>,
     pub op: RedirectOp,
+    /// The file, fd or (for `<<`) the heredoc delimiter.
     pub target: String,
+    /// The here-document, when `op` is `HereDoc`.
+    pub heredoc: Option<HereDoc>,
+}
+
+/// A here-document (`<<EOF` ... `EOF`) as bash reads it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HereDoc {
+    /// The delimiter with any quoting removed.
+    pub delimiter: String,
+    /// `<<'EOF'`, `<<"EOF"` or `<<\EOF`: the body is passed through
+    /// literally, without expansions.
+    pub quoted: bool,
+    /// `<<-EOF`: leading tabs are stripped from body lines.
+    pub strip_tabs: bool,
+    /// The body lines (tabs already stripped for `<<-`), without the
+    /// closing delimiter line or the final newline.
+    pub body: String,
+    /// Command substitutions in an unquoted body, which bash runs while
+    /// expanding it. Always empty when `quoted`.
+    pub substitutions: Vec<Statement>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -123,6 +182,7 @@ pub enum RedirectOp {
     DupOutput, // >&
     DupInput,  // <&
     Clobber,   // >|
+    HereDoc,   // << and <<-
 }
 
 impl fmt::Display for RedirectOp {
@@ -135,6 +195,7 @@ impl fmt::Display for RedirectOp {
             RedirectOp::DupOutput => write!(f, ">&"),
             RedirectOp::DupInput => write!(f, "<&"),
             RedirectOp::Clobber => write!(f, ">|"),
+            RedirectOp::HereDoc => write!(f, "<<"),
         }
     }
 }
@@ -165,15 +226,7 @@ pub fn parse(command: &str) -> Result<Statement, String> {
         return Ok(Statement::Opaque(String::new()));
     }
 
-    let mut parser = TsParser::new();
-    parser
-        .set_language(&tree_sitter_bash::LANGUAGE.into())
-        .map_err(|e| format!("Failed to load bash grammar: {e}"))?;
-
-    let tree = parser
-        .parse(command, None)
-        .ok_or_else(|| "tree-sitter parse returned None".to_string())?;
-
+    let tree = syntax_tree(command)?;
     let root = tree.root_node();
 
     // If the root itself is an ERROR node, return Opaque
@@ -181,7 +234,20 @@ pub fn parse(command: &str) -> Result<Statement, String> {
         return Ok(Statement::Opaque(command.to_string()));
     }
 
-    Ok(convert_program(root, command))
+    let mut stmt = convert_program(root, command);
+    variables::resolve(&mut stmt, root, command);
+    Ok(stmt)
+}
+
+fn syntax_tree(command: &str) -> Result<tree_sitter::Tree, String> {
+    let mut parser = TsParser::new();
+    parser
+        .set_language(&tree_sitter_bash::LANGUAGE.into())
+        .map_err(|e| format!("Failed to load bash grammar: {e}"))?;
+
+    parser
+        .parse(command, None)
+        .ok_or_else(|| "tree-sitter parse returned None".to_string())
 }
 
 /// Flatten a Statement into its leaf SimpleCommand, Opaque, and Empty nodes.
@@ -220,6 +286,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         });
         let leaves = flatten(&cmd);
         assert_eq!(leaves.len(), 1);
@@ -235,6 +302,7 @@ mod tests {
                     redirects: vec![],
                     assignments: vec![],
                     embedded_substitutions: vec![],
+                    resolution: None,
                 }),
                 Statement::SimpleCommand(SimpleCommand {
                     name: Some("sh".into()),
@@ -242,6 +310,7 @@ mod tests {
                     redirects: vec![],
                     assignments: vec![],
                     embedded_substitutions: vec![],
+                    resolution: None,
                 }),
             ],
             negated: false,
@@ -259,6 +328,7 @@ mod tests {
                 redirects: vec![],
                 assignments: vec![],
                 embedded_substitutions: vec![],
+                resolution: None,
             })),
             rest: vec![(
                 ListOp::And,
@@ -268,6 +338,7 @@ mod tests {
                     redirects: vec![],
                     assignments: vec![],
                     embedded_substitutions: vec![],
+                    resolution: None,
                 }),
             )],
         });
@@ -292,6 +363,7 @@ mod tests {
                     redirects: vec![],
                     assignments: vec![],
                     embedded_substitutions: vec![],
+                    resolution: None,
                 }),
                 Statement::SimpleCommand(SimpleCommand {
                     name: Some("grep".into()),
@@ -299,6 +371,7 @@ mod tests {
                     redirects: vec![],
                     assignments: vec![],
                     embedded_substitutions: vec![],
+                    resolution: None,
                 }),
             ],
             negated: false,
@@ -737,6 +810,148 @@ mod tests {
         assert!(has_echo, "Function definition should contain echo command");
     }
 
+    fn leaf_names(stmt: &Statement) -> Vec<String> {
+        flatten(stmt)
+            .into_iter()
+            .map(|leaf| match leaf {
+                Statement::SimpleCommand(cmd) => cmd.name.clone().unwrap_or_default(),
+                other => format!("{other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_function_call_resolves_to_body() {
+        let stmt = parse("f() { git status; }; f").unwrap();
+        assert_eq!(leaf_names(&stmt), vec!["git", "git"]);
+    }
+
+    #[test]
+    fn test_parse_function_call_carries_redirects_and_substitutions() {
+        let stmt = parse("f() { echo hi; }; f $(whoami) > out.txt").unwrap();
+        let leaves = flatten(&stmt);
+        let Statement::SimpleCommand(call) = leaves[1] else {
+            panic!("expected the resolved body, got {:?}", leaves[1]);
+        };
+        assert_eq!(call.name.as_deref(), Some("echo"));
+        assert_eq!(call.redirects[0].target, "out.txt");
+        assert_eq!(leaf_names(&stmt), vec!["echo", "echo", "whoami"]);
+    }
+
+    fn leaf_texts(stmt: &Statement) -> Vec<String> {
+        flatten(stmt)
+            .into_iter()
+            .map(|leaf| match leaf {
+                Statement::SimpleCommand(cmd) => {
+                    std::iter::once(cmd.name.clone().unwrap_or_default())
+                        .chain(cmd.argv.iter().map(|arg| arg.text.clone()))
+                        .chain(
+                            cmd.redirects
+                                .iter()
+                                .map(|r| format!("{}{}", r.op, r.target)),
+                        )
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                }
+                other => format!("{other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_function_call_binds_positional_parameters() {
+        let stmt = parse(r#"f() { cp "$1" "${2}.bak" > "$3"; }; f a b c"#).unwrap();
+        assert_eq!(leaf_texts(&stmt)[1], "cp a b.bak >c");
+        let stmt = parse(r#"f() { echo "$#:$*" "$@" "$4"; }; f a 'b c'"#).unwrap();
+        assert_eq!(leaf_texts(&stmt)[1], "echo 2:a b c a b c ");
+        let stmt = parse(r#"f() { "$@"; }; f rm -rf /"#).unwrap();
+        assert_eq!(leaf_texts(&stmt)[1], "rm -rf /");
+        let stmt = parse(r#"f() { shift; cat "$1"; }; f a b"#).unwrap();
+        assert_eq!(leaf_texts(&stmt)[2], r#"Opaque("f a b")"#);
+    }
+
+    #[test]
+    fn test_parse_function_recursion_stays_a_call() {
+        let stmt = parse("f() { g; }; g() { f; }; f").unwrap();
+        assert_eq!(leaf_names(&stmt), vec!["g", "f", "f"]);
+    }
+
+    #[test]
+    fn test_parse_function_call_needs_unconditional_earlier_definition() {
+        let stmt = parse("f; f() { ls; }").unwrap();
+        assert_eq!(leaf_names(&stmt), vec!["f", "ls"]);
+        let stmt = parse("(f() { ls; }); f").unwrap();
+        assert_eq!(leaf_names(&stmt), vec!["ls", "f"]);
+    }
+
+    fn heredoc_of(stmt: &Statement) -> &HereDoc {
+        let Statement::SimpleCommand(cmd) = flatten(stmt)[0] else {
+            panic!("expected a simple command first, got {stmt:?}");
+        };
+        cmd.stdin_heredoc().expect("heredoc on stdin")
+    }
+
+    #[test]
+    fn test_parse_heredoc_unquoted_body_and_substitutions() {
+        let stmt = parse("cat <<EOF\n\nhello $(whoami) $HOME\nEOF").unwrap();
+        let heredoc = heredoc_of(&stmt);
+        assert_eq!(heredoc.delimiter, "EOF");
+        assert!(!heredoc.quoted);
+        assert_eq!(heredoc.body, "\nhello $(whoami) $HOME");
+        assert_eq!(heredoc.substitutions.len(), 1);
+        assert_eq!(leaf_names(&stmt), vec!["cat", "whoami"]);
+    }
+
+    #[test]
+    fn test_parse_heredoc_quoted_and_tab_stripped() {
+        let stmt = parse("python3 - <<-'PY'\n\tprint($(x))\n\t\tpass\n\tPY").unwrap();
+        let heredoc = heredoc_of(&stmt);
+        assert_eq!(heredoc.delimiter, "PY");
+        assert!(heredoc.quoted);
+        assert!(heredoc.strip_tabs);
+        assert_eq!(heredoc.body, "print($(x))\npass");
+        assert!(heredoc.substitutions.is_empty());
+        assert_eq!(leaf_names(&stmt), vec!["python3"]);
+    }
+
+    #[test]
+    fn test_parse_heredoc_keeps_commands_after_delimiter() {
+        let stmt = parse("cat <<EOF | grep a | bash && rm x\nbody\nEOF").unwrap();
+        assert_eq!(leaf_names(&stmt), vec!["cat", "grep", "bash", "rm"]);
+        let Statement::List(list) = &stmt else {
+            panic!("expected a list, got {stmt:?}");
+        };
+        let Statement::Pipeline(pipeline) = list.first.as_ref() else {
+            panic!("expected cat | grep | bash first, got {:?}", list.first);
+        };
+        assert_eq!(pipeline.stages.len(), 3);
+
+        let stmt = parse("cat <<EOF > out.txt && rm x\nbody\nEOF").unwrap();
+        assert_eq!(leaf_names(&stmt), vec!["cat", "rm"]);
+        let Statement::SimpleCommand(cat) = flatten(&stmt)[0] else {
+            panic!("expected cat, got {stmt:?}");
+        };
+        let ops: Vec<_> = cat.redirects.iter().map(|r| r.op).collect();
+        assert_eq!(ops, vec![RedirectOp::HereDoc, RedirectOp::Write]);
+        assert_eq!(cat.redirects[1].target, "out.txt");
+    }
+
+    #[test]
+    fn test_parse_heredoc_unreliable_shape_is_opaque() {
+        for source in [
+            "cat <<EOF; rm x\nbody\nEOF",
+            "cat <<EOF > out.txt | grep a\nbody\nEOF",
+        ] {
+            let stmt = parse(source).unwrap();
+            assert!(
+                flatten(&stmt)
+                    .iter()
+                    .any(|leaf| matches!(leaf, Statement::Opaque(_))),
+                "{source:?} parsed as {stmt:?}"
+            );
+        }
+    }
+
     #[test]
     fn test_flatten_empty() {
         let stmt = Statement::Empty;
@@ -1,6 +1,9 @@
 //! Shell-C wrapper unwrapping: re-parses the string arg of bash -c, sh -c,
 //! sg <group> -c, etc. when the arg's ArgMeta indicates it's safe.
 //!
+//! The same applies to a script fed on stdin as a heredoc: `bash <<'EOF'`,
+//! `sh -s <<EOF`.
+//!
 //! Entry points:
 //! - unwrap_shell_c(cmd) — main mechanism; returns Some(Statement) for
 //!   successful re-parse, Some(Opaque) for fail-closed, None for pass-through.
@@ -8,7 +11,7 @@
 //!   decide whether an outer shell-c wrapper leaf is safe at the leaf level
 //!   because its inner command is being separately evaluated.
 
-use super::{parse, ArgMeta, SimpleCommand, Statement};
+use super::{parse, ArgMeta, HereDoc, SimpleCommand, Statement};
 
 struct ShellCDef {
     /// Basename match, e.g. "bash", "sh", "sg".
@@ -145,13 +148,23 @@ pub(crate) fn unwrap_shell_c(cmd: &SimpleCommand) -> Option<Statement> {
 
     // Phase 2: decide based on what's at argv[i].
     if i >= argv.len() {
-        // argv exhausted after flag consumption → pass-through
-        // (bash alone, bash --version, sg docker, sg docker --version, etc.)
-        return None;
+        // argv exhausted after flag consumption → the shell reads its script
+        // from stdin. A heredoc there is re-parsed; anything else passes
+        // through (bash alone, bash --version, sg docker, etc.)
+        return cmd.stdin_heredoc().map(reparse_heredoc);
     }
 
     let next = argv[i].text.as_str();
 
+    if next == "-s" {
+        // `-s [args...]`: the script is still stdin, the rest are positional
+        // parameters.
+        return Some(cmd.stdin_heredoc().map_or_else(
+            || Statement::Opaque("shell -s reading a script from stdin".to_string()),
+            reparse_heredoc,
+        ));
+    }
+
     if next == "-c" {
         // Phase 3: ArgMeta gate on the string argument.
         let Some(c_arg) = argv.get(i + 1) else {
@@ -190,6 +203,24 @@ pub(crate) fn unwrap_shell_c(cmd: &SimpleCommand) -> Option<Statement> {
     }
 }
 
+/// Re-parse a heredoc script. A quoted heredoc is the literal script, like a
+/// RawString `-c` arg; an unquoted one is only taken as-is when it has
+/// nothing bash would expand or unescape first.
+fn reparse_heredoc(heredoc: &HereDoc) -> Statement {
+    if !heredoc.quoted && heredoc.body.contains(['$', '`', '\\']) {
+        return Statement::Opaque(
+            "shell heredoc body expands at runtime; cannot analyze".to_string(),
+        );
+    }
+    match parse(&heredoc.body) {
+        Ok(Statement::Opaque(_)) => {
+            Statement::Opaque("shell heredoc inner parse opaque".to_string())
+        }
+        Ok(stmt) => stmt,
+        Err(err) => Statement::Opaque(format!("shell heredoc inner parse setup failed: {err}")),
+    }
+}
+
 /// Returns true if `leaf` is a SimpleCommand that is itself a shell-c wrapper
 /// AND `unwrap_shell_c` produces a non-Opaque inner Statement for it. Used by
 /// the policy evaluator to decide whether the outer wrapper leaf should be
@@ -216,6 +247,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         }
     }
 
@@ -779,4 +811,48 @@ mod tests {
         );
         assert!(!is_covered_shell_c_wrapper(&Statement::SimpleCommand(cmd)));
     }
+
+    // ── Heredoc scripts on stdin ────────────────────────────────
+    fn heredoc_cmd(source: &str) -> SimpleCommand {
+        match parse(source).unwrap() {
+            Statement::SimpleCommand(cmd) => cmd,
+            other => panic!("expected a simple command, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn heredoc_quoted_body_is_reparsed() {
+        let cmd = heredoc_cmd("bash <<'EOF'\nls $HOME\nEOF");
+        let Some(Statement::SimpleCommand(inner)) = unwrap_shell_c(&cmd) else {
+            panic!("expected a parsed inner command");
+        };
+        assert_eq!(inner.name.as_deref(), Some("ls"));
+    }
+
+    #[test]
+    fn heredoc_with_dash_s_is_reparsed() {
+        let cmd = heredoc_cmd("sh -s -- one two <<EOF\ngit status\nEOF");
+        assert!(matches!(
+            unwrap_shell_c(&cmd),
+            Some(Statement::SimpleCommand(inner)) if inner.name.as_deref() == Some("git")
+        ));
+    }
+
+    #[test]
+    fn heredoc_unquoted_expansion_is_opaque() {
+        let cmd = heredoc_cmd("bash <<EOF\n$CMD\nEOF");
+        assert!(matches!(unwrap_shell_c(&cmd), Some(Statement::Opaque(_))));
+    }
+
+    #[test]
+    fn heredoc_overridden_by_later_stdin_redirect_passes_through() {
+        let cmd = heredoc_cmd("bash <<EOF < script.sh\nls\nEOF");
+        assert_eq!(unwrap_shell_c(&cmd), None);
+    }
+
+    #[test]
+    fn dash_s_without_heredoc_is_opaque() {
+        let cmd = mk_cmd("bash", vec![arg("-s", ArgMeta::PlainWord)]);
+        assert!(matches!(unwrap_shell_c(&cmd), Some(Statement::Opaque(_))));
+    }
 }
@@ -0,0 +1,805 @@
+//! Constant propagation for variables assigned earlier in the same command.
+//!
+//! `DIR=/tmp/build; rm -rf "$DIR"` is judged as written and, through
+//! `SimpleCommand::resolution`, as `rm -rf "/tmp/build"`. The walk follows
+//! bash's execution order over the syntax tree, where conditionals and loops
+//! are still visible (the `Statement` tree flattens them into plain lists).
+//! A `$NAME` or `${NAME}` is resolved only when an assignment certainly ran
+//! before it and its value is one inert word, so substituting the text gives
+//! exactly what bash expands. Anything that may have changed a variable — a
+//! branch, a loop, a builtin such as `read`, a function call, a `${X:=y}` —
+//! forgets it, and uses of a forgotten variable stay as written.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use tree_sitter::Node;
+
+use super::convert::convert_program;
+use super::helpers::{classify_arg_node, node_text, parse_heredoc_delimiter};
+use super::{ArgMeta, Assignment, Resolution, SimpleCommand, Statement};
+
+type Env = HashMap<String, Assignment>;
+
+/// Builtins that can assign or unset a variable named by their arguments.
+const VARIABLE_WRITERS: &[&str] = &[
+    "read",
+    "mapfile",
+    "readarray",
+    "printf",
+    "getopts",
+    "let",
+    "eval",
+    "source",
+    ".",
+    "unset",
+    "command",
+    "builtin",
+    "enable",
+    "wait",
+];
+
+/// Words the grammar reads as syntax when they start a command.
+const RESERVED_WORDS: &[&str] = &[
+    "if", "then", "elif", "else", "fi", "case", "esac", "for", "select", "while", "until", "do",
+    "done", "in", "function", "time", "coproc",
+];
+
+/// Statement kinds met while scanning words, for instance the commands after
+/// a heredoc start (`cat <<EOF && D=x`).
+const STATEMENT_KINDS: &[&str] = &[
+    "command",
+    "pipeline",
+    "list",
+    "subshell",
+    "compound_statement",
+    "redirected_statement",
+    "negated_command",
+    "variable_assignment",
+    "variable_assignments",
+    "declaration_command",
+    "unset_command",
+    "if_statement",
+    "for_statement",
+    "c_style_for_statement",
+    "while_statement",
+    "case_statement",
+    "function_definition",
+    "test_command",
+];
+
+/// Attach a `Resolution` to every command in `stmt` that uses a variable
+/// assigned earlier in `source`. The substituted source is parsed again and
+/// must produce a statement of the same shape; otherwise nothing resolves.
+pub(super) fn resolve(stmt: &mut Statement, root: Node, source: &str) {
+    // A changed IFS re-splits every unquoted expansion, and a DEBUG trap can
+    // assign between any two commands.
+    if root.has_error() || source.contains("IFS") {
+        return;
+    }
+    let mut functions = HashSet::new();
+    if !collect_functions(root, source, &mut functions) {
+        return;
+    }
+    let mut walker = Walker {
+        source,
+        functions,
+        uses: Vec::new(),
+        recording: true,
+    };
+    walker.visit_sequence(root, &mut Env::new());
+    if walker.uses.is_empty() {
+        return;
+    }
+
+    let mut uses = walker.uses;
+    uses.sort_by_key(|(range, _)| range.start);
+    uses.dedup_by_key(|(range, _)| range.start);
+    let mut resolved_source = String::with_capacity(source.len());
+    let mut end = 0;
+    for (range, assignment) in &uses {
+        resolved_source.push_str(&source[end..range.start]);
+        resolved_source.push_str(&assignment.value);
+        end = range.end;
+    }
+    resolved_source.push_str(&source[end..]);
+
+    let Ok(tree) = super::syntax_tree(&resolved_source) else {
+        return;
+    };
+    if tree.root_node().has_error() {
+        return;
+    }
+    let resolved = convert_program(tree.root_node(), &resolved_source);
+    if same_shape(stmt, &resolved) {
+        let bindings: Vec<Assignment> = uses.into_iter().map(|(_, a)| a).collect();
+        attach(stmt, resolved, &bindings);
+    }
+}
+
+/// Record the names of functions defined anywhere in `node`. False when a
+/// `trap` is set, since its handler may assign at any point.
+fn collect_functions(node: Node, source: &str, functions: &mut HashSet<String>) -> bool {
+    match node.kind() {
+        "function_definition" => {
+            if let Some(name) = node.child_by_field_name("name") {
+                functions.insert(node_text(name, source).to_string());
+            }
+        }
+        "command_name" if node_text(node, source) == "trap" => return false,
+        _ => {}
+    }
+    let mut cursor = node.walk();
+    let children: Vec<Node> = node.named_children(&mut cursor).collect();
+    children
+        .into_iter()
+        .all(|child| collect_functions(child, source, functions))
+}
+
+struct Walker<'s> {
+    source: &'s str,
+    functions: HashSet<String>,
+    /// Expansions to substitute, with the assignment that supplies each.
+    uses: Vec<(Range<usize>, Assignment)>,
+    /// False while a loop body is walked only to learn what it assigns.
+    recording: bool,
+}
+
+impl Walker<'_> {
+    fn text(&self, node: Node) -> &str {
+        node_text(node, self.source)
+    }
+
+    fn visit_sequence(&mut self, node: Node, env: &mut Env) {
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        for child in children {
+            self.visit(child, env);
+        }
+    }
+
+    /// Walk a statement that may not run: `env` keeps only the bindings the
+    /// statement leaves unchanged.
+    fn branch(&mut self, env: &mut Env, walk: impl FnOnce(&mut Self, &mut Env)) {
+        let mut inner = env.clone();
+        walk(self, &mut inner);
+        env.retain(|name, value| inner.get(name) == Some(value));
+    }
+
+    /// Walk a statement run in a subshell, whose assignments are lost.
+    fn isolated(&mut self, env: &Env, walk: impl FnOnce(&mut Self, &mut Env)) {
+        walk(self, &mut env.clone());
+    }
+
+    fn visit(&mut self, node: Node, env: &mut Env) {
+        match node.kind() {
+            "comment" | "function_definition" => {}
+            "command" => self.visit_command(node, env),
+            "variable_assignment" => self.assign(node, env),
+            "variable_assignments" => self.visit_sequence(node, env),
+            "declaration_command" => self.visit_declaration(node, env),
+            "program" | "do_group" | "negated_command" => self.visit_sequence(node, env),
+            "compound_statement" => {
+                if self.text(node).starts_with("((") {
+                    // Arithmetic can assign any variable it names.
+                    env.clear();
+                } else {
+                    self.visit_sequence(node, env);
+                }
+            }
+            "subshell" => self.isolated(env, |w, env| w.visit_sequence(node, env)),
+            "pipeline" => {
+                let mut cursor = node.walk();
+                for stage in node.named_children(&mut cursor) {
+                    self.isolated(env, |w, env| w.visit(stage, env));
+                }
+            }
+            "list" => {
+                let mut cursor = node.walk();
+                let parts: Vec<Node> = node.named_children(&mut cursor).collect();
+                if let Some((first, rest)) = parts.split_first() {
+                    self.visit(*first, env);
+                    for part in rest {
+                        self.branch(env, |w, env| w.visit(*part, env));
+                    }
+                }
+            }
+            "redirected_statement" => {
+                // Redirect words expand before the command runs.
+                let body = node.child_by_field_name("body");
+                let mut cursor = node.walk();
+                let children: Vec<Node> = node.named_children(&mut cursor).collect();
+                for child in children.iter().filter(|c| Some(**c) != body) {
+                    self.scan(*child, env);
+                }
+                if let Some(body) = body {
+                    self.visit(body, env);
+                }
+            }
+            "test_command" => self.scan_children(node, env),
+            "if_statement" => self.visit_if(node, env),
+            "case_statement" => self.visit_case(node, env),
+            "for_statement" | "c_style_for_statement" | "while_statement" => {
+                self.visit_loop(node, env)
+            }
+            _ => {
+                self.scan_children(node, env);
+                env.clear();
+            }
+        }
+    }
+
+    fn visit_command(&mut self, node: Node, env: &mut Env) {
+        let mut name = None;
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        for child in children {
+            match child.kind() {
+                // `X=1 cmd` assigns for `cmd` only.
+                "variable_assignment" => {
+                    if let Some(value) = child.child_by_field_name("value") {
+                        self.scan(value, env);
+                    }
+                }
+                "command_name" => {
+                    let word = child.named_child(0).unwrap_or(child);
+                    let value = static_value(word, env, self.source);
+                    // A value the grammar would read as an assignment or a
+                    // keyword in this position is left unsubstituted.
+                    let safe = value.as_deref().is_some_and(|v| {
+                        !v.is_empty() && !v.contains('=') && !RESERVED_WORDS.contains(&v)
+                    });
+                    if safe {
+                        self.scan(child, env);
+                    }
+                    name = value;
+                }
+                _ => self.scan(child, env),
+            }
+        }
+        let writes = match name.as_deref() {
+            Some(name) => VARIABLE_WRITERS.contains(&name) || self.functions.contains(name),
+            None => true,
+        };
+        if writes {
+            env.clear();
+        }
+    }
+
+    /// A bare `NAME=value`.
+    fn assign(&mut self, node: Node, env: &mut Env) {
+        let value = node.child_by_field_name("value");
+        if let Some(value) = value {
+            self.scan(value, env);
+        }
+        let Some(name) = node
+            .child_by_field_name("name")
+            .filter(|n| n.kind() == "variable_name")
+        else {
+            // `arr[i]=x`: the subscript is arithmetic.
+            env.clear();
+            return;
+        };
+        let name = self.text(name).to_string();
+        let appends = self.text(node)[name.len()..].starts_with("+=");
+        let resolved = match value {
+            _ if appends || is_special(&name) => None,
+            Some(value) => static_value(value, env, self.source).map(|text| Assignment {
+                name: name.clone(),
+                value: text,
+                value_meta: classify_arg_node(value, self.source),
+            }),
+            None => Some(Assignment {
+                name: name.clone(),
+                value: String::new(),
+                value_meta: ArgMeta::PlainWord,
+            }),
+        };
+        match resolved {
+            Some(assignment) => env.insert(name, assignment),
+            None => env.remove(&name),
+        };
+    }
+
+    /// `export`, `declare`, `readonly` and `typeset` without flags assign
+    /// like a bare assignment; anything else (flags, `local`, dynamic names)
+    /// forgets every binding.
+    fn visit_declaration(&mut self, node: Node, env: &mut Env) {
+        let keyword = node.child(0).map(|k| self.text(k)).unwrap_or_default();
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        let plain = matches!(keyword, "export" | "declare" | "readonly" | "typeset")
+            && children
+                .iter()
+                .all(|c| matches!(c.kind(), "variable_assignment" | "variable_name"));
+        if !plain {
+            self.scan_children(node, env);
+            env.clear();
+            return;
+        }
+        for child in children {
+            if child.kind() == "variable_assignment" {
+                self.assign(child, env);
+            }
+        }
+    }
+
+    fn visit_if(&mut self, node: Node, env: &mut Env) {
+        let mut cursor = node.walk();
+        let conditions: Vec<Node> = node
+            .children_by_field_name("condition", &mut cursor)
+            .filter(|c| c.is_named())
+            .collect();
+        for condition in &conditions {
+            self.visit(*condition, env);
+        }
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        let (clauses, body): (Vec<Node>, Vec<Node>) = children
+            .into_iter()
+            .filter(|c| !conditions.contains(c))
+            .partition(|c| matches!(c.kind(), "elif_clause" | "else_clause"));
+        self.branch(env, |w, env| {
+            for statement in body {
+                w.visit(statement, env);
+            }
+        });
+        for clause in clauses {
+            self.branch(env, |w, env| w.visit_sequence(clause, env));
+        }
+    }
+
+    fn visit_case(&mut self, node: Node, env: &mut Env) {
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        for child in children {
+            if child.kind() != "case_item" {
+                self.scan(child, env);
+                continue;
+            }
+            self.branch(env, |w, env| {
+                let mut cursor = child.walk();
+                let patterns: Vec<Node> =
+                    child.children_by_field_name("value", &mut cursor).collect();
+                let mut cursor = child.walk();
+                let parts: Vec<Node> = child.named_children(&mut cursor).collect();
+                for part in parts {
+                    if patterns.contains(&part) {
+                        w.scan(part, env);
+                    } else {
+                        w.visit(part, env);
+                    }
+                }
+            });
+        }
+    }
+
+    /// A loop body may run any number of times, so a use inside it can see
+    /// an assignment made later in the body on an earlier iteration. Walk
+    /// the body without recording until the bindings it leaves unchanged
+    /// stop shrinking, then walk it once more for its uses.
+    fn visit_loop(&mut self, node: Node, env: &mut Env) {
+        if node.kind() == "c_style_for_statement" {
+            env.clear();
+        }
+        let mut units = Vec::new();
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        for (i, child) in children.into_iter().enumerate() {
+            match node.field_name_for_named_child(i as u32) {
+                Some("variable") => {
+                    env.remove(self.text(child));
+                }
+                Some("value") => self.scan(child, env),
+                Some("initializer" | "update") => {}
+                _ if node.kind() == "c_style_for_statement" && child.kind() != "do_group" => {}
+                _ => units.push(child),
+            }
+        }
+        let recording = std::mem::replace(&mut self.recording, false);
+        loop {
+            let before = env.len();
+            self.branch(env, |w, env| {
+                for unit in &units {
+                    w.visit(*unit, env);
+                }
+            });
+            if env.len() == before {
+                break;
+            }
+        }
+        self.recording = recording;
+        self.branch(env, |w, env| {
+            for unit in &units {
+                w.visit(*unit, env);
+            }
+        });
+    }
+
+    /// Record the resolvable expansions among the words under `node`.
+    fn scan(&mut self, node: Node, env: &mut Env) {
+        match node.kind() {
+            "simple_expansion" | "expansion" => match plain_reference(node, self.source) {
+                Some(name) => {
+                    if let Some(assignment) = env.get(name).filter(|_| self.recording) {
+                        self.uses.push((node.byte_range(), assignment.clone()));
+                    }
+                }
+                // `${X:=y}`, `${X[i++]}`: may assign.
+                None if node.kind() == "expansion" => env.clear(),
+                // `$1`, `$?`.
+                None => {}
+            },
+            "arithmetic_expansion" => env.clear(),
+            "raw_string" | "ansi_c_string" => {}
+            "command_substitution" | "process_substitution" => {
+                self.isolated(env, |w, env| w.visit_sequence(node, env))
+            }
+            "heredoc_redirect" => self.scan_heredoc(node, env),
+            kind if STATEMENT_KINDS.contains(&kind) => {
+                self.branch(env, |w, env| w.visit(node, env))
+            }
+            _ => self.scan_children(node, env),
+        }
+    }
+
+    fn scan_children(&mut self, node: Node, env: &mut Env) {
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        for child in children {
+            self.scan(child, env);
+        }
+    }
+
+    /// Expansions in an unquoted heredoc body resolve too, unless the value
+    /// could end the body early by containing the delimiter.
+    fn scan_heredoc(&mut self, node: Node, env: &mut Env) {
+        let start = node
+            .named_child(0)
+            .filter(|c| c.kind() == "heredoc_start")
+            .map(|c| parse_heredoc_delimiter(self.text(c)));
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        for child in children {
+            match child.kind() {
+                "heredoc_start" | "heredoc_end" => {}
+                "heredoc_body" => {
+                    let Some((delimiter, false)) = &start else {
+                        continue;
+                    };
+                    let from = self.uses.len();
+                    self.scan(child, env);
+                    let body_uses = self.uses.split_off(from);
+                    self.uses.extend(
+                        body_uses
+                            .into_iter()
+                            .filter(|(_, a)| !a.value.contains(delimiter.as_str())),
+                    );
+                }
+                _ => self.scan(child, env),
+            }
+        }
+    }
+}
+
+/// Variables bash sets itself, whose assignment may fail or not stick.
+fn is_special(name: &str) -> bool {
+    name.starts_with("BASH")
+        || matches!(
+            name,
+            "UID"
+                | "EUID"
+                | "PPID"
+                | "PWD"
+                | "OLDPWD"
+                | "RANDOM"
+                | "SRANDOM"
+                | "SECONDS"
+                | "LINENO"
+                | "EPOCHSECONDS"
+                | "EPOCHREALTIME"
+                | "SHELLOPTS"
+                | "GROUPS"
+                | "FUNCNAME"
+                | "HISTCMD"
+                | "PIPESTATUS"
+                | "DIRSTACK"
+                | "OPTIND"
+                | "OPTARG"
+                | "REPLY"
+        )
+}
+
+/// A value that expands to itself as one word, quoted or not.
+fn is_inert(value: &str) -> bool {
+    value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "_./:@%+,=-".contains(c))
+}
+
+/// The variable a `$NAME` or `${NAME}` expansion reads, with no operator.
+fn plain_reference<'a>(node: Node, source: &'a str) -> Option<&'a str> {
+    let name = node
+        .named_child(0)
+        .filter(|c| c.kind() == "variable_name")?;
+    let name = node_text(name, source);
+    let text = node_text(node, source);
+    let identifier = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_');
+    let plain = text.strip_prefix('$').is_some_and(|rest| {
+        rest == name
+            || rest
+                .strip_prefix('{')
+                .and_then(|r| r.strip_suffix('}'))
+                .is_some_and(|inner| inner == name)
+    });
+    (identifier && plain).then_some(name)
+}
+
+/// The inert word `node` always expands to under `env`.
+fn static_value(node: Node, env: &Env, source: &str) -> Option<String> {
+    let value = match node.kind() {
+        "word" | "number" => node_text(node, source).to_string(),
+        "raw_string" => node_text(node, source)
+            .strip_prefix('\'')?
+            .strip_suffix('\'')?
+            .to_string(),
+        "simple_expansion" | "expansion" => env.get(plain_reference(node, source)?)?.value.clone(),
+        "string" | "concatenation" => {
+            let mut out = String::new();
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                match child.kind() {
+                    "\"" if node.kind() == "string" => {}
+                    "string_content" => out.push_str(node_text(child, source)),
+                    _ if child.is_named() => out.push_str(&static_value(child, env, source)?),
+                    _ => return None,
+                }
+            }
+            out
+        }
+        _ => return None,
+    };
+    is_inert(&value).then_some(value)
+}
+
+fn same_shape(a: &Statement, b: &Statement) -> bool {
+    match (a, b) {
+        (Statement::SimpleCommand(a), Statement::SimpleCommand(b)) => {
+            a.name.is_some() == b.name.is_some()
+                && a.argv.len() == b.argv.len()
+                && a.redirects.len() == b.redirects.len()
+                && a.assignments.len() == b.assignments.len()
+                && a.embedded_substitutions.len() == b.embedded_substitutions.len()
+                && a.embedded_substitutions
+                    .iter()
+                    .zip(&b.embedded_substitutions)
+                    .all(|(a, b)| same_shape(a, b))
+        }
+        (Statement::Pipeline(a), Statement::Pipeline(b)) => {
+            a.negated == b.negated
+                && a.stages.len() == b.stages.len()
+                && a.stages
+                    .iter()
+                    .zip(&b.stages)
+                    .all(|(a, b)| same_shape(a, b))
+        }
+        (Statement::List(a), Statement::List(b)) => {
+            same_shape(&a.first, &b.first)
+                && a.rest.len() == b.rest.len()
+                && a.rest
+                    .iter()
+                    .zip(&b.rest)
+                    .all(|((op_a, a), (op_b, b))| op_a == op_b && same_shape(a, b))
+        }
+        (Statement::Subshell(a), Statement::Subshell(b))
+        | (Statement::CommandSubstitution(a), Statement::CommandSubstitution(b)) => {
+            same_shape(a, b)
+        }
+        (Statement::Opaque(_), Statement::Opaque(_)) | (Statement::Empty, Statement::Empty) => true,
+        _ => false,
+    }
+}
+
+/// Pair each command of `raw` with its counterpart in the same-shaped
+/// `resolved` and keep the counterpart where the two differ.
+fn attach(raw: &mut Statement, resolved: Statement, bindings: &[Assignment]) {
+    match (raw, resolved) {
+        (Statement::SimpleCommand(raw), Statement::SimpleCommand(mut resolved)) => {
+            let substitutions = std::mem::take(&mut resolved.embedded_substitutions);
+            for (raw_sub, sub) in raw.embedded_substitutions.iter_mut().zip(substitutions) {
+                attach(raw_sub, sub, bindings);
+            }
+            let changed = raw.name != resolved.name
+                || raw.argv != resolved.argv
+                || raw.redirects != resolved.redirects
+                || raw.assignments != resolved.assignments;
+            if changed {
+                raw.resolution = Some(Box::new(Resolution {
+                    bindings: provenance(raw, bindings),
+                    command: resolved,
+                }));
+            }
+        }
+        (Statement::Pipeline(raw), Statement::Pipeline(resolved)) => {
+            for (raw, resolved) in raw.stages.iter_mut().zip(resolved.stages) {
+                attach(raw, resolved, bindings);
+            }
+        }
+        (Statement::List(raw), Statement::List(resolved)) => {
+            attach(&mut raw.first, *resolved.first, bindings);
+            for ((_, raw), (_, resolved)) in raw.rest.iter_mut().zip(resolved.rest) {
+                attach(raw, resolved, bindings);
+            }
+        }
+        (Statement::Subshell(raw), Statement::Subshell(resolved))
+        | (Statement::CommandSubstitution(raw), Statement::CommandSubstitution(resolved)) => {
+            attach(raw, *resolved, bindings);
+        }
+        _ => {}
+    }
+}
+
+/// The bindings whose variable `cmd` reads, first occurrence of each name.
+fn provenance(cmd: &SimpleCommand, bindings: &[Assignment]) -> Vec<Assignment> {
+    let texts: Vec<&str> = cmd
+        .name
+        .iter()
+        .map(String::as_str)
+        .chain(cmd.argv.iter().map(|a| a.text.as_str()))
+        .chain(cmd.assignments.iter().map(|a| a.value.as_str()))
+        .chain(cmd.redirects.iter().map(|r| r.target.as_str()))
+        .chain(
+            cmd.redirects
+                .iter()
+                .filter_map(|r| r.heredoc.as_ref())
+                .map(|h| h.body.as_str()),
+        )
+        .collect();
+    let mut seen = HashSet::new();
+    bindings
+        .iter()
+        .filter(|b| texts.iter().any(|text| mentions(text, &b.name)))
+        .filter(|b| seen.insert(b.name.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Whether `text` contains `$name` or `${name}`.
+fn mentions(text: &str, name: &str) -> bool {
+    text.match_indices('$').any(|(i, _)| {
+        let rest = &text[i + 1..];
+        match rest.strip_prefix('{') {
+            Some(braced) => braced
+                .strip_prefix(name)
+                .is_some_and(|r| r.starts_with('}')),
+            None => rest
+                .strip_prefix(name)
+                .is_some_and(|r| !r.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_')),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{flatten, parse, Resolution, Statement};
+
+    /// The resolution of the last command in `source`.
+    fn resolution(source: &str) -> Option<Resolution> {
+        let stmt = parse(source).unwrap();
+        match flatten(&stmt).last() {
+            Some(Statement::SimpleCommand(cmd)) => cmd.resolution.as_deref().cloned(),
+            other => panic!("expected a command, got {other:?}"),
+        }
+    }
+
+    fn resolved_argv(source: &str) -> Option<Vec<String>> {
+        resolution(source).map(|r| r.command.argv.into_iter().map(|a| a.text).collect())
+    }
+
+    #[test]
+    fn test_resolves_quoted_and_unquoted_uses() {
+        let r = resolution("DIR=/tmp/build; rm -rf \"$DIR\" ${DIR}/x").unwrap();
+        let argv: Vec<&str> = r.command.argv.iter().map(|a| a.text.as_str()).collect();
+        assert_eq!(argv, ["-rf", "/tmp/build", "/tmp/build/x"]);
+        assert_eq!(r.bindings.len(), 1);
+        assert_eq!(r.bindings[0].name, "DIR");
+        assert_eq!(r.bindings[0].value, "/tmp/build");
+    }
+
+    #[test]
+    fn test_resolves_command_name_and_chained_values() {
+        let r = resolution("C=git; S=stat; X=${S}us; $C $X").unwrap();
+        assert_eq!(r.command.name.as_deref(), Some("git"));
+        assert_eq!(
+            resolved_argv("C=git; S=stat; X=${S}us; $C $X").unwrap(),
+            ["status"]
+        );
+    }
+
+    #[test]
+    fn test_literal_and_unknown_uses_stay_unresolved() {
+        assert_eq!(resolution("D=/; rm -rf '$D' \\$D"), None);
+        assert_eq!(resolution("D=/; rm -rf $E $1"), None);
+        assert_eq!(
+            resolved_argv("D=/; rm -rf $D $E").unwrap(),
+            ["-rf", "/", "$E"]
+        );
+    }
+
+    #[test]
+    fn test_values_that_split_or_glob_are_not_resolved() {
+        assert_eq!(resolution("D='a b'; rm $D"), None);
+        assert_eq!(resolution("D=*.txt; rm $D"), None);
+        assert_eq!(resolution("D=~/x; rm $D"), None);
+        assert_eq!(resolution("D=$(pwd); rm $D"), None);
+        assert_eq!(resolution("IFS=/; D=a/b; rm $D"), None);
+    }
+
+    #[test]
+    fn test_assignments_that_may_not_run_are_forgotten() {
+        assert_eq!(resolution("true && D=/; rm $D"), None);
+        assert_eq!(resolution("false || D=/; rm $D"), None);
+        assert_eq!(resolution("D=a; if x; then D=/; fi; rm $D"), None);
+        assert_eq!(resolution("D=a; case x in y) D=/;; esac; rm $D"), None);
+        assert_eq!(resolution("D=a; while x; do D=/; done; rm $D"), None);
+        assert_eq!(resolution("D=a; for i in 1; do rm $D; D=/; done"), None);
+        assert_eq!(resolution("D=a; for D in /; do :; done; rm $D"), None);
+        assert_eq!(resolution("D=a; D+=b; rm $D"), None);
+        // Branches that agree, and assignments that certainly ran, still count.
+        assert_eq!(
+            resolved_argv("D=a; if x; then D=a; fi; rm $D").unwrap(),
+            ["a"]
+        );
+        assert_eq!(resolved_argv("D=a && rm $D").unwrap(), ["a"]);
+        assert_eq!(resolved_argv("if D=a; then rm $D; fi").unwrap(), ["a"]);
+    }
+
+    #[test]
+    fn test_variable_writers_forget_bindings() {
+        for source in [
+            "D=a; read D; rm $D",
+            "D=a; unset D; rm $D",
+            "D=a; eval \"D=/\"; rm $D",
+            "D=a; local D=b; rm $D",
+            "D=a; declare -n D=E; rm $D",
+            "D=a; : ${D:=b}; rm $D",
+            "D=a; ((D=2)); rm $D",
+            "f() { D=/; }; D=a; f; rm $D",
+            "trap 'D=/' DEBUG; D=a; rm $D",
+            "D=a; $CMD; rm $D",
+        ] {
+            assert_eq!(resolution(source), None, "{source}");
+        }
+    }
+
+    #[test]
+    fn test_subshell_assignments_do_not_leak() {
+        assert_eq!(resolved_argv("D=a; (D=b); rm $D").unwrap(), ["a"]);
+        assert_eq!(resolved_argv("D=a; echo | D=b; rm $D").unwrap(), ["a"]);
+        assert_eq!(resolved_argv("D=a; X=$(D=b); rm $D").unwrap(), ["a"]);
+        assert_eq!(resolution("D=a X=b rm $X"), None);
+    }
+
+    #[test]
+    fn test_substitutions_and_heredocs_resolve() {
+        let stmt = parse("D=/; echo $(rm -rf $D)").unwrap();
+        let resolved: Vec<_> = flatten(&stmt)
+            .into_iter()
+            .filter_map(|leaf| match leaf {
+                Statement::SimpleCommand(cmd) => cmd.resolution.as_ref(),
+                _ => None,
+            })
+            .map(|r| {
+                let argv: Vec<&str> = r.command.argv.iter().map(|a| a.text.as_str()).collect();
+                format!("{} {}", r.command.name.as_deref().unwrap(), argv.join(" "))
+            })
+            .collect();
+        assert_eq!(resolved, ["echo $(rm -rf /)", "rm -rf /"]);
+
+        let r = resolution("D=/tmp; bash <<EOF\nrm -rf $D\nEOF").unwrap();
+        assert_eq!(r.command.stdin_heredoc().unwrap().body, "rm -rf /tmp");
+        // A value spelling the delimiter would end the body early.
+        assert_eq!(resolution("D=EOF; cat <<EOF\n$D\nEOF"), None);
+        assert_eq!(resolution("D=/; cat <<'EOF'\n$D\nEOF"), None);
+    }
+}
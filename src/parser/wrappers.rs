@@ -12,7 +12,7 @@ use super::{Arg, ArgMeta, Assignment, SimpleCommand, Statement};
 
 /// Maximum recursion depth for chained wrappers.
 /// If exceeded, evaluation falls back to ask via an Opaque node.
-const MAX_UNWRAP_DEPTH: usize = 16;
+pub(crate) const MAX_UNWRAP_DEPTH: usize = 16;
 
 /// How to skip past wrapper-specific arguments to find the inner command.
 #[derive(Debug, Clone, Copy)]
@@ -301,6 +301,7 @@ pub(crate) fn classify_env_invocation(cmd: &SimpleCommand) -> EnvInvocation {
         redirects: cmd.redirects.clone(),
         assignments: merged_assignments,
         embedded_substitutions: cmd.embedded_substitutions.clone(),
+        resolution: None,
     })
 }
 
@@ -471,6 +472,7 @@ pub fn unwrap_transparent(cmd: &SimpleCommand) -> Option<SimpleCommand> {
         redirects: cmd.redirects.clone(),
         assignments: merged_assignments,
         embedded_substitutions: cmd.embedded_substitutions.clone(),
+        resolution: None,
     })
 }
 
@@ -521,6 +523,7 @@ fn extract_find_exec(cmd: &SimpleCommand) -> Vec<SimpleCommand> {
                     redirects: cmd.redirects.clone(),
                     assignments: cmd.assignments.clone(),
                     embedded_substitutions: vec![],
+                    resolution: None,
                 });
             }
             if i < argv.len() {
@@ -570,6 +573,7 @@ fn extract_xargs_command(cmd: &SimpleCommand) -> Option<SimpleCommand> {
             redirects: cmd.redirects.clone(),
             assignments: cmd.assignments.clone(),
             embedded_substitutions: vec![],
+            resolution: None,
         })
     } else {
         None
@@ -837,6 +841,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         }
     }
 
@@ -1410,6 +1415,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         };
         assert!(unwrap_transparent(&cmd).is_none());
     }
@@ -1426,9 +1432,11 @@ mod tests {
                 fd: None,
                 op: RedirectOp::Write,
                 target: "/tmp/out".to_string(),
+                heredoc: None,
             }],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         };
         let inner = unwrap_transparent(&cmd).unwrap();
         assert_eq!(inner.name.as_deref(), Some("ls"));
@@ -1585,6 +1593,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         };
         let inner = unwrap_transparent(&cmd).expect("unwrap");
         assert_eq!(inner.name.as_deref(), Some("bash"));
@@ -1616,6 +1625,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         };
         let inners = extract_find_exec(&cmd);
         assert_eq!(inners.len(), 1);
@@ -1644,6 +1654,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         };
         let inner = extract_xargs_command(&cmd).expect("extract");
         assert_eq!(inner.name.as_deref(), Some("cat"));
@@ -0,0 +1,305 @@
+//! Canonical path resolution shared by file-tool path rules and redirect
+//! target matching.
+
+use std::path::{Component, Path, PathBuf};
+
+/// A path as written by the tool or command, alongside its canonical form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedPath {
+    pub raw: String,
+    pub resolved: String,
+}
+
+impl ResolvedPath {
+    /// Spellings to match rule globs against: the raw path, the resolved
+    /// path, and (when it lives under `$HOME`) the resolved path contracted
+    /// to `~/...`, so home-anchored patterns like `~/.ssh/id_*` fire on
+    /// absolute and relative spellings alike. Duplicates are dropped.
+    pub fn match_candidates(&self) -> Vec<String> {
+        let home = home_dir();
+        self.match_candidates_with_home(home.as_deref())
+    }
+
+    fn match_candidates_with_home(&self, home: Option<&Path>) -> Vec<String> {
+        let mut candidates = vec![self.raw.clone()];
+        for candidate in self.canonical_candidates_with_home(home) {
+            push_unique(&mut candidates, candidate);
+        }
+        candidates
+    }
+
+    /// `match_candidates` without the raw spelling: only the resolved path
+    /// and its `~/...` form, for globs that grant rather than restrict
+    /// access and so must not be satisfied by `..` or a symlink.
+    pub fn canonical_candidates(&self) -> Vec<String> {
+        let home = home_dir();
+        self.canonical_candidates_with_home(home.as_deref())
+    }
+
+    fn canonical_candidates_with_home(&self, home: Option<&Path>) -> Vec<String> {
+        let mut candidates = vec![self.resolved.clone()];
+        if let Some(home) = home {
+            let homes = [Some(home.to_path_buf()), std::fs::canonicalize(home).ok()];
+            for home in homes.into_iter().flatten() {
+                if let Ok(rest) = Path::new(&self.resolved).strip_prefix(&home) {
+                    if !rest.as_os_str().is_empty() {
+                        push_unique(&mut candidates, format!("~/{}", rest.display()));
+                    }
+                }
+            }
+        }
+        candidates
+    }
+
+    /// The resolved path as rules spell it: `~` or `~/...` when it lives
+    /// under `$HOME`, the absolute path otherwise.
+    pub fn home_contracted(&self) -> String {
+        let home = home_dir();
+        self.home_contracted_with_home(home.as_deref())
+    }
+
+    fn home_contracted_with_home(&self, home: Option<&Path>) -> String {
+        if let Some(home) = home {
+            let homes = [Some(home.to_path_buf()), std::fs::canonicalize(home).ok()];
+            for home in homes.into_iter().flatten() {
+                if let Ok(rest) = Path::new(&self.resolved).strip_prefix(&home) {
+                    return if rest.as_os_str().is_empty() {
+                        "~".to_string()
+                    } else {
+                        format!("~/{}", rest.display())
+                    };
+                }
+            }
+        }
+        self.resolved.clone()
+    }
+
+    /// Whether resolution changed the spelling of the path.
+    pub fn is_rewritten(&self) -> bool {
+        self.raw != self.resolved
+    }
+}
+
+/// Resolve `raw` to a canonical path: expand `~`, join relative paths onto
+/// `cwd` (when it is a non-empty absolute path), normalize `.`/`..`, and
+/// resolve symlinks for the longest prefix that exists on disk. Components
+/// that do not exist yet (e.g. a file about to be written) are re-appended
+/// lexically. Without a usable `cwd` a relative path stays relative.
+pub fn resolve(raw: &str, cwd: Option<&str>) -> ResolvedPath {
+    let home = home_dir();
+    resolve_with_home(raw, cwd, home.as_deref())
+}
+
+fn resolve_with_home(raw: &str, cwd: Option<&str>, home: Option<&Path>) -> ResolvedPath {
+    let expanded = expand_tilde_with_home(raw, home).unwrap_or_else(|| PathBuf::from(raw));
+    let joined = match cwd.filter(|c| Path::new(c).is_absolute()) {
+        Some(cwd) if expanded.is_relative() => Path::new(cwd).join(&expanded),
+        _ => expanded,
+    };
+    let normalized = normalize_lexically(&joined);
+    let resolved = if normalized.is_absolute() {
+        canonicalize_existing_prefix(&normalized)
+    } else {
+        normalized
+    };
+    ResolvedPath {
+        raw: raw.to_string(),
+        resolved: resolved.to_string_lossy().to_string(),
+    }
+}
+
+/// Expand a leading `~` or `~/` using `$HOME`. Returns `None` when the path
+/// needs `$HOME` and it is unset; other paths (including `~user`) are
+/// returned unchanged.
+pub fn expand_tilde(path: &str) -> Option<String> {
+    let home = home_dir();
+    expand_tilde_with_home(path, home.as_deref()).map(|p| p.to_string_lossy().to_string())
+}
+
+fn expand_tilde_with_home(path: &str, home: Option<&Path>) -> Option<PathBuf> {
+    if path == "~" {
+        return home.map(Path::to_path_buf);
+    }
+    if let Some(rest) = path.strip_prefix("~/") {
+        return home.map(|h| h.join(rest));
+    }
+    Some(PathBuf::from(path))
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .filter(|h| !h.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Fold `.` and `..` without touching the filesystem. `..` at the root is
+/// dropped; leading `..` on a relative path is kept.
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match out.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    out.pop();
+                }
+                Some(Component::RootDir) | Some(Component::Prefix(_)) => {}
+                _ => out.push(".."),
+            },
+            other => out.push(other.as_os_str()),
+        }
+    }
+    if out.as_os_str().is_empty() {
+        out.push(".");
+    }
+    out
+}
+
+/// Canonicalize the longest existing ancestor of an absolute path and
+/// re-append the missing tail, so symlinked directories (and a symlinked
+/// leaf, when it exists) resolve to their targets.
+fn canonicalize_existing_prefix(path: &Path) -> PathBuf {
+    let mut existing = path.to_path_buf();
+    let mut tail = Vec::new();
+    loop {
+        if let Ok(mut canonical) = std::fs::canonicalize(&existing) {
+            for name in tail.iter().rev() {
+                canonical.push(name);
+            }
+            return canonical;
+        }
+        match (existing.file_name(), existing.parent()) {
+            (Some(name), Some(parent)) => {
+                tail.push(name.to_os_string());
+                existing = parent.to_path_buf();
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+fn push_unique(candidates: &mut Vec<String>, candidate: String) {
+    if !candidates.contains(&candidate) {
+        candidates.push(candidate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve_in(raw: &str, cwd: Option<&str>) -> String {
+        resolve_with_home(raw, cwd, Some(Path::new("/nonexistent-home/user"))).resolved
+    }
+
+    #[test]
+    fn test_expands_tilde() {
+        assert_eq!(
+            resolve_in("~/.ssh/id_rsa", None),
+            "/nonexistent-home/user/.ssh/id_rsa"
+        );
+        assert_eq!(resolve_in("~", None), "/nonexistent-home/user");
+    }
+
+    #[test]
+    fn test_leaves_tilde_user_alone() {
+        assert_eq!(resolve_in("~root/.bashrc", None), "~root/.bashrc");
+    }
+
+    #[test]
+    fn test_joins_relative_path_with_cwd() {
+        assert_eq!(
+            resolve_in("src/main.rs", Some("/nonexistent-repo")),
+            "/nonexistent-repo/src/main.rs"
+        );
+    }
+
+    #[test]
+    fn test_normalizes_parent_dirs() {
+        assert_eq!(
+            resolve_in("./../../etc/passwd", Some("/nonexistent-repo/sub")),
+            "/etc/passwd"
+        );
+        assert_eq!(resolve_in("/../../etc/shadow", None), "/etc/shadow");
+    }
+
+    #[test]
+    fn test_relative_path_without_cwd_stays_relative() {
+        assert_eq!(resolve_in("a/./b/../c", None), "a/c");
+        assert_eq!(resolve_in("../x", None), "../x");
+        assert_eq!(resolve_in("a/b", Some("")), "a/b");
+    }
+
+    #[test]
+    fn test_resolves_symlinked_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().join("real");
+        std::fs::create_dir(&real).unwrap();
+        std::os::unix::fs::symlink(&real, dir.path().join("link")).unwrap();
+        let cwd = dir.path().to_string_lossy().to_string();
+
+        let resolved = resolve_in("link/new-file.txt", Some(&cwd));
+
+        let expected = std::fs::canonicalize(&real).unwrap().join("new-file.txt");
+        assert_eq!(resolved, expected.to_string_lossy());
+    }
+
+    #[test]
+    fn test_resolves_symlinked_leaf() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("bashrc");
+        std::fs::write(&target, "").unwrap();
+        std::os::unix::fs::symlink(&target, dir.path().join("notes.txt")).unwrap();
+        let cwd = dir.path().to_string_lossy().to_string();
+
+        let resolved = resolve_in("notes.txt", Some(&cwd));
+
+        assert_eq!(
+            resolved,
+            std::fs::canonicalize(&target).unwrap().to_string_lossy()
+        );
+    }
+
+    #[test]
+    fn test_match_candidates_include_home_contracted_form() {
+        let home = Path::new("/nonexistent-home/user");
+        let path = resolve_with_home(
+            "../.ssh/id_rsa",
+            Some("/nonexistent-home/user/repo"),
+            Some(home),
+        );
+
+        assert_eq!(
+            path.match_candidates_with_home(Some(home)),
+            vec![
+                "../.ssh/id_rsa".to_string(),
+                "/nonexistent-home/user/.ssh/id_rsa".to_string(),
+                "~/.ssh/id_rsa".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_home_contracted_spells_home_with_tilde() {
+        let home = Path::new("/nonexistent-home/user");
+        let spell = |raw: &str, cwd: &str| {
+            resolve_with_home(raw, Some(cwd), Some(home)).home_contracted_with_home(Some(home))
+        };
+        assert_eq!(
+            spell(".ssh/id_rsa", "/nonexistent-home/user"),
+            "~/.ssh/id_rsa"
+        );
+        assert_eq!(spell("..", "/nonexistent-home/user/repo"), "~");
+        assert_eq!(spell("*", "/nonexistent-root"), "/nonexistent-root/*");
+    }
+
+    #[test]
+    fn test_match_candidates_dedup_unchanged_path() {
+        let path = resolve_with_home("/nonexistent-x/y", None, None);
+        assert!(!path.is_rewritten());
+        assert_eq!(
+            path.match_candidates_with_home(None),
+            vec!["/nonexistent-x/y"]
+        );
+    }
+}
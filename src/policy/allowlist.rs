@@ -338,37 +338,43 @@ fn find_matching_entry<'a>(
     if !effective.allowlist_safe {
         return None;
     }
-    let argv = effective.argv;
 
-    for entry in &config.allowlists.commands {
-        if check_trust && entry.trust > config.trust_level {
-            continue;
-        }
+    config
+        .allowlists
+        .commands
+        .iter()
+        .filter(|entry| !check_trust || entry.trust <= config.trust_level)
+        .find(|entry| entry_matches(entry, cmd_name, &effective))
+}
 
-        let parts: Vec<&str> = entry.command.split_whitespace().collect();
-        if parts.is_empty() {
-            continue;
-        }
-        if parts[0] != normalize_command_name(cmd_name) {
-            continue;
-        }
-        if parts.len() == 1 {
-            return Some(entry);
-        }
-        let required_args = &parts[1..];
-        if let Some(SubcommandResolution::Resolved(subcommand)) = &effective.git_subcommand {
-            if required_args
-                .first()
-                .is_some_and(|required| *required != normalize_arg(subcommand))
-            {
-                continue;
-            }
-        }
-        if args_match_prefix(required_args, argv.as_ref()) {
-            return Some(entry);
+fn entry_matches(entry: &AllowlistEntry, cmd_name: &str, effective: &EffectiveArgv<'_>) -> bool {
+    let parts: Vec<&str> = entry.command.split_whitespace().collect();
+    if parts.is_empty() || parts[0] != normalize_command_name(cmd_name) {
+        return false;
+    }
+    if parts.len() == 1 {
+        return true;
+    }
+    let required_args = &parts[1..];
+    if let Some(SubcommandResolution::Resolved(subcommand)) = &effective.git_subcommand {
+        if required_args
+            .first()
+            .is_some_and(|required| *required != normalize_arg(subcommand))
+        {
+            return false;
         }
     }
-    None
+    args_match_prefix(required_args, effective.argv.as_ref())
+}
+
+/// Whether `entry` matches `cmd`, regardless of its trust level or of any
+/// earlier entry that also matches.
+pub fn allowlist_entry_matches(entry: &AllowlistEntry, cmd: &SimpleCommand) -> bool {
+    let Some(cmd_name) = cmd.name.as_deref() else {
+        return false;
+    };
+    let effective = effective_argv(cmd);
+    effective.allowlist_safe && entry_matches(entry, cmd_name, &effective)
 }
 
 /// Find the matching allowlist entry for a SimpleCommand.
@@ -441,6 +447,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         }
     }
 
@@ -451,6 +458,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         }
     }
 
@@ -467,14 +475,17 @@ mod tests {
             default_decision: crate::domain::Decision::Ask,
             safety_level: crate::policy::SafetyLevel::High,
             trust_level: crate::policy::TrustLevel::default(),
+            glob_expansion: Default::default(),
             allowlists: crate::policy::Allowlists {
                 commands: vec![crate::policy::AllowlistEntry {
                     command: command.to_string(),
                     trust: crate::policy::TrustLevel::Standard,
                     reason: None,
+                    examples: Default::default(),
                     source: crate::policy::RuleSource::default(),
                 }],
                 paths: vec![],
+                hosts: vec![],
             },
             rules: vec![],
         }
@@ -700,14 +711,17 @@ mod tests {
             default_decision: crate::domain::Decision::Ask,
             safety_level: crate::policy::SafetyLevel::High,
             trust_level: crate::policy::TrustLevel::default(),
+            glob_expansion: Default::default(),
             allowlists: crate::policy::Allowlists {
                 commands: vec![crate::policy::AllowlistEntry {
                     command: "git commit".to_string(),
                     trust: crate::policy::TrustLevel::Standard,
                     reason: None,
+                    examples: Default::default(),
                     source: crate::policy::RuleSource::default(),
                 }],
                 paths: vec![],
+                hosts: vec![],
             },
             rules: vec![],
         };
@@ -723,14 +737,17 @@ mod tests {
             default_decision: crate::domain::Decision::Ask,
             safety_level: crate::policy::SafetyLevel::High,
             trust_level: crate::policy::TrustLevel::default(),
+            glob_expansion: Default::default(),
             allowlists: crate::policy::Allowlists {
                 commands: vec![crate::policy::AllowlistEntry {
                     command: "git status".to_string(),
                     trust: crate::policy::TrustLevel::Standard,
                     reason: None,
+                    examples: Default::default(),
                     source: crate::policy::RuleSource::default(),
                 }],
                 paths: vec![],
+                hosts: vec![],
             },
             rules: vec![],
         };
@@ -830,28 +847,33 @@ mod tests {
             default_decision: crate::domain::Decision::Ask,
             safety_level: crate::policy::SafetyLevel::High,
             trust_level: crate::policy::TrustLevel::Minimal,
+            glob_expansion: Default::default(),
             allowlists: crate::policy::Allowlists {
                 commands: vec![
                     crate::policy::AllowlistEntry {
                         command: "ls".to_string(),
                         trust: crate::policy::TrustLevel::Minimal,
                         reason: None,
+                        examples: Default::default(),
                         source: crate::policy::RuleSource::default(),
                     },
                     crate::policy::AllowlistEntry {
                         command: "go build".to_string(),
                         trust: crate::policy::TrustLevel::Standard,
                         reason: None,
+                        examples: Default::default(),
                         source: crate::policy::RuleSource::default(),
                     },
                     crate::policy::AllowlistEntry {
                         command: "docker run".to_string(),
                         trust: crate::policy::TrustLevel::Full,
                         reason: None,
+                        examples: Default::default(),
                         source: crate::policy::RuleSource::default(),
                     },
                 ],
                 paths: vec![],
+                hosts: vec![],
             },
             rules: vec![],
         };
@@ -863,6 +885,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         };
         assert_eq!(
             find_allowlist_match(&config_minimal, &ls_cmd),
@@ -876,6 +899,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         };
         assert_eq!(
             find_allowlist_match(&config_minimal, &go_cmd),
@@ -889,6 +913,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         };
         assert_eq!(
             find_allowlist_match(&config_minimal, &docker_cmd),
@@ -899,6 +924,7 @@ mod tests {
         // Standard trust: minimal and standard match, full is skipped
         let config_standard = RulesConfig {
             trust_level: crate::policy::TrustLevel::Standard,
+            glob_expansion: Default::default(),
             ..config_minimal
         };
         assert_eq!(
@@ -920,14 +946,17 @@ mod tests {
             default_decision: crate::domain::Decision::Ask,
             safety_level: crate::policy::SafetyLevel::High,
             trust_level: crate::policy::TrustLevel::Standard,
+            glob_expansion: Default::default(),
             allowlists: crate::policy::Allowlists {
                 commands: vec![crate::policy::AllowlistEntry {
                     command: "git push".to_string(),
                     trust: crate::policy::TrustLevel::Full,
                     reason: Some("Pushes local commits to a remote repository".to_string()),
+                    examples: Default::default(),
                     source: crate::policy::RuleSource::default(),
                 }],
                 paths: vec![],
+                hosts: vec![],
             },
             rules: vec![],
         };
@@ -951,14 +980,17 @@ mod tests {
             default_decision: crate::domain::Decision::Ask,
             safety_level: crate::policy::SafetyLevel::High,
             trust_level: crate::policy::TrustLevel::Standard,
+            glob_expansion: Default::default(),
             allowlists: crate::policy::Allowlists {
                 commands: vec![crate::policy::AllowlistEntry {
                     command: "git push".to_string(),
                     trust: crate::policy::TrustLevel::Full,
                     reason: Some("Pushes local commits to a remote repository".to_string()),
+                    examples: Default::default(),
                     source: crate::policy::RuleSource::default(),
                 }],
                 paths: vec![],
+                hosts: vec![],
             },
             rules: vec![],
         };
@@ -969,6 +1001,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         };
 
         assert_eq!(find_allowlist_reason(&config, &cmd), None);
@@ -981,14 +1014,17 @@ mod tests {
             default_decision: crate::domain::Decision::Ask,
             safety_level: crate::policy::SafetyLevel::High,
             trust_level: crate::policy::TrustLevel::Standard,
+            glob_expansion: Default::default(),
             allowlists: crate::policy::Allowlists {
                 commands: vec![crate::policy::AllowlistEntry {
                     command: "git push".to_string(),
                     trust: crate::policy::TrustLevel::Full,
                     reason: None,
+                    examples: Default::default(),
                     source: crate::policy::RuleSource::default(),
                 }],
                 paths: vec![],
+                hosts: vec![],
             },
             rules: vec![],
         };
@@ -1004,14 +1040,17 @@ mod tests {
             default_decision: crate::domain::Decision::Ask,
             safety_level: crate::policy::SafetyLevel::High,
             trust_level: crate::policy::TrustLevel::default(),
+            glob_expansion: Default::default(),
             allowlists: crate::policy::Allowlists {
                 commands: vec![crate::policy::AllowlistEntry {
                     command: "git status".to_string(),
                     trust: crate::policy::TrustLevel::Standard,
                     reason: None,
+                    examples: Default::default(),
                     source: crate::policy::RuleSource::default(),
                 }],
                 paths: vec![],
+                hosts: vec![],
             },
             rules: vec![],
         };
@@ -1108,14 +1147,17 @@ mod tests {
             default_decision: crate::domain::Decision::Ask,
             safety_level: crate::policy::SafetyLevel::High,
             trust_level: crate::policy::TrustLevel::Standard,
+            glob_expansion: Default::default(),
             allowlists: crate::policy::Allowlists {
                 commands: vec![crate::policy::AllowlistEntry {
                     command: "uv run yamllint".to_string(),
                     trust: crate::policy::TrustLevel::Standard,
                     reason: None,
+                    examples: Default::default(),
                     source: crate::policy::RuleSource::default(),
                 }],
                 paths: vec![],
+                hosts: vec![],
             },
             rules: vec![],
         };
@@ -1126,6 +1168,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         });
 
         let extra_leaf_covered = Statement::SimpleCommand(SimpleCommand {
@@ -1134,6 +1177,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         });
 
         let extra_leaf_not_covered = Statement::SimpleCommand(SimpleCommand {
@@ -1142,6 +1186,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         });
 
         let leaves = vec![&original_leaf];
@@ -1165,14 +1210,17 @@ mod tests {
             default_decision: crate::domain::Decision::Ask,
             safety_level: crate::policy::SafetyLevel::High,
             trust_level: crate::policy::TrustLevel::Standard,
+            glob_expansion: Default::default(),
             allowlists: crate::policy::Allowlists {
                 commands: vec![crate::policy::AllowlistEntry {
                     command: "uv run prefect config view".to_string(),
                     trust: crate::policy::TrustLevel::Standard,
                     reason: None,
+                    examples: Default::default(),
                     source: crate::policy::RuleSource::default(),
                 }],
                 paths: vec![],
+                hosts: vec![],
             },
             rules: vec![],
         };
@@ -1184,6 +1232,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         });
 
         // Extra leaf: prefect (the unwrapped inner command)
@@ -1193,6 +1242,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         });
 
         let extra_leaf_not_covered = Statement::SimpleCommand(SimpleCommand {
@@ -1201,6 +1251,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         });
 
         let leaves = vec![&original_leaf];
@@ -1224,14 +1275,17 @@ mod tests {
             default_decision: crate::domain::Decision::Ask,
             safety_level: crate::policy::SafetyLevel::High,
             trust_level: crate::policy::TrustLevel::Standard,
+            glob_expansion: Default::default(),
             allowlists: crate::policy::Allowlists {
                 commands: vec![crate::policy::AllowlistEntry {
                     command: "timeout".to_string(),
                     trust: crate::policy::TrustLevel::Standard,
                     reason: None,
+                    examples: Default::default(),
                     source: crate::policy::RuleSource::default(),
                 }],
                 paths: vec![],
+                hosts: vec![],
             },
             rules: vec![],
         };
@@ -1242,6 +1296,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         });
 
         let extra_leaf = Statement::SimpleCommand(SimpleCommand {
@@ -1250,6 +1305,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         });
 
         let leaves = vec![&original_leaf];
@@ -1278,6 +1334,7 @@ mod tests {
             redirects: vec![],
             assignments: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         }
     }
 
@@ -1287,14 +1344,17 @@ mod tests {
             default_decision: crate::domain::Decision::Ask,
             safety_level: crate::policy::SafetyLevel::High,
             trust_level: crate::policy::TrustLevel::Standard,
+            glob_expansion: Default::default(),
             allowlists: crate::policy::Allowlists {
                 commands: vec![crate::policy::AllowlistEntry {
                     command: "uv run ruff".to_string(),
                     trust: crate::policy::TrustLevel::Standard,
                     reason: None,
+                    examples: Default::default(),
                     source: crate::policy::RuleSource::default(),
                 }],
                 paths: vec![],
+                hosts: vec![],
             },
             rules: vec![],
         }
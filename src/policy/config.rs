@@ -5,8 +5,11 @@ pub use crate::config::{
     load_embedded_rules, load_embedded_rules_with_info, load_global_config, load_project_config,
     load_rules, load_rules_with_info, merge_overlay_config, merge_project_config,
     project_config_path, validate_ai_judge_prompt, validate_project_ai_judge_prompt,
-    AllowlistEntry, Allowlists, ArgsMatcher, EnvException, EnvMatcher, EnvValueClass, FlagsMatcher,
-    GitConfigMatcher, GitConfigSource, LoadedConfig, LoadedFileInfo, Matcher, PartialRulesConfig,
-    PipelineMatcher, ProjectAiJudgeConfig, ProjectConfig, RedirectMatcher, Rule, RuleSource,
-    RulesConfig, RulesManifestConfig, SafetyLevel, StageMatcher, StringOrList, TrustLevel,
+    AllowlistEntry, Allowlists, ArgRegex, ArgsMatcher, EnvException, EnvMatcher, EnvValueClass,
+    Examples, FlagsMatcher, GitConfigMatcher, GitConfigSource, GlobExpansion, JsonPointer,
+    LoadedConfig, LoadedFileInfo, Matcher, MatcherScope, McpInputCondition, McpMatcher,
+    PartialRulesConfig, PathMatcher, PathTool, PipelineMatcher, PortList, ProjectAiJudgeConfig,
+    ProjectConfig, RedirectMatcher, RegexPattern, Rule, RuleCondition, RuleSource, RulesConfig,
+    RulesManifestConfig, SafetyLevel, StageMatcher, StringOrList, TrustLevel, UrlMatcher,
+    ValuePattern,
 };
@@ -0,0 +1,280 @@
+//! The directory each leaf runs in, followed across `cd` through lists and
+//! subshells, so relative paths are matched the way they resolve:
+//! `cd / && rm -rf *` is evaluated as `rm -rf /*` as well as written.
+
+use crate::parser::{self, ArgMeta, ListOp, RedirectOp, SimpleCommand, Statement};
+use crate::paths;
+
+/// Every simple command in `stmt` other than `cd` itself, paired with the
+/// directory it runs in when that is known. `cwd` is the hook's working
+/// directory, when known.
+pub(super) fn commands_with_cwd<'a>(
+    stmt: &'a Statement,
+    cwd: Option<&str>,
+) -> Vec<(&'a SimpleCommand, Option<String>)> {
+    let mut walker = Walker {
+        commands: Vec::new(),
+    };
+    walker.walk(stmt, cwd.map(str::to_string));
+    walker.commands
+}
+
+/// The located form of every leaf that runs after a `cd` moved away from
+/// `cwd`, each followed by the commands its wrappers run. A leaf is
+/// located when its relative path arguments or redirect targets resolve
+/// differently in that directory.
+pub(super) fn collect_located_statements(stmt: &Statement, cwd: Option<&str>) -> Vec<Statement> {
+    let mut out = Vec::new();
+    for (cmd, dir) in commands_with_cwd(stmt, cwd) {
+        let Some(dir) = dir.filter(|dir| Some(dir.as_str()) != cwd) else {
+            continue;
+        };
+        let resolved = cmd.resolution.as_ref().map(|r| &r.command);
+        for cmd in std::iter::once(cmd).chain(resolved) {
+            if let Some(located) = located_command(cmd, &dir) {
+                let located = Statement::SimpleCommand(located);
+                let inner = parser::wrappers::extract_inner_commands(&located);
+                out.push(located);
+                out.extend(inner);
+            }
+        }
+    }
+    out
+}
+
+/// The hook's working directory and the commands that run in it, before
+/// any `cd` moves away. Their relative redirect targets are matched as
+/// they resolve from it; commands that run elsewhere are located instead.
+pub(super) struct StartDir<'a> {
+    cwd: Option<&'a str>,
+    commands: Vec<&'a SimpleCommand>,
+}
+
+impl<'a> StartDir<'a> {
+    pub(super) fn new(stmt: &'a Statement, cwd: Option<&'a str>) -> Self {
+        let commands = commands_with_cwd(stmt, cwd)
+            .into_iter()
+            .filter(|(_, dir)| cwd.is_some() && dir.as_deref() == cwd)
+            .map(|(cmd, _)| cmd)
+            .collect();
+        StartDir { cwd, commands }
+    }
+
+    /// The start directory, when `leaf` is one of the commands that run in
+    /// it (compared by address, so copies made by unwrapping never are).
+    pub(super) fn of(&self, leaf: &Statement) -> Option<&'a str> {
+        match leaf {
+            Statement::SimpleCommand(cmd)
+                if self.commands.iter().any(|c| std::ptr::eq(*c, cmd)) =>
+            {
+                self.cwd
+            }
+            _ => None,
+        }
+    }
+}
+
+struct Walker<'a> {
+    commands: Vec<(&'a SimpleCommand, Option<String>)>,
+}
+
+impl<'a> Walker<'a> {
+    /// Visit `stmt` running in `cwd` (None when unknown) and return the
+    /// directory the next statement runs in.
+    fn walk(&mut self, stmt: &'a Statement, cwd: Option<String>) -> Option<String> {
+        match stmt {
+            Statement::SimpleCommand(cmd) => {
+                for sub in &cmd.embedded_substitutions {
+                    self.walk(sub, cwd.clone());
+                }
+                match cmd.name.as_deref() {
+                    Some("cd" | "pushd" | "popd") => cd_target(cmd, cwd.as_deref()),
+                    _ => {
+                        self.commands.push((cmd, cwd.clone()));
+                        cwd
+                    }
+                }
+            }
+            // Every stage runs in its own subshell.
+            Statement::Pipeline(pipe) => {
+                for stage in &pipe.stages {
+                    self.walk(stage, cwd.clone());
+                }
+                cwd
+            }
+            Statement::List(list) => {
+                let mut chain_start = cwd.clone();
+                let mut cwd = self.walk(&list.first, cwd);
+                for (op, next) in &list.rest {
+                    match op {
+                        ListOp::And => cwd = self.walk(next, cwd),
+                        ListOp::Semi => {
+                            chain_start = cwd.clone();
+                            cwd = self.walk(next, cwd);
+                        }
+                        // `a || b` runs `b` after any command of the chain
+                        // failed, and continues in whichever directory ran
+                        // last: known only when they all agree.
+                        ListOp::Or => {
+                            let entry = cwd.clone().filter(|_| chain_start == cwd);
+                            let after = self.walk(next, entry);
+                            if after != cwd {
+                                cwd = None;
+                            }
+                        }
+                    }
+                }
+                cwd
+            }
+            Statement::Subshell(inner) | Statement::CommandSubstitution(inner) => {
+                self.walk(inner, cwd.clone());
+                cwd
+            }
+            Statement::Opaque(_) => None,
+            Statement::Empty => cwd,
+        }
+    }
+}
+
+/// The directory `cd`/`pushd` moves to from `cwd`: a literal target (or
+/// `$HOME` for a bare `cd`), resolved and symlink-free. None when it
+/// cannot be known statically — `cd -`, `popd`, `cd "$DIR"`, or a
+/// relative target from an unknown directory.
+fn cd_target(cmd: &SimpleCommand, cwd: Option<&str>) -> Option<String> {
+    let cmd = cmd.resolution.as_ref().map_or(cmd, |r| &r.command);
+    let args: Vec<_> = cmd
+        .argv
+        .iter()
+        .skip_while(|arg| matches!(arg.text.as_str(), "-L" | "-P" | "-e" | "-@"))
+        .collect();
+    let target = match (cmd.name.as_deref(), args.as_slice()) {
+        (Some("cd"), []) => "~",
+        (Some("cd" | "pushd"), [arg]) if is_literal(arg.meta) && arg.text != "-" => {
+            arg.text.as_str()
+        }
+        _ => return None,
+    };
+    if target.starts_with('-') || target.starts_with('+') {
+        return None;
+    }
+    let resolved = paths::resolve(target, cwd).resolved;
+    std::path::Path::new(&resolved)
+        .is_absolute()
+        .then_some(resolved)
+}
+
+/// `cmd` with its relative path arguments and redirect targets resolved
+/// against `cwd`, spelled as rules spell them (`~/...` under `$HOME`).
+/// None when nothing changes. Substitutions are left out; they are
+/// walked, and located, on their own.
+fn located_command(cmd: &SimpleCommand, cwd: &str) -> Option<SimpleCommand> {
+    let mut located = SimpleCommand {
+        embedded_substitutions: vec![],
+        resolution: None,
+        ..cmd.clone()
+    };
+    let mut changed = false;
+    for arg in &mut located.argv {
+        if let Some(path) = locate_path(&arg.text, cwd) {
+            arg.text = path;
+            changed = true;
+        }
+    }
+    for redirect in &mut located.redirects {
+        let is_file = matches!(
+            redirect.op,
+            RedirectOp::Write
+                | RedirectOp::Append
+                | RedirectOp::Read
+                | RedirectOp::ReadWrite
+                | RedirectOp::Clobber
+        );
+        if let Some(path) = locate_path(&redirect.target, cwd).filter(|_| is_file) {
+            redirect.target = path;
+            changed = true;
+        }
+    }
+    changed.then_some(located)
+}
+
+/// `word` resolved against `cwd`, when it reads as a relative path: not a
+/// flag, URL, absolute or `~` path, and free of expansions.
+fn locate_path(word: &str, cwd: &str) -> Option<String> {
+    if word.is_empty()
+        || word.starts_with(['-', '/', '~'])
+        || word.contains(['$', '`'])
+        || word.contains("://")
+    {
+        return None;
+    }
+    Some(paths::resolve(word, Some(cwd)).home_contracted())
+}
+
+fn is_literal(meta: ArgMeta) -> bool {
+    matches!(
+        meta,
+        ArgMeta::PlainWord | ArgMeta::RawString | ArgMeta::SafeString
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::findings::statement_text;
+
+    fn located(command: &str, cwd: Option<&str>) -> Vec<String> {
+        let stmt = parser::parse(command).unwrap();
+        collect_located_statements(&stmt, cwd)
+            .iter()
+            .map(statement_text)
+            .collect()
+    }
+
+    #[test]
+    fn test_cd_locates_following_commands() {
+        assert_eq!(located("cd / && rm -rf *", None), ["rm -rf /*"]);
+        assert_eq!(
+            located("cd /etc; cat passwd > copy", None),
+            ["cat /etc/passwd >/etc/copy"]
+        );
+        assert_eq!(located("cd /etc && cd .. && rm -rf .", None), ["rm -rf /"]);
+    }
+
+    #[test]
+    fn test_commands_before_cd_or_in_the_start_directory_are_not_located() {
+        assert!(located("rm -rf build && cd /", None).is_empty());
+        assert!(located("rm -rf build", Some("/srv/app")).is_empty());
+        assert!(located("cd /srv/app && rm -rf build", Some("/srv/app")).is_empty());
+        assert_eq!(
+            located("cd .. && rm -rf app", Some("/srv/app")),
+            ["rm -rf /srv/app"]
+        );
+    }
+
+    #[test]
+    fn test_subshells_and_pipeline_stages_do_not_leak_cd() {
+        assert!(located("(cd /) && rm -rf *", None).is_empty());
+        assert!(located("cd / | rm -rf *", None).is_empty());
+        assert_eq!(located("(cd / && rm -rf *)", None), ["rm -rf /*"]);
+        assert_eq!(located("cd / && echo $(rm -rf *)", None), ["rm -rf /*"]);
+    }
+
+    #[test]
+    fn test_unknown_targets_forget_the_directory() {
+        assert!(located("cd / && cd \"$DIR\" && rm -rf *", None).is_empty());
+        assert!(located("cd / && cd - && rm -rf *", None).is_empty());
+        assert!(located("cd /tmp || rm -rf *", None).is_empty());
+        assert!(located("cd /tmp || exit; rm -rf *", None).is_empty());
+        assert!(located("cd /tmp && ls || cat x", None).is_empty());
+        assert_eq!(located("cd /tmp && ls x || true", None), ["ls /tmp/x"]);
+    }
+
+    #[test]
+    fn test_flags_urls_and_expansions_are_left_alone() {
+        assert_eq!(
+            located("cd /srv && curl -o out https://example.com/x $HOME", None),
+            ["curl -o /srv/out https://example.com/x $HOME"]
+        );
+        assert!(located("cd /srv && ls -la", None).is_empty());
+    }
+}
@@ -0,0 +1,273 @@
+//! Inline `examples:` on rules and allowlist entries, for `longline test`.
+//!
+//! A rule example matches when the rule's matcher fires on it, whatever the
+//! configured safety level: shell commands go through the same leaf and
+//! pipeline walk as `explain`, `path` rule examples are target paths and
+//! `mcp` rule examples are `mcp__<server>__<tool>` names called with no
+//! input. An allowlist example matches when the entry covers one of the
+//! command's leaves, whatever the configured trust level.
+
+use serde::Serialize;
+
+use crate::parser::{self, Statement};
+
+use super::allowlist::allowlist_entry_matches;
+use super::matching::{matches_mcp_rule, matches_path_rule};
+use super::{
+    collect_substitution_leaves, explain, flatten_top_only, split_mcp_tool_name, AllowlistEntry,
+    Examples, MatcherScope, PathTool, Rule, RuleSource, RulesConfig,
+};
+
+/// What an example belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExampleOwner {
+    Rule,
+    Allowlist,
+}
+
+/// One example and whether it behaved as declared.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExampleResult {
+    pub owner: ExampleOwner,
+    /// The rule id, or the allowlist entry's command.
+    pub id: String,
+    pub source: RuleSource,
+    pub example: String,
+    pub expect_match: bool,
+    pub matched: bool,
+    /// Set when the example could not be evaluated (a shell parse error or
+    /// a malformed MCP tool name); the example then fails.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ExampleResult {
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.matched == self.expect_match
+    }
+}
+
+/// Check every example on every rule and allowlist entry of `config`, in
+/// config order.
+pub fn check_examples(config: &RulesConfig) -> Vec<ExampleResult> {
+    let rules = config.rules.iter().flat_map(|rule| {
+        expand(&rule.examples).map(move |(example, expect_match)| {
+            let outcome = rule_matches_example(config, rule, example);
+            result(
+                ExampleOwner::Rule,
+                &rule.id,
+                rule.source,
+                example,
+                expect_match,
+                outcome,
+            )
+        })
+    });
+    let allowlist = config.allowlists.commands.iter().flat_map(|entry| {
+        expand(&entry.examples).map(move |(example, expect_match)| {
+            let outcome = allowlist_matches_example(entry, example);
+            result(
+                ExampleOwner::Allowlist,
+                &entry.command,
+                entry.source,
+                example,
+                expect_match,
+                outcome,
+            )
+        })
+    });
+    rules.chain(allowlist).collect()
+}
+
+fn expand(examples: &Examples) -> impl Iterator<Item = (&str, bool)> {
+    let matches = examples.matches.iter().map(|e| (e.as_str(), true));
+    let not_matches = examples.not_matches.iter().map(|e| (e.as_str(), false));
+    matches.chain(not_matches)
+}
+
+fn result(
+    owner: ExampleOwner,
+    id: &str,
+    source: RuleSource,
+    example: &str,
+    expect_match: bool,
+    outcome: Result<bool, String>,
+) -> ExampleResult {
+    let (matched, error) = match outcome {
+        Ok(matched) => (matched, None),
+        Err(e) => (false, Some(e)),
+    };
+    ExampleResult {
+        owner,
+        id: id.to_string(),
+        source,
+        example: example.to_string(),
+        expect_match,
+        matched,
+        error,
+    }
+}
+
+fn rule_matches_example(config: &RulesConfig, rule: &Rule, example: &str) -> Result<bool, String> {
+    match rule.matcher.scope() {
+        Some(MatcherScope::Path) => {
+            let targets = [example.to_string()];
+            Ok([PathTool::Read, PathTool::Search, PathTool::Write]
+                .into_iter()
+                .any(|tool| matches_path_rule(&rule.matcher, tool, &targets)))
+        }
+        Some(MatcherScope::Mcp) => {
+            let (server, tool) = split_mcp_tool_name(example)
+                .ok_or_else(|| format!("{example} is not an mcp__<server>__<tool> name"))?;
+            Ok(matches_mcp_rule(
+                &rule.matcher,
+                server,
+                tool,
+                &serde_json::json!({}),
+            ))
+        }
+        _ => {
+            let trace = explain(config, example, None)?;
+            let hit = |hits: &[super::RuleHit]| hits.iter().any(|hit| hit.id == rule.id);
+            Ok(trace.leaves.iter().any(|leaf| hit(&leaf.rules))
+                || trace.pipelines.iter().any(|pipe| hit(&pipe.rules)))
+        }
+    }
+}
+
+/// Whether `entry` covers any leaf `evaluate` looks at: as written,
+/// unwrapped from wrappers, or inside command substitutions.
+fn allowlist_matches_example(entry: &AllowlistEntry, example: &str) -> Result<bool, String> {
+    let stmt = parser::parse(example)?;
+    let extra_stmts = parser::wrappers::extract_inner_commands(&stmt);
+    let mut leaves = flatten_top_only(&stmt)
+        .into_iter()
+        .chain(extra_stmts.iter().flat_map(parser::flatten))
+        .chain(collect_substitution_leaves(&stmt));
+    Ok(leaves.any(|leaf| match leaf {
+        Statement::SimpleCommand(cmd) => allowlist_entry_matches(entry, cmd),
+        _ => false,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(yaml: &str) -> RulesConfig {
+        serde_norway::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_embedded_examples_pass() {
+        let config = crate::config::load_embedded_rules().unwrap();
+        let results = check_examples(&config);
+        assert!(!results.is_empty());
+        let failed: Vec<_> = results.iter().filter(|r| !r.passed()).collect();
+        assert!(failed.is_empty(), "{failed:#?}");
+    }
+
+    #[test]
+    fn test_rule_examples_pass_and_fail() {
+        let config = config(
+            r#"
+version: 1
+safety_level: critical
+rules:
+  - id: rm-recursive
+    level: high
+    match:
+      command: rm
+      flags:
+        any_of: ["-r", "-rf"]
+    decision: ask
+    reason: recursive delete
+    examples:
+      matches: ["timeout 5 rm -rf build", "echo $(rm -r x)"]
+      not_matches: ["rm file.txt", "rm -rf build"]
+"#,
+        );
+        let results = check_examples(&config);
+        assert_eq!(results.len(), 4);
+        let passed: Vec<bool> = results.iter().map(ExampleResult::passed).collect();
+        assert_eq!(passed, vec![true, true, true, false]);
+        assert!(results[3].matched);
+    }
+
+    #[test]
+    fn test_examples_run_on_rules_whose_when_fails() {
+        let mut config = config(
+            r#"
+version: 1
+rules:
+  - id: codex-curl
+    level: high
+    match: { command: curl }
+    decision: deny
+    reason: r
+    when: { runtime: codex }
+    examples:
+      matches: ["curl example.com"]
+"#,
+        );
+        config.rules[0].applies = false;
+        let results = check_examples(&config);
+        assert_eq!(results.len(), 1);
+        assert!(results[0].passed(), "{results:#?}");
+    }
+
+    #[test]
+    fn test_allowlist_examples_ignore_trust_and_earlier_entries() {
+        let config = config(
+            r#"
+version: 1
+trust_level: minimal
+allowlists:
+  commands:
+    - { command: git, trust: minimal }
+    - command: git status
+      trust: full
+      examples:
+        matches: ["cd repo && git status --short"]
+        not_matches: ["git log"]
+"#,
+        );
+        let results = check_examples(&config);
+        assert!(results.iter().all(ExampleResult::passed), "{results:?}");
+    }
+
+    #[test]
+    fn test_path_and_mcp_examples() {
+        let config = config(
+            r#"
+version: 1
+rules:
+  - id: env-files
+    level: high
+    match:
+      path:
+        target: "**/.env"
+    decision: deny
+    reason: env file
+    examples:
+      matches: ["/repo/.env"]
+      not_matches: ["/repo/.envrc"]
+  - id: pg
+    level: high
+    match:
+      mcp:
+        server: postgres
+    decision: ask
+    reason: database
+    examples:
+      matches: ["mcp__postgres__query"]
+      not_matches: ["mcp__github__search", "postgres"]
+"#,
+        );
+        let results = check_examples(&config);
+        let passed: Vec<bool> = results.iter().map(ExampleResult::passed).collect();
+        assert_eq!(passed, vec![true, true, true, true, false]);
+        assert!(results[4].error.is_some());
+    }
+}
@@ -0,0 +1,116 @@
+//! Per-leaf rule findings recorded alongside the policy decision, and the
+//! one-line leaf rendering shared by findings and `longline explain`.
+
+use serde::Serialize;
+
+use crate::domain::Decision;
+use crate::parser::{self, ListOp, SimpleCommand, Statement};
+
+/// Which collection a leaf was evaluated from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeafBucket {
+    /// A leaf of the statement as written.
+    Original,
+    /// Extracted from a wrapper (`timeout`, `xargs`, `find -exec`, `bash -c`, ...).
+    Wrapper,
+    /// Inside a command or process substitution.
+    Substitution,
+    /// A leaf with variables assigned earlier in the command substituted,
+    /// or a command its wrappers run.
+    Resolved,
+    /// A leaf that runs after a `cd`, with its relative paths resolved
+    /// against the directory it runs in, or a command its wrappers run.
+    Located,
+    /// A destructive command with a glob argument replaced by one of the
+    /// paths it matches on disk.
+    Expanded,
+}
+
+impl LeafBucket {
+    pub fn is_extra(self) -> bool {
+        self != LeafBucket::Original
+    }
+}
+
+impl std::fmt::Display for LeafBucket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            LeafBucket::Original => "original",
+            LeafBucket::Wrapper => "wrapper",
+            LeafBucket::Substitution => "substitution",
+            LeafBucket::Resolved => "resolved",
+            LeafBucket::Located => "located",
+            LeafBucket::Expanded => "expanded",
+        })
+    }
+}
+
+/// A rule (or classifier) id that matched one leaf or pipeline during
+/// evaluation. Every match is recorded, not just the one that decided.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub rule_id: String,
+    pub decision: Decision,
+    pub leaf: String,
+    pub bucket: LeafBucket,
+}
+
+/// The leaf (or pipeline) whose result became the final decision.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DecidingLeaf {
+    pub text: String,
+    pub bucket: LeafBucket,
+}
+
+pub(super) fn list_op(op: &ListOp) -> &'static str {
+    match op {
+        ListOp::Semi => ";",
+        ListOp::And => "&&",
+        ListOp::Or => "||",
+    }
+}
+
+/// Render a statement back to a single line of shell, for display only.
+pub(super) fn statement_text(stmt: &Statement) -> String {
+    match stmt {
+        Statement::SimpleCommand(cmd) => command_text(cmd),
+        Statement::Pipeline(p) => pipeline_text(p),
+        Statement::List(l) => {
+            let mut out = statement_text(&l.first);
+            for (op, s) in &l.rest {
+                out.push_str(&format!(" {} {}", list_op(op), statement_text(s)));
+            }
+            out
+        }
+        Statement::Subshell(inner) => format!("( {} )", statement_text(inner)),
+        Statement::CommandSubstitution(inner) => format!("$( {} )", statement_text(inner)),
+        Statement::Opaque(text) => text.clone(),
+        Statement::Empty => String::new(),
+    }
+}
+
+pub(super) fn pipeline_text(p: &parser::Pipeline) -> String {
+    let stages: Vec<String> = p.stages.iter().map(statement_text).collect();
+    let joined = stages.join(" | ");
+    if p.negated {
+        format!("! {joined}")
+    } else {
+        joined
+    }
+}
+
+pub(super) fn command_text(cmd: &SimpleCommand) -> String {
+    let mut parts: Vec<String> = cmd
+        .assignments
+        .iter()
+        .map(|a| format!("{}={}", a.name, a.value))
+        .collect();
+    parts.extend(cmd.name.clone());
+    parts.extend(cmd.argv.iter().map(|a| a.text.clone()));
+    parts.extend(cmd.redirects.iter().map(|r| {
+        let fd = r.fd.map(|fd| fd.to_string()).unwrap_or_default();
+        format!("{fd}{}{}", r.op, r.target)
+    }));
+    parts.join(" ")
+}
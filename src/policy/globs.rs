@@ -0,0 +1,432 @@
+//! Filesystem-aware glob expansion for destructive commands (see
+//! `GlobExpansion`). Rules only ever see a glob's text; here `rm -rf .*` is
+//! also checked against what `.*` matches in the directory it runs in, so
+//! a glob that would sweep up `.git` or `.env` asks and names them.
+
+use std::path::PathBuf;
+
+use crate::domain::{Decision, PolicyResult};
+use crate::parser::{self, ArgMeta, SimpleCommand, Statement};
+use crate::paths;
+
+use super::cwd::commands_with_cwd;
+use super::findings::{command_text, DecidingLeaf, Finding, LeafBucket};
+use super::matching::{normalize_command_name, redirect_targets};
+use super::{
+    env_dump_alias, evaluate_path_access, rule_matches_command, Evaluation, GlobExpansion,
+    PathTool, Rule, RulesConfig,
+};
+
+/// How many matching paths a reason names before summarizing the rest.
+const NAMED_MATCHES: usize = 5;
+
+/// A glob argument of a destructive command, expanded.
+pub(super) struct ExpandedGlob {
+    /// The command as written.
+    pub command: SimpleCommand,
+    pub pattern: String,
+    pub matches: Vec<GlobMatch>,
+    /// Expansion stopped at `max_matches`.
+    pub truncated: bool,
+}
+
+/// One path a glob expands to.
+pub(super) struct GlobMatch {
+    /// The path as bash passes it (`.git`, `./.env`, `~/notes`).
+    pub path: String,
+    /// The command with the glob replaced by the path as passed and, when
+    /// that differs, by its resolved spelling (`~/...` under `$HOME`), since
+    /// rules spell paths both ways.
+    pub probes: Vec<SimpleCommand>,
+    /// The path resolved, for the write `path` rules.
+    pub resolved: paths::ResolvedPath,
+}
+
+/// A rule that fires on a glob match but not on the command as written.
+pub(super) struct GlobHit<'a> {
+    pub path: &'a str,
+    pub probe: &'a SimpleCommand,
+    pub rule: &'a Rule,
+}
+
+/// Expand the glob arguments of every command `settings` names, including
+/// commands its wrappers run, against the directory each runs in. `cwd` is
+/// the hook's working directory, when known; relative globs in an unknown
+/// directory are not expanded.
+pub(super) fn collect_expanded_globs(
+    settings: &GlobExpansion,
+    stmt: &Statement,
+    cwd: Option<&str>,
+) -> Vec<ExpandedGlob> {
+    if !settings.enabled {
+        return vec![];
+    }
+    let mut out = Vec::new();
+    for (cmd, dir) in commands_with_cwd(stmt, cwd) {
+        let wrapped = Statement::SimpleCommand(SimpleCommand {
+            embedded_substitutions: vec![],
+            resolution: None,
+            ..cmd.clone()
+        });
+        let inner = parser::wrappers::extract_inner_commands(&wrapped);
+        let resolved = cmd.resolution.as_ref().map(|r| &r.command);
+        let commands = std::iter::once(cmd).chain(resolved).chain(
+            inner
+                .iter()
+                .flat_map(parser::flatten)
+                .filter_map(|leaf| match leaf {
+                    Statement::SimpleCommand(inner) => Some(inner),
+                    _ => None,
+                }),
+        );
+        for cmd in commands {
+            let destructive = cmd.name.as_deref().is_some_and(|name| {
+                let name = normalize_command_name(name);
+                settings.commands.iter().any(|c| c == name)
+            });
+            if destructive {
+                expand_command(cmd, dir.as_deref(), settings.max_matches, &mut out);
+            }
+        }
+    }
+    out
+}
+
+fn expand_command(
+    cmd: &SimpleCommand,
+    cwd: Option<&str>,
+    max_matches: usize,
+    out: &mut Vec<ExpandedGlob>,
+) {
+    for (index, arg) in cmd.argv.iter().enumerate() {
+        if !is_glob(arg) {
+            continue;
+        }
+        let Some((paths, truncated)) = expand(&arg.text, cwd, max_matches) else {
+            continue;
+        };
+        let matches = paths
+            .into_iter()
+            .map(|path| {
+                let resolved = paths::resolve(&path, cwd);
+                let mut spellings = vec![path.clone()];
+                let contracted = resolved.home_contracted();
+                if std::path::Path::new(&resolved.resolved).is_absolute() && contracted != path {
+                    spellings.push(contracted);
+                }
+                let probes = spellings
+                    .into_iter()
+                    .map(|spelling| {
+                        let mut probe = SimpleCommand {
+                            embedded_substitutions: vec![],
+                            resolution: None,
+                            ..cmd.clone()
+                        };
+                        probe.argv[index] = parser::Arg::plain(spelling);
+                        probe
+                    })
+                    .collect();
+                GlobMatch {
+                    resolved,
+                    path,
+                    probes,
+                }
+            })
+            .collect();
+        out.push(ExpandedGlob {
+            command: cmd.clone(),
+            pattern: arg.text.clone(),
+            matches,
+            truncated,
+        });
+    }
+}
+
+/// An unquoted word bash would glob: a `*`, `?` or `[` outside quotes,
+/// with no expansion or brace whose result longline cannot see. A word's
+/// text is unescaped, so `\*` reads as `*` and is expanded too; that can
+/// only add asks.
+fn is_glob(arg: &parser::Arg) -> bool {
+    arg.meta == ArgMeta::UnsafeString
+        && arg.text.contains(['*', '?', '['])
+        && !arg
+            .text
+            .contains(['\\', '$', '`', '\'', '"', '{', '}', '(', ')'])
+}
+
+/// The paths `pattern` matches, spelled as bash passes them, in bash's
+/// order, and whether expansion stopped at `max_matches`. Like bash without
+/// `dotglob`, a wildcard does not match a leading `.`, and `.`/`..` are
+/// never matched. None when the pattern matches nothing (bash then passes
+/// it through unchanged) or names a relative path in an unknown directory.
+fn expand(pattern: &str, cwd: Option<&str>, max_matches: usize) -> Option<(Vec<String>, bool)> {
+    let (root, rest) = match pattern.strip_prefix('/') {
+        Some(rest) => ("/", rest),
+        None => ("", pattern),
+    };
+    let components: Vec<&str> = rest.split('/').collect();
+    let mut found = vec![root.to_string()];
+    let mut truncated = false;
+    for (i, component) in components.iter().enumerate() {
+        let last = i + 1 == components.len();
+        if component.is_empty() {
+            // A trailing `/` keeps only directories.
+            if last {
+                found.retain(|path| on_disk(path, cwd).is_some_and(|p| p.is_dir()));
+                for path in &mut found {
+                    path.push('/');
+                }
+            }
+            continue;
+        }
+        if !component.contains(['*', '?', '[']) {
+            for path in &mut found {
+                *path = join(path, component);
+            }
+            continue;
+        }
+        let mut next = Vec::new();
+        'dirs: for path in &found {
+            let Some(dir) = on_disk(path, cwd) else {
+                continue;
+            };
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            let mut names: Vec<String> = entries
+                .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                .filter(|name| name != "." && name != "..")
+                .filter(|name| !name.starts_with('.') || component.starts_with('.'))
+                .filter(|name| glob_match::glob_match(component, name))
+                .filter(|name| last || dir.join(name).is_dir())
+                .collect();
+            names.sort();
+            for name in names {
+                if next.len() == max_matches {
+                    truncated = true;
+                    break 'dirs;
+                }
+                next.push(join(path, &name));
+            }
+        }
+        found = next;
+    }
+    found.retain(|path| on_disk(path, cwd).is_some_and(|p| p.symlink_metadata().is_ok()));
+    let globbed = components.iter().any(|c| c.contains(['*', '?', '[']));
+    (globbed && !found.is_empty()).then_some((found, truncated))
+}
+
+fn join(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() || prefix.ends_with('/') {
+        format!("{prefix}{name}")
+    } else {
+        format!("{prefix}/{name}")
+    }
+}
+
+/// Where `path` (as spelled in the pattern) lives on disk.
+fn on_disk(path: &str, cwd: Option<&str>) -> Option<PathBuf> {
+    let path = if path.is_empty() { "." } else { path };
+    let expanded = PathBuf::from(paths::expand_tilde(path)?);
+    if expanded.is_absolute() {
+        return Some(expanded);
+    }
+    let cwd = cwd.filter(|cwd| std::path::Path::new(cwd).is_absolute())?;
+    Some(PathBuf::from(cwd).join(expanded))
+}
+
+/// Every ask or deny rule that fires on a match of `glob` but not on the
+/// command as written: command rules on the probes, and the write `path`
+/// rules (with the redirect rules they imply) on the path itself.
+pub(super) fn glob_hits<'a>(config: &'a RulesConfig, glob: &'a ExpandedGlob) -> Vec<GlobHit<'a>> {
+    let active = |rule: &&Rule| config.is_active(rule) && rule.decision != Decision::Allow;
+    let firing = |cmd: &SimpleCommand| -> Vec<&'a Rule> {
+        let printenv_cmd = env_dump_alias(cmd);
+        let targets = redirect_targets(cmd, None);
+        config
+            .rules
+            .iter()
+            .filter(active)
+            .filter(|rule| rule_matches_command(config, rule, cmd, printenv_cmd.as_ref(), &targets))
+            .collect()
+    };
+    let baseline: Vec<&str> = firing(&glob.command)
+        .into_iter()
+        .map(|rule| rule.id.as_str())
+        .collect();
+    let mut hits = Vec::new();
+    for glob_match in &glob.matches {
+        for probe in &glob_match.probes {
+            for rule in firing(probe) {
+                let seen = hits
+                    .iter()
+                    .any(|hit: &GlobHit| hit.path == glob_match.path && hit.rule.id == rule.id);
+                if !seen && !baseline.contains(&rule.id.as_str()) {
+                    hits.push(GlobHit {
+                        path: &glob_match.path,
+                        probe,
+                        rule,
+                    });
+                }
+            }
+        }
+        let access = evaluate_path_access(config, PathTool::Write, &glob_match.resolved);
+        let rule = access
+            .rule_id
+            .as_deref()
+            .and_then(|id| config.rules.iter().find(|rule| rule.id == id));
+        if let Some(rule) = rule.filter(|_| access.decision != Decision::Allow) {
+            let seen = hits
+                .iter()
+                .any(|hit| hit.path == glob_match.path && hit.rule.id == rule.id);
+            if !seen {
+                hits.push(GlobHit {
+                    path: &glob_match.path,
+                    probe: &glob_match.probes[0],
+                    rule,
+                });
+            }
+        }
+    }
+    hits
+}
+
+/// The result of `glob` given its hits: the most restrictive rule, with a
+/// reason naming the paths any hit fired on.
+pub(super) fn glob_result(glob: &ExpandedGlob, hits: &[GlobHit]) -> Option<PolicyResult> {
+    let worst = hits.iter().max_by_key(|hit| hit.rule.decision)?;
+    let mut paths: Vec<&str> = Vec::new();
+    for hit in hits {
+        if !paths.contains(&hit.path) {
+            paths.push(hit.path);
+        }
+    }
+    let mut named = paths
+        .iter()
+        .take(NAMED_MATCHES)
+        .copied()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if paths.len() > NAMED_MATCHES {
+        named.push_str(&format!(" and {} more", paths.len() - NAMED_MATCHES));
+    }
+    let checked = if glob.truncated {
+        format!(" (first {} matches checked)", glob.matches.len())
+    } else {
+        String::new()
+    };
+    Some(PolicyResult {
+        decision: worst.rule.decision,
+        rule_id: Some(worst.rule.id.clone()),
+        reason: format!(
+            "`{}` matches {named}{checked}: {}",
+            glob.pattern, worst.rule.reason
+        ),
+    })
+}
+
+/// Fold the expanded globs into `evaluation`: every hit becomes a finding,
+/// and a glob result at least as restrictive as the decision so far
+/// replaces it, so the ask names the paths the glob would hit.
+pub(super) fn apply_expanded_globs(
+    config: &RulesConfig,
+    globs: &[ExpandedGlob],
+    evaluation: &mut Evaluation,
+) {
+    for glob in globs {
+        let hits = glob_hits(config, glob);
+        evaluation.findings.extend(hits.iter().map(|hit| Finding {
+            rule_id: hit.rule.id.clone(),
+            decision: hit.rule.decision,
+            leaf: command_text(hit.probe),
+            bucket: LeafBucket::Expanded,
+        }));
+        let Some(result) = glob_result(glob, &hits) else {
+            continue;
+        };
+        if result.decision >= evaluation.result.decision {
+            evaluation.result = result;
+            evaluation.deciding_leaf = Some(DecidingLeaf {
+                text: command_text(&glob.command),
+                bucket: LeafBucket::Expanded,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn workspace() -> tempfile::TempDir {
+        let dir = tempfile::TempDir::new().unwrap();
+        for sub in [".git", "src", "target"] {
+            fs::create_dir(dir.path().join(sub)).unwrap();
+        }
+        for file in [".env", "Cargo.toml", "src/main.rs"] {
+            fs::write(dir.path().join(file), "").unwrap();
+        }
+        dir
+    }
+
+    fn expand_in(
+        pattern: &str,
+        dir: &tempfile::TempDir,
+        max: usize,
+    ) -> Option<(Vec<String>, bool)> {
+        expand(pattern, dir.path().to_str(), max)
+    }
+
+    #[test]
+    fn test_expand_follows_bash_dotfile_rules() {
+        let dir = workspace();
+        assert_eq!(
+            expand_in("*", &dir, 200),
+            Some((
+                vec!["Cargo.toml".into(), "src".into(), "target".into()],
+                false
+            ))
+        );
+        assert_eq!(
+            expand_in(".*", &dir, 200),
+            Some((vec![".env".into(), ".git".into()], false))
+        );
+        assert_eq!(
+            expand_in("./*/", &dir, 200),
+            Some((vec!["./src/".into(), "./target/".into()], false))
+        );
+        assert_eq!(
+            expand_in("src/*.rs", &dir, 200),
+            Some((vec!["src/main.rs".into()], false))
+        );
+    }
+
+    #[test]
+    fn test_expand_caps_and_passes_unmatched_globs_through() {
+        let dir = workspace();
+        assert_eq!(
+            expand_in("*", &dir, 2),
+            Some((vec!["Cargo.toml".into(), "src".into()], true))
+        );
+        assert_eq!(expand_in("*.py", &dir, 200), None);
+        assert_eq!(expand("*", None, 200), None);
+        let absolute = format!("{}/.e*", dir.path().display());
+        assert_eq!(
+            expand(&absolute, None, 200),
+            Some((vec![format!("{}/.env", dir.path().display())], false))
+        );
+    }
+
+    #[test]
+    fn test_only_unquoted_globs_expand() {
+        let args = |command: &str| match parser::parse(command).unwrap() {
+            Statement::SimpleCommand(cmd) => cmd.argv.iter().map(is_glob).collect::<Vec<_>>(),
+            other => panic!("expected a command, got {other:?}"),
+        };
+        assert_eq!(
+            args("rm -rf * '*' \"*\" $D/* build"),
+            [false, true, false, false, false, false]
+        );
+    }
+}
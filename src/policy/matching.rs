@@ -5,8 +5,11 @@ use std::borrow::Cow;
 
 use super::config::{
     EnvMatcher, EnvValueClass, FlagsMatcher, GitConfigMatcher, GitConfigSource, Matcher,
-    PipelineMatcher, RedirectMatcher, StringOrList,
+    MatcherScope, McpInputCondition, McpMatcher, PathMatcher, PathTool, PipelineMatcher,
+    RedirectMatcher, StringOrList, UrlMatcher,
 };
+use crate::urls::ParsedUrl;
+
 use super::git_invocation::{GitConfigValue, GitInvocation, SubcommandResolution};
 use super::value_safety::{is_safe_program_value, SafeProgramClass};
 
@@ -156,6 +159,22 @@ fn argv_from_subcommand<'a>(cmd: &'a SimpleCommand) -> Cow<'a, [Arg]> {
     }
 }
 
+/// The values `flag` carries in `argv`, in the `flag value` and
+/// `flag=value` forms, up to a `--` that ends option processing.
+fn option_values<'a>(argv: &'a [Arg], flag: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    let options = argv
+        .iter()
+        .position(|a| a.text == "--")
+        .map_or(argv, |end| &argv[..end]);
+    options.iter().enumerate().filter_map(move |(i, arg)| {
+        if arg.text == flag {
+            options.get(i + 1).map(|value| value.text.as_str())
+        } else {
+            arg.text.strip_prefix(flag)?.strip_prefix('=')
+        }
+    })
+}
+
 fn arg_matches_flag(arg: &str, flag: &str) -> bool {
     if arg == flag {
         return true;
@@ -314,10 +333,38 @@ fn git_config_matches(git_config: &GitConfigMatcher, cmd: &SimpleCommand) -> boo
     }
 }
 
+/// Evaluate the `all`/`any`/`not` structure of `matcher`, deciding each
+/// leaf with `leaf`. A non-composite matcher is passed to `leaf` directly.
+pub fn matches_composed<F: FnMut(&Matcher) -> bool>(matcher: &Matcher, leaf: &mut F) -> bool {
+    match matcher {
+        Matcher::All { all } => all.iter().all(|m| matches_composed(m, leaf)),
+        Matcher::Any { any } => any.iter().any(|m| matches_composed(m, leaf)),
+        Matcher::Not { not } => !matches_composed(not, leaf),
+        leaf_matcher => leaf(leaf_matcher),
+    }
+}
+
 /// Check if a rule's matcher matches a given SimpleCommand.
 /// Pipeline matchers are handled separately in `evaluate` and are skipped here.
 pub fn matches_rule(matcher: &Matcher, cmd: &SimpleCommand) -> bool {
+    matches_rule_with(matcher, cmd, &redirect_targets(cmd, None))
+}
+
+/// `matches_rule` with the command's `redirect_targets` already resolved.
+pub fn matches_rule_with(
+    matcher: &Matcher,
+    cmd: &SimpleCommand,
+    redirect_targets: &[Vec<String>],
+) -> bool {
     match matcher {
+        Matcher::All { .. } | Matcher::Any { .. } | Matcher::Not { .. } => {
+            matches!(
+                matcher.scope(),
+                Some(MatcherScope::Command | MatcherScope::Url)
+            ) && matches_composed(matcher, &mut |leaf| {
+                matches_rule_with(leaf, cmd, redirect_targets)
+            })
+        }
         Matcher::GitConfig { git_config } => git_config_matches(git_config, cmd),
         Matcher::Command {
             command,
@@ -402,7 +449,9 @@ pub fn matches_rule(matcher: &Matcher, cmd: &SimpleCommand) -> bool {
                             let has_other_discriminator = flags.is_some()
                                 || !args_matcher.any_of.is_empty()
                                 || !args_matcher.all_of.is_empty()
-                                || !args_matcher.none_of.is_empty();
+                                || !args_matcher.none_of.is_empty()
+                                || !args_matcher.regex.is_empty()
+                                || !args_matcher.option_values.is_empty();
                             if !has_other_discriminator {
                                 return false;
                             }
@@ -466,6 +515,41 @@ pub fn matches_rule(matcher: &Matcher, cmd: &SimpleCommand) -> bool {
                         }
                     }
                 }
+                if !args_matcher.regex.is_empty() {
+                    let has_any = args_matcher.regex.iter().any(|regex| {
+                        cmd.argv
+                            .iter()
+                            .any(|a| regex.is_match(&a.text, args_matcher.case_insensitive))
+                    });
+                    if !has_any {
+                        return false;
+                    }
+                }
+                if !args_matcher.positional.is_empty() {
+                    let argv = argv_from_subcommand(cmd);
+                    let all_match = args_matcher.positional.iter().all(|(index, pattern)| {
+                        argv.get(*index).is_some_and(|a| {
+                            pattern.matches(&a.text, args_matcher.case_insensitive)
+                        })
+                    });
+                    if !all_match {
+                        return false;
+                    }
+                }
+                if !args_matcher.option_values.is_empty() {
+                    let argv = if has_subcommand_pin {
+                        argv_from_subcommand(cmd)
+                    } else {
+                        Cow::Borrowed(cmd.argv.as_slice())
+                    };
+                    let any_match = args_matcher.option_values.iter().any(|(flag, pattern)| {
+                        option_values(&argv, flag)
+                            .any(|value| pattern.matches(value, args_matcher.case_insensitive))
+                    });
+                    if !any_match {
+                        return false;
+                    }
+                }
             }
             if let Some(env_matcher) = env {
                 if !env_matches(env_matcher, &cmd.assignments) {
@@ -474,7 +558,19 @@ pub fn matches_rule(matcher: &Matcher, cmd: &SimpleCommand) -> bool {
             }
             true
         }
-        Matcher::Redirect { redirect } => matches_redirect(redirect, cmd),
+        Matcher::Redirect { redirect } => matches_redirect(redirect, cmd, redirect_targets),
+        Matcher::Path { .. } => {
+            // Path rules apply to file-tool invocations, not shell commands;
+            // they are evaluated by evaluate_path_access().
+            false
+        }
+        Matcher::Mcp { .. } => {
+            // MCP rules apply to MCP tool calls; see evaluate_mcp_call().
+            false
+        }
+        Matcher::Url { url } => super::url_args::command_urls(cmd)
+            .iter()
+            .any(|target| matches_url(url, target)),
         Matcher::Pipeline { .. } => {
             // Pipeline matching is handled at the statement level in evaluate()
             false
@@ -482,6 +578,50 @@ pub fn matches_rule(matcher: &Matcher, cmd: &SimpleCommand) -> bool {
     }
 }
 
+/// Whether a pipeline-scoped rule matcher fires on `pipe`: a `pipeline`
+/// matcher, or a combination containing one, whose command-level leaves
+/// fire when any stage matches them.
+pub fn matches_pipeline_rule(matcher: &Matcher, pipe: &parser::Pipeline) -> bool {
+    match matcher {
+        Matcher::Pipeline { pipeline } => matches_pipeline(pipeline, pipe),
+        _ if matcher.is_composite() && matcher.scope() == Some(MatcherScope::Pipeline) => {
+            matches_composed(matcher, &mut |leaf| match leaf {
+                Matcher::Pipeline { pipeline } => matches_pipeline(pipeline, pipe),
+                leaf => pipe.stages.iter().any(|stage| match stage {
+                    Statement::SimpleCommand(cmd) => matches_rule(leaf, cmd),
+                    _ => false,
+                }),
+            })
+        }
+        _ => false,
+    }
+}
+
+/// Whether a path-scoped rule matcher (a `path` matcher or a combination
+/// of them) fires on a file-tool access.
+pub fn matches_path_rule(matcher: &Matcher, tool: PathTool, targets: &[String]) -> bool {
+    matcher.scope() == Some(MatcherScope::Path)
+        && matches_composed(matcher, &mut |leaf| match leaf {
+            Matcher::Path { path } => matches_path(path, tool, targets),
+            _ => false,
+        })
+}
+
+/// Whether an mcp-scoped rule matcher (an `mcp` matcher or a combination
+/// of them) fires on an MCP tool call.
+pub fn matches_mcp_rule(
+    matcher: &Matcher,
+    server: &str,
+    tool: &str,
+    input: &serde_json::Value,
+) -> bool {
+    matcher.scope() == Some(MatcherScope::Mcp)
+        && matches_composed(matcher, &mut |leaf| match leaf {
+            Matcher::Mcp { mcp } => matches_mcp(mcp, server, tool, input),
+            _ => false,
+        })
+}
+
 /// Check if a pipeline matcher's stages appear as a subsequence in the pipeline's stages.
 pub fn matches_pipeline(matcher: &PipelineMatcher, pipe: &parser::Pipeline) -> bool {
     if matcher.stages.is_empty() {
@@ -522,26 +662,148 @@ pub fn matches_pipeline(matcher: &PipelineMatcher, pipe: &parser::Pipeline) -> b
     matcher_idx == matcher.stages.len()
 }
 
+/// The match candidates of each of `cmd`'s redirect targets, in order: the
+/// raw target and its canonical (`~`-expanded, `..`-normalized,
+/// symlink-resolved) spellings, with relative targets joined onto `cwd`,
+/// the directory the command runs in, when known. Resolving reads the
+/// filesystem, so it is done once per command rather than once per rule.
+pub fn redirect_targets(cmd: &SimpleCommand, cwd: Option<&str>) -> Vec<Vec<String>> {
+    cmd.redirects
+        .iter()
+        .map(|redir| crate::paths::resolve(&redir.target, cwd).match_candidates())
+        .collect()
+}
+
 /// Check if any of the command's redirects match the redirect matcher.
-pub fn matches_redirect(redirect_matcher: &RedirectMatcher, cmd: &SimpleCommand) -> bool {
-    cmd.redirects.iter().any(|redir| {
-        // Check op if specified
-        let op_matches = match &redirect_matcher.op {
-            Some(op_matcher) => op_matcher.matches(&redir.op.to_string()),
-            None => true,
-        };
-        // Check target with glob matching if specified
-        let target_matches = match &redirect_matcher.target {
-            Some(target_matcher) => match target_matcher {
-                StringOrList::Single(pattern) => glob_match::glob_match(pattern, &redir.target),
-                StringOrList::List { any_of } => any_of
+/// `targets` are the command's `redirect_targets`.
+pub fn matches_redirect(
+    redirect_matcher: &RedirectMatcher,
+    cmd: &SimpleCommand,
+    targets: &[Vec<String>],
+) -> bool {
+    cmd.redirects
+        .iter()
+        .zip(targets)
+        .any(|(redir, candidates)| {
+            // Check op if specified
+            let op_matches = match &redirect_matcher.op {
+                Some(op_matcher) => op_matcher.matches(&redir.op.to_string()),
+                None => true,
+            };
+            // Check target with glob matching if specified, against any of its
+            // spellings.
+            let target_matches = match &redirect_matcher.target {
+                Some(target_matcher) => candidates
                     .iter()
-                    .any(|p| glob_match::glob_match(p, &redir.target)),
-            },
-            None => true,
+                    .any(|target| target_glob_matches(target_matcher, target)),
+                None => true,
+            };
+            op_matches && target_matches
+        })
+}
+
+/// Check if a file-tool access matches a path matcher: the tool kind must be
+/// in scope and any of the target spellings must match a target glob.
+pub fn matches_path(path_matcher: &PathMatcher, tool: PathTool, targets: &[String]) -> bool {
+    if !path_matcher.tools.is_empty() && !path_matcher.tools.contains(&tool) {
+        return false;
+    }
+    targets
+        .iter()
+        .any(|target| target_glob_matches(&path_matcher.target, target))
+}
+
+/// Check if a URL matches a url matcher: every field that is set must
+/// match. Hosts, paths and queries are globs; schemes compare
+/// case-insensitively (the parsed scheme is already lowercase).
+pub fn matches_url(url_matcher: &UrlMatcher, url: &ParsedUrl) -> bool {
+    url_matcher
+        .host
+        .as_ref()
+        .is_none_or(|hosts| target_glob_matches(hosts, &url.host))
+        && url_matcher.scheme.as_ref().is_none_or(|schemes| {
+            schemes
+                .patterns()
+                .iter()
+                .any(|s| s.eq_ignore_ascii_case(&url.scheme))
+        })
+        && url_matcher
+            .port
+            .as_ref()
+            .is_none_or(|ports| url.port.is_some_and(|port| ports.contains(port)))
+        && url_matcher
+            .path
+            .as_ref()
+            .is_none_or(|paths| target_glob_matches(paths, &url.path))
+        && url_matcher
+            .query
+            .as_ref()
+            .is_none_or(|queries| target_glob_matches(queries, &url.query))
+}
+
+/// Check if an MCP tool call matches an mcp matcher: server and tool globs
+/// (when set) and every `tool_input` condition.
+pub fn matches_mcp(
+    mcp_matcher: &McpMatcher,
+    server: &str,
+    tool: &str,
+    input: &serde_json::Value,
+) -> bool {
+    mcp_matcher
+        .server
+        .as_ref()
+        .is_none_or(|patterns| target_glob_matches(patterns, server))
+        && mcp_matcher
+            .tool
+            .as_ref()
+            .is_none_or(|patterns| target_glob_matches(patterns, tool))
+        && mcp_matcher
+            .input
+            .iter()
+            .all(|condition| mcp_input_condition_matches(condition, input))
+}
+
+fn mcp_input_condition_matches(condition: &McpInputCondition, input: &serde_json::Value) -> bool {
+    let value = input.pointer(condition.pointer.as_str());
+    if value.is_some() != condition.exists.unwrap_or(true) {
+        return false;
+    }
+    if let Some(expected) = &condition.equals {
+        if value != Some(expected) {
+            return false;
+        }
+    }
+    if let Some(patterns) = &condition.matches {
+        let text = match value {
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(v @ (serde_json::Value::Number(_) | serde_json::Value::Bool(_))) => v.to_string(),
+            _ => return false,
         };
-        op_matches && target_matches
-    })
+        if !target_glob_matches(patterns, &text) {
+            return false;
+        }
+    }
+    if let Some(needles) = &condition.contains {
+        let Some(serde_json::Value::String(text)) = value else {
+            return false;
+        };
+        let text = text.to_lowercase();
+        if !needles
+            .patterns()
+            .iter()
+            .any(|needle| text.contains(&needle.to_lowercase()))
+        {
+            return false;
+        }
+    }
+    true
+}
+
+fn target_glob_matches(patterns: &StringOrList, target: &str) -> bool {
+    match patterns {
+        StringOrList::Single(pattern) => glob_match::glob_match(pattern, target),
+        StringOrList::List { any_of } => any_of.iter().any(|p| glob_match::glob_match(p, target)),
+    }
 }
 
 #[cfg(test)]
@@ -588,6 +850,9 @@ mod tests {
                 .collect(),
             case_insensitive: false,
             min_args: None,
+            regex: vec![],
+            positional: Default::default(),
+            option_values: Default::default(),
         }
     }
 
@@ -641,6 +906,7 @@ mod tests {
             assignments: vec![],
             redirects: vec![],
             embedded_substitutions: vec![],
+            resolution: None,
         }
     }
 
@@ -1409,4 +1675,109 @@ mod tests {
         // Has neither → no match (any_of fails)
         assert!(!super::flags_match(&m, &argv(&["cmd", "-x"])));
     }
+
+    fn yaml_matcher(yaml: &str) -> Matcher {
+        serde_norway::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_option_values_match_separate_and_joined_forms() {
+        let m = yaml_matcher(
+            r#"
+command: kubectl
+args:
+  option_values: { -n: "prod*", --namespace: "prod*" }
+"#,
+        );
+        assert!(matches_rule(
+            &m,
+            &parse_cmd("kubectl delete pod x -n prod-eu")
+        ));
+        assert!(matches_rule(
+            &m,
+            &parse_cmd("kubectl --namespace=prod delete pod x")
+        ));
+        assert!(matches_rule(
+            &m,
+            &parse_cmd("kubectl delete --namespace prod pod x")
+        ));
+        assert!(!matches_rule(
+            &m,
+            &parse_cmd("kubectl delete pod x -n staging")
+        ));
+        assert!(!matches_rule(&m, &parse_cmd("kubectl delete pod prod")));
+        // `--` ends option processing.
+        assert!(!matches_rule(
+            &m,
+            &parse_cmd("kubectl exec x -- env -n prod")
+        ));
+    }
+
+    #[test]
+    fn test_option_values_with_subcommand_pin_ignore_global_options() {
+        let m = yaml_matcher(
+            r#"
+command: git
+args:
+  subcommand: [commit]
+  option_values: { -c: { regex: "^core\\." } }
+"#,
+        );
+        assert!(matches_rule(&m, &parse_cmd("git commit -c core.x")));
+        assert!(!matches_rule(
+            &m,
+            &parse_cmd("git -c core.editor=vi commit -m msg")
+        ));
+    }
+
+    #[test]
+    fn test_regex_and_positional_args() {
+        let m = yaml_matcher(
+            r#"
+command: helm
+args:
+  regex: ["^--set=image\\.tag="]
+  positional: { 0: upgrade, 1: { regex: "^prod-[a-z]+$" } }
+  case_insensitive: true
+"#,
+        );
+        assert!(matches_rule(
+            &m,
+            &parse_cmd("helm upgrade PROD-api chart --set=image.tag=1")
+        ));
+        assert!(!matches_rule(
+            &m,
+            &parse_cmd("helm upgrade prod-api-2 chart --set=image.tag=1")
+        ));
+        assert!(!matches_rule(&m, &parse_cmd("helm upgrade prod-api chart")));
+        assert!(!matches_rule(
+            &m,
+            &parse_cmd("helm install prod-api chart --set=image.tag=1")
+        ));
+    }
+
+    #[test]
+    fn test_positional_counts_from_effective_subcommand() {
+        let m = yaml_matcher(
+            r#"
+command: git
+args:
+  positional: { 0: push, 2: "main" }
+"#,
+        );
+        assert!(matches_rule(&m, &parse_cmd("git -C repo push origin main")));
+        assert!(!matches_rule(&m, &parse_cmd("git push origin")));
+    }
+
+    #[test]
+    fn test_invalid_regex_is_a_load_error() {
+        let err = serde_norway::from_str::<Matcher>("command: x\nargs: { regex: [\"(\"] }\n")
+            .unwrap_err()
+            .to_string();
+        assert!(!err.is_empty());
+        let err = serde_norway::from_str::<ArgsMatcher>("regex: [\"(\"]\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("invalid regex '('"), "{err}");
+    }
 }
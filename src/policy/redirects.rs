@@ -19,7 +19,12 @@ pub(super) fn is_devnull_target(r: &Redirect) -> bool {
 /// accepted because it cannot exfiltrate data to a sensitive file
 /// path; the worst case is information leaking to the controlling
 /// terminal. Empty list returns true (no redirects = nothing to leak).
+/// Heredocs only feed stdin, so they are left out.
 pub(super) fn redirects_discard_all_output(redirs: &[Redirect]) -> bool {
+    let redirs: Vec<&Redirect> = redirs
+        .iter()
+        .filter(|r| r.op != RedirectOp::HereDoc)
+        .collect();
     if redirs.is_empty() {
         return true;
     }
@@ -70,6 +75,7 @@ mod tests {
             fd,
             op: RedirectOp::Write,
             target: target.to_string(),
+            heredoc: None,
         }
     }
     fn dup(fd: Option<u32>, target: &str) -> Redirect {
@@ -77,6 +83,7 @@ mod tests {
             fd,
             op: RedirectOp::DupOutput,
             target: target.to_string(),
+            heredoc: None,
         }
     }
     fn append(fd: Option<u32>, target: &str) -> Redirect {
@@ -84,6 +91,7 @@ mod tests {
             fd,
             op: RedirectOp::Append,
             target: target.to_string(),
+            heredoc: None,
         }
     }
 
@@ -164,6 +172,25 @@ mod tests {
         )]));
     }
 
+    #[test]
+    fn heredoc_is_not_output() {
+        let heredoc = Redirect {
+            fd: None,
+            op: RedirectOp::HereDoc,
+            target: "EOF".to_string(),
+            heredoc: None,
+        };
+        assert!(redirects_discard_all_output(std::slice::from_ref(&heredoc)));
+        assert!(redirects_discard_all_output(&[
+            heredoc.clone(),
+            w(Some(2), "/dev/null")
+        ]));
+        assert!(!redirects_discard_all_output(&[
+            heredoc,
+            w(None, "out.txt")
+        ]));
+    }
+
     #[test]
     fn is_devnull_target_basic() {
         assert!(is_devnull_target(&w(Some(1), "/dev/null")));
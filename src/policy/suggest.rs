@@ -0,0 +1,356 @@
+//! Allowlist suggestions from decision history, for `longline suggest`.
+//!
+//! A logged command is a candidate only when it still asks purely because
+//! of the default decision: no rule or classifier matched, and the
+//! all-covered gate found leaves that nothing covers. Those uncovered
+//! leaves are labelled with `command_label` (wrapper-unwrapped, subcommand
+//! resolved) and clustered. A command that matches any ask or deny rule at
+//! any safety level, or reads a sensitive variable, is never a candidate,
+//! and its labels are withheld so an allowlist entry cannot cover it when
+//! its rule is inactive at the current level.
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Serialize;
+
+use crate::domain::Decision;
+use crate::parser::{self, Statement};
+
+use super::allowlist::command_label;
+use super::explain::coverage;
+use super::findings::LeafBucket;
+use super::sensitive_env::classify_sensitive_env;
+use super::{
+    collect_pipelines, env_dump_alias, evaluate_with_findings, matches_pipeline_rule,
+    redirect_targets, rule_matches_command, EvaluationInputs, Matcher, RulesConfig, TrustLevel,
+};
+
+/// Examples kept per suggestion.
+const MAX_EXAMPLES: usize = 3;
+
+/// A proposed `allowlists.commands` entry with the history behind it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Suggestion {
+    pub label: String,
+    /// How many logged commands this entry would have let through.
+    pub count: usize,
+    /// Up to three distinct logged commands, in first-seen order.
+    pub examples: Vec<String>,
+}
+
+impl Suggestion {
+    /// A bare family (`make`) allowlists every invocation; it is emitted
+    /// commented out so the user narrows it first.
+    pub fn is_broad(&self) -> bool {
+        !self.label.contains(' ')
+    }
+}
+
+/// What the history says about one logged command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Candidate {
+    /// Asks by default; these labels would cover it.
+    Labels(Vec<String>),
+    /// Matches a restrictive rule or a sensitive variable; never propose
+    /// anything covering these labels.
+    Sensitive(Vec<String>),
+    /// Allowed, decided by a rule, or not nameable.
+    Skip,
+}
+
+/// Cluster `commands` (one entry per logged decision, with the directory it
+/// ran in when known) into allowlist suggestions seen at least `min_count`
+/// times, most frequent first.
+pub fn suggest_allowlist<'a>(
+    config: &RulesConfig,
+    commands: impl IntoIterator<Item = (&'a str, Option<&'a str>)>,
+    min_count: usize,
+) -> Vec<Suggestion> {
+    let mut clusters: HashMap<String, Suggestion> = HashMap::new();
+    let mut order: Vec<String> = Vec::new();
+    let mut withheld: BTreeSet<String> = BTreeSet::new();
+
+    for (command, cwd) in commands {
+        match classify(config, command, cwd) {
+            Candidate::Labels(labels) => {
+                for label in labels {
+                    let suggestion = clusters.entry(label.clone()).or_insert_with(|| {
+                        order.push(label.clone());
+                        Suggestion {
+                            label,
+                            count: 0,
+                            examples: vec![],
+                        }
+                    });
+                    suggestion.count += 1;
+                    if suggestion.examples.len() < MAX_EXAMPLES
+                        && !suggestion.examples.iter().any(|e| e == command)
+                    {
+                        suggestion.examples.push(command.to_string());
+                    }
+                }
+            }
+            Candidate::Sensitive(labels) => withheld.extend(labels),
+            Candidate::Skip => {}
+        }
+    }
+
+    let mut suggestions: Vec<Suggestion> = order
+        .into_iter()
+        .filter_map(|label| clusters.remove(&label))
+        .filter(|s| s.count >= min_count && !withheld.contains(&s.label))
+        .collect();
+    // Stable sort keeps first-seen order among equal counts.
+    suggestions.sort_by_key(|s| std::cmp::Reverse(s.count));
+    suggestions
+}
+
+fn classify(config: &RulesConfig, command: &str, cwd: Option<&str>) -> Candidate {
+    let Ok(stmt) = parser::parse(command) else {
+        return Candidate::Skip;
+    };
+    let EvaluationInputs {
+        leaves,
+        extra_stmts,
+        subst_leaves,
+        resolved_stmts,
+        located_stmts,
+        start_dir,
+        ..
+    } = EvaluationInputs::collect(&stmt, cwd);
+    let extra_leaves: Vec<&Statement> = extra_stmts.iter().flat_map(parser::flatten).collect();
+    let all_leaves = leaves
+        .iter()
+        .map(|l| (*l, LeafBucket::Original))
+        .chain(extra_leaves.iter().map(|l| (*l, LeafBucket::Wrapper)))
+        .chain(subst_leaves.iter().map(|l| (*l, LeafBucket::Substitution)));
+    let resolved_leaves = resolved_stmts
+        .iter()
+        .flat_map(parser::flatten)
+        .map(|l| (l, LeafBucket::Resolved));
+    let located_leaves = located_stmts
+        .iter()
+        .flat_map(parser::flatten)
+        .map(|l| (l, LeafBucket::Located));
+
+    let mut labels: Vec<String> = Vec::new();
+    let mut sensitive = is_sensitive_pipeline(config, &stmt, &extra_stmts);
+    let mut nameless_uncovered = false;
+    for (leaf, bucket) in all_leaves.chain(resolved_leaves).chain(located_leaves) {
+        let Statement::SimpleCommand(cmd) = leaf else {
+            continue;
+        };
+        sensitive |= is_sensitive_command(config, cmd, start_dir.of(leaf));
+        if !coverage(config, leaf, bucket, &leaves, &extra_stmts, &resolved_stmts).is_empty() {
+            continue;
+        }
+        let label = command_label(cmd);
+        if label.is_empty() {
+            nameless_uncovered = true;
+        } else if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+
+    if sensitive {
+        return Candidate::Sensitive(labels);
+    }
+    let evaluation = evaluate_with_findings(config, &stmt, cwd);
+    let default_ask = evaluation.result.decision != Decision::Allow
+        && evaluation.result.rule_id.is_none()
+        && evaluation.findings.is_empty();
+    if !default_ask || nameless_uncovered || labels.is_empty() {
+        return Candidate::Skip;
+    }
+    Candidate::Labels(labels)
+}
+
+/// Whether any ask/deny rule, at any safety level, or the sensitive-env
+/// classifier fires on `cmd`, run in `cwd` when known.
+fn is_sensitive_command(
+    config: &RulesConfig,
+    cmd: &parser::SimpleCommand,
+    cwd: Option<&str>,
+) -> bool {
+    let printenv_cmd = env_dump_alias(cmd);
+    let targets = redirect_targets(cmd, cwd);
+    classify_sensitive_env(cmd).is_some()
+        || config.rules.iter().any(|rule| {
+            rule.decision != Decision::Allow
+                && !matches!(rule.matcher, Matcher::Pipeline { .. })
+                && rule_matches_command(config, rule, cmd, printenv_cmd.as_ref(), &targets)
+        })
+}
+
+fn is_sensitive_pipeline(
+    config: &RulesConfig,
+    stmt: &Statement,
+    extra_stmts: &[Statement],
+) -> bool {
+    let pipelines = collect_pipelines(stmt)
+        .into_iter()
+        .chain(extra_stmts.iter().flat_map(collect_pipelines));
+    pipelines.into_iter().any(|pipe| {
+        config.rules.iter().any(|rule| {
+            rule.decision != Decision::Allow && matches_pipeline_rule(&rule.matcher, pipe)
+        })
+    })
+}
+
+/// Render suggestions as a `.claude/longline.yaml` snippet. Broad
+/// (bare-family) entries are commented out.
+pub fn render_allowlist_snippet(suggestions: &[Suggestion], trust: TrustLevel) -> String {
+    let mut out = String::from("allowlists:\n  commands:\n");
+    for s in suggestions {
+        let plural = if s.count == 1 { "" } else { "s" };
+        out.push_str(&format!(
+            "    # {} ask{plural}, e.g. {}\n",
+            s.count,
+            s.examples.join(" ; ")
+        ));
+        let entry = format!("- {{ command: {}, trust: {trust} }}", yaml_scalar(&s.label));
+        if s.is_broad() {
+            out.push_str(&format!(
+                "    # broad: allows every `{}` invocation -- narrow to a subcommand before enabling\n    # {entry}\n",
+                s.label
+            ));
+        } else {
+            out.push_str(&format!("    {entry}\n"));
+        }
+    }
+    out
+}
+
+/// Quote a label for flow-style YAML when it is more than a plain word.
+fn yaml_scalar(label: &str) -> String {
+    if label
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        label.to_string()
+    } else {
+        format!("\"{}\"", label.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::load_embedded_rules;
+
+    fn config() -> RulesConfig {
+        load_embedded_rules().unwrap()
+    }
+
+    /// `commands` as logged without a working directory.
+    fn anywhere<'a>(commands: &[&'a str]) -> Vec<(&'a str, Option<&'a str>)> {
+        commands.iter().map(|command| (*command, None)).collect()
+    }
+
+    #[test]
+    fn test_clusters_default_asks_by_label() {
+        let suggestions = suggest_allowlist(
+            &config(),
+            anywhere(&[
+                "kubectl get pods",
+                "kubectl get pods -n web",
+                "timeout 30 kubectl get nodes",
+                "kubectl apply -f deploy.yaml",
+                "ls -la",
+            ]),
+            2,
+        );
+        assert_eq!(
+            suggestions,
+            vec![Suggestion {
+                label: "kubectl get pods".to_string(),
+                count: 2,
+                examples: vec![
+                    "kubectl get pods".to_string(),
+                    "kubectl get pods -n web".to_string()
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_min_count_and_ordering() {
+        let suggestions = suggest_allowlist(
+            &config(),
+            anywhere(&[
+                "frob status",
+                "zap run",
+                "zap run",
+                "frob status",
+                "zap run",
+            ]),
+            1,
+        );
+        let labels: Vec<(&str, usize)> = suggestions
+            .iter()
+            .map(|s| (s.label.as_str(), s.count))
+            .collect();
+        assert_eq!(labels, vec![("zap run", 3), ("frob status", 2)]);
+    }
+
+    #[test]
+    fn test_rule_matched_commands_are_never_proposed() {
+        // `rm -rf` is decided by a rule, not the default decision.
+        assert!(
+            suggest_allowlist(&config(), anywhere(&["rm -rf build", "rm -rf build"]), 1).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_sensitive_commands_withhold_their_labels() {
+        let suggestions = suggest_allowlist(
+            &config(),
+            anywhere(&[
+                "frobctl dump",
+                "frobctl dump",
+                "PATH=/tmp/bin frobctl dump",
+                "frobctl show < .env",
+                "frobctl show",
+                "frobctl show",
+            ]),
+            1,
+        );
+        assert!(suggestions.is_empty(), "{suggestions:?}");
+    }
+
+    #[test]
+    fn test_redirects_are_judged_from_the_logged_directory() {
+        let suggest = |cwd| suggest_allowlist(&config(), [("frobctl dump > hosts", cwd)], 1);
+        assert_eq!(suggest(Some("/tmp")).len(), 1);
+        // From /etc the relative target is `/etc/hosts`, which a rule asks on.
+        assert!(suggest(Some("/etc")).is_empty());
+    }
+
+    #[test]
+    fn test_render_snippet_comments_out_broad_entries() {
+        let snippet = render_allowlist_snippet(
+            &[
+                Suggestion {
+                    label: "kubectl get".to_string(),
+                    count: 4,
+                    examples: vec!["kubectl get pods".to_string()],
+                },
+                Suggestion {
+                    label: "frob".to_string(),
+                    count: 1,
+                    examples: vec!["frob".to_string()],
+                },
+            ],
+            TrustLevel::Standard,
+        );
+        assert_eq!(
+            snippet,
+            "allowlists:\n  commands:\n    # 4 asks, e.g. kubectl get pods\n    - { command: \"kubectl get\", trust: standard }\n    # 1 ask, e.g. frob\n    # broad: allows every `frob` invocation -- narrow to a subcommand before enabling\n    # - { command: frob, trust: standard }\n"
+        );
+        let parsed: serde_norway::Value = serde_norway::from_str(&snippet).unwrap();
+        assert_eq!(
+            parsed["allowlists"]["commands"][0]["command"],
+            "kubectl get"
+        );
+    }
+}
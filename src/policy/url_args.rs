@@ -0,0 +1,300 @@
+//! URL arguments of HTTP clients, for `url` rules on shell commands.
+//!
+//! Each supported client's value-taking options are skipped so their values
+//! (`-o out.txt`, `-H 'Accept: …'`) are not read as hosts; every remaining
+//! positional is a URL. Scheme-less URLs get the client's default scheme.
+
+use crate::parser::SimpleCommand;
+use crate::urls::{self, ParsedUrl};
+
+use super::matching::normalize_command_name;
+
+/// curl options that take a value. `--url` is handled separately.
+const CURL_SHORT_VALUE_FLAGS: &str = "AbcCdDeEFHKmortTuUwxXyYzQP";
+const CURL_LONG_VALUE_FLAGS: &[&str] = &[
+    "--cacert",
+    "--capath",
+    "--cert",
+    "--cert-type",
+    "--config",
+    "--connect-timeout",
+    "--connect-to",
+    "--continue-at",
+    "--cookie",
+    "--cookie-jar",
+    "--data",
+    "--data-ascii",
+    "--data-binary",
+    "--data-raw",
+    "--data-urlencode",
+    "--dump-header",
+    "--form",
+    "--form-string",
+    "--ftp-port",
+    "--header",
+    "--interface",
+    "--json",
+    "--key",
+    "--key-type",
+    "--limit-rate",
+    "--max-filesize",
+    "--max-redirs",
+    "--max-time",
+    "--output",
+    "--output-dir",
+    "--proxy",
+    "--proxy-user",
+    "--quote",
+    "--range",
+    "--referer",
+    "--request",
+    "--resolve",
+    "--retry",
+    "--retry-delay",
+    "--retry-max-time",
+    "--speed-limit",
+    "--speed-time",
+    "--telnet-option",
+    "--time-cond",
+    "--trace",
+    "--trace-ascii",
+    "--upload-file",
+    "--url-query",
+    "--user",
+    "--user-agent",
+    "--variable",
+    "--write-out",
+];
+
+const WGET_SHORT_VALUE_FLAGS: &str = "aABDeiIlOoPQRtTUwX";
+const WGET_LONG_VALUE_FLAGS: &[&str] = &[
+    "--accept",
+    "--append-output",
+    "--base",
+    "--bind-address",
+    "--body-data",
+    "--body-file",
+    "--ca-certificate",
+    "--certificate",
+    "--directory-prefix",
+    "--domains",
+    "--exclude-directories",
+    "--execute",
+    "--header",
+    "--http-password",
+    "--http-user",
+    "--include-directories",
+    "--input-file",
+    "--level",
+    "--limit-rate",
+    "--load-cookies",
+    "--method",
+    "--output-document",
+    "--output-file",
+    "--password",
+    "--post-data",
+    "--post-file",
+    "--private-key",
+    "--quota",
+    "--referer",
+    "--reject",
+    "--save-cookies",
+    "--timeout",
+    "--tries",
+    "--user",
+    "--user-agent",
+    "--wait",
+];
+
+const HTTPIE_SHORT_VALUE_FLAGS: &str = "aAoprs";
+const HTTPIE_LONG_VALUE_FLAGS: &[&str] = &[
+    "--auth",
+    "--auth-type",
+    "--boundary",
+    "--cert",
+    "--cert-key",
+    "--cert-key-pass",
+    "--ciphers",
+    "--default-scheme",
+    "--format-options",
+    "--max-redirects",
+    "--output",
+    "--pretty",
+    "--print",
+    "--proxy",
+    "--raw",
+    "--response-charset",
+    "--response-mime",
+    "--session",
+    "--session-read-only",
+    "--ssl",
+    "--style",
+    "--timeout",
+    "--verify",
+];
+
+/// HTTP clients whose URL arguments `url` rules inspect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Client {
+    Curl,
+    Wget,
+    /// HTTPie's `http` / `https` commands.
+    Httpie {
+        default_scheme: &'static str,
+    },
+}
+
+impl Client {
+    fn from_name(name: &str) -> Option<Self> {
+        match normalize_command_name(name) {
+            "curl" => Some(Client::Curl),
+            "wget" => Some(Client::Wget),
+            "http" => Some(Client::Httpie {
+                default_scheme: "http",
+            }),
+            "https" => Some(Client::Httpie {
+                default_scheme: "https",
+            }),
+            _ => None,
+        }
+    }
+
+    fn value_flags(self) -> (&'static str, &'static [&'static str]) {
+        match self {
+            Client::Curl => (CURL_SHORT_VALUE_FLAGS, CURL_LONG_VALUE_FLAGS),
+            Client::Wget => (WGET_SHORT_VALUE_FLAGS, WGET_LONG_VALUE_FLAGS),
+            Client::Httpie { .. } => (HTTPIE_SHORT_VALUE_FLAGS, HTTPIE_LONG_VALUE_FLAGS),
+        }
+    }
+}
+
+/// The URLs `cmd` fetches, or an empty list when it is not a supported
+/// HTTP client. Arguments that do not parse as URLs are dropped.
+pub(crate) fn command_urls(cmd: &SimpleCommand) -> Vec<ParsedUrl> {
+    let Some(client) = cmd.name.as_deref().and_then(Client::from_name) else {
+        return vec![];
+    };
+    let (short_value_flags, long_value_flags) = client.value_flags();
+
+    let mut raw_urls: Vec<&str> = Vec::new();
+    let mut positionals: Vec<&str> = Vec::new();
+    let mut args = cmd.argv.iter().map(|a| a.text.as_str());
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            positionals.extend(args.by_ref());
+            break;
+        }
+        if arg == "-" {
+            continue;
+        }
+        if let Some(long) = arg.strip_prefix("--") {
+            let (flag, inline_value) = match long.split_once('=') {
+                Some((flag, value)) => (flag, Some(value)),
+                None => (long, None),
+            };
+            let flag = format!("--{flag}");
+            let is_url_flag = client == Client::Curl && flag == "--url";
+            if is_url_flag || long_value_flags.contains(&flag.as_str()) {
+                let value = inline_value.or_else(|| args.next());
+                if is_url_flag {
+                    raw_urls.extend(value);
+                }
+            }
+            continue;
+        }
+        if let Some(cluster) = arg.strip_prefix('-').filter(|c| !c.is_empty()) {
+            // `-sSo out`: the first value-taking letter consumes the rest
+            // of the cluster, or the next argument when it ends the cluster.
+            if let Some(pos) = cluster.find(|c| short_value_flags.contains(c)) {
+                if pos + 1 == cluster.len() {
+                    args.next();
+                }
+            }
+            continue;
+        }
+        positionals.push(arg);
+    }
+
+    match client {
+        Client::Curl | Client::Wget => raw_urls.extend(positionals),
+        Client::Httpie { .. } => {
+            // `http [METHOD] URL [REQUEST_ITEM ...]`
+            let skip_method =
+                positionals.len() > 1 && positionals[0].chars().all(|c| c.is_ascii_alphabetic());
+            raw_urls.extend(positionals.get(usize::from(skip_method)));
+        }
+    }
+
+    let default_scheme = match client {
+        Client::Curl | Client::Wget => "http",
+        Client::Httpie { default_scheme } => default_scheme,
+    };
+    raw_urls
+        .into_iter()
+        .filter_map(|raw| match raw.strip_prefix(':') {
+            // HTTPie's `:3000/path` shorthand for localhost.
+            Some(rest) if matches!(client, Client::Httpie { .. }) => {
+                urls::parse(&format!("localhost:{rest}"), Some(default_scheme))
+                    .or_else(|| urls::parse(&format!("localhost{rest}"), Some(default_scheme)))
+            }
+            _ => urls::parse(raw, Some(default_scheme)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{self, Statement};
+
+    fn hosts(command: &str) -> Vec<String> {
+        let Statement::SimpleCommand(cmd) = parser::parse(command).unwrap() else {
+            panic!("expected a simple command: {command}");
+        };
+        command_urls(&cmd).into_iter().map(|u| u.host).collect()
+    }
+
+    #[test]
+    fn test_curl_skips_option_values() {
+        assert_eq!(
+            hosts("curl -sS -o out.txt -H 'Accept: text/html' https://docs.rs/serde"),
+            vec!["docs.rs"]
+        );
+        assert_eq!(hosts("curl -so out.txt example.com"), vec!["example.com"]);
+        assert_eq!(hosts("curl -sooutput.txt example.com"), vec!["example.com"]);
+        assert_eq!(
+            hosts("curl --output=out.txt --max-time 5 http://a.test http://b.test"),
+            vec!["a.test", "b.test"]
+        );
+    }
+
+    #[test]
+    fn test_curl_url_flag() {
+        assert_eq!(hosts("curl --url https://evil.test"), vec!["evil.test"]);
+        assert_eq!(hosts("curl --url=https://evil.test"), vec!["evil.test"]);
+    }
+
+    #[test]
+    fn test_wget_urls() {
+        assert_eq!(
+            hosts("wget -q -O - --header 'X: y' https://github.com/x.tar.gz"),
+            vec!["github.com"]
+        );
+    }
+
+    #[test]
+    fn test_httpie_method_items_and_shorthand() {
+        assert_eq!(
+            hosts("http POST api.example.com/items name=x Auth:token"),
+            vec!["api.example.com"]
+        );
+        assert_eq!(hosts("http -a user:pw :3000/health"), vec!["localhost"]);
+        assert_eq!(hosts("https example.com"), vec!["example.com"]);
+    }
+
+    #[test]
+    fn test_other_commands_have_no_urls() {
+        assert!(hosts("echo https://example.com").is_empty());
+        assert!(hosts("curl --version").is_empty());
+    }
+}
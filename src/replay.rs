@@ -0,0 +1,292 @@
+//! `longline replay`: re-evaluate logged shell commands under a candidate
+//! configuration and report which decisions would flip.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use longline::config;
+use longline::domain::{Decision, PolicyResult};
+use longline::parser;
+use longline::policy::{self, RulesConfig, SafetyLevel, TrustLevel};
+
+use crate::audit::{self, AuditFilter};
+
+/// How each logged entry's configuration is finalized.
+pub(crate) struct ReplayOptions<'a> {
+    pub home: &'a Path,
+    /// Project directory for every entry; each entry's own `cwd` otherwise.
+    pub dir_override: Option<&'a Path>,
+    pub cli_trust_level: Option<TrustLevel>,
+    pub cli_safety_level: Option<SafetyLevel>,
+    pub profile: Option<&'a str>,
+}
+
+/// One distinct command whose decision changes, with how often it was logged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct Flip {
+    pub from: Decision,
+    pub to: Decision,
+    pub command: String,
+    pub cwd: String,
+    pub count: usize,
+    pub rule_id: Option<String>,
+    pub reason: String,
+}
+
+impl Flip {
+    pub fn is_tightening(&self) -> bool {
+        self.to > self.from
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ReplayReport {
+    pub files: Vec<String>,
+    /// Shell entries that were re-evaluated.
+    pub replayed: usize,
+    /// Lines that were not valid log entries.
+    pub skipped_lines: usize,
+    pub unchanged: usize,
+    /// Replayed entries whose decision becomes more restrictive.
+    pub tightened: usize,
+    /// Replayed entries whose decision becomes less restrictive.
+    pub loosened: usize,
+    /// Tightenings first, then by transition and frequency.
+    pub flips: Vec<Flip>,
+}
+
+/// Re-evaluate every shell entry of `files` under `base` finalized for the
+/// entry's project and runtime. The logged policy decision (before
+/// `--ask-on-deny` or the AI judge) is the baseline.
+pub(crate) fn replay(
+    base: &RulesConfig,
+    files: &[(PathBuf, &'static str)],
+    filter: &AuditFilter,
+    options: &ReplayOptions,
+) -> Result<ReplayReport, String> {
+    let mut configs: HashMap<(Option<PathBuf>, String), RulesConfig> = HashMap::new();
+    let mut flips: HashMap<(String, String, Decision, Decision), Flip> = HashMap::new();
+    let mut report = ReplayReport {
+        files: files.iter().map(|(p, _)| p.display().to_string()).collect(),
+        replayed: 0,
+        skipped_lines: 0,
+        unchanged: 0,
+        tightened: 0,
+        loosened: 0,
+        flips: vec![],
+    };
+    let mut error: Option<String> = None;
+
+    report.skipped_lines = audit::read_entries(files, filter, |entry, file_runtime| {
+        if error.is_some() || !entry.is_shell() || entry.command.is_empty() {
+            return;
+        }
+        let project_dir = match options.dir_override {
+            Some(dir) => Some(dir.to_path_buf()),
+            None => (!entry.cwd.is_empty()).then(|| PathBuf::from(&entry.cwd)),
+        };
+        let runtime = entry
+            .runtime
+            .clone()
+            .unwrap_or_else(|| file_runtime.to_string());
+        let key = (project_dir, runtime);
+        if !configs.contains_key(&key) {
+            match config::finalize_config(
+                base.clone(),
+                options.home,
+                key.0.as_deref(),
+                options.cli_trust_level,
+                options.cli_safety_level,
+                &key.1,
+                options.profile,
+            ) {
+                Ok(final_config) => {
+                    configs.insert(key.clone(), final_config.rules);
+                }
+                Err(e) => {
+                    error = Some(e);
+                    return;
+                }
+            }
+        }
+        let config = &configs[&key];
+
+        let before = entry.original_decision.unwrap_or(entry.decision);
+        let after = match parser::parse(&entry.command) {
+            Ok(stmt) => {
+                let cwd = (!entry.cwd.is_empty()).then_some(entry.cwd.as_str());
+                policy::evaluate_with_findings(config, &stmt, cwd).result
+            }
+            Err(e) => PolicyResult::ask(&format!("parse error: {e}")),
+        };
+        report.replayed += 1;
+        if after.decision == before {
+            report.unchanged += 1;
+            return;
+        }
+        if after.decision > before {
+            report.tightened += 1;
+        } else {
+            report.loosened += 1;
+        }
+        flips
+            .entry((
+                entry.command.clone(),
+                entry.cwd.clone(),
+                before,
+                after.decision,
+            ))
+            .or_insert_with(|| Flip {
+                from: before,
+                to: after.decision,
+                command: entry.command,
+                cwd: entry.cwd,
+                count: 0,
+                rule_id: after.rule_id,
+                reason: after.reason,
+            })
+            .count += 1;
+    })?;
+    if let Some(e) = error {
+        return Err(e);
+    }
+
+    let mut flips: Vec<Flip> = flips.into_values().collect();
+    flips.sort_by(|a, b| {
+        b.is_tightening()
+            .cmp(&a.is_tightening())
+            .then_with(|| b.to.cmp(&a.to))
+            .then_with(|| a.from.cmp(&b.from))
+            .then_with(|| b.count.cmp(&a.count))
+            .then_with(|| a.command.cmp(&b.command))
+            .then_with(|| a.cwd.cmp(&b.cwd))
+    });
+    report.flips = flips;
+    Ok(report)
+}
+
+/// The budget violations of `report`: more tightened or loosened entries
+/// than allowed.
+pub(crate) fn budget_violations(
+    report: &ReplayReport,
+    max_tightened: Option<usize>,
+    max_loosened: Option<usize>,
+) -> Vec<String> {
+    let checks = [
+        ("tightened", report.tightened, max_tightened),
+        ("loosened", report.loosened, max_loosened),
+    ];
+    checks
+        .into_iter()
+        .filter_map(|(kind, count, max)| {
+            let max = max?;
+            (count > max).then(|| format!("{count} {kind} decision(s) exceed --max-{kind} {max}"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(decision: &str, command: &str) -> serde_json::Value {
+        serde_json::json!({
+            "runtime": "claude",
+            "ts": "2026-03-01T12:00:00.000Z",
+            "tool": "Bash",
+            "cwd": "",
+            "command": command,
+            "decision": decision,
+            "matched_rules": [],
+        })
+    }
+
+    fn run(lines: &[serde_json::Value]) -> ReplayReport {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("longline.jsonl");
+        let body: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        std::fs::write(&path, body.join("\n") + "\n").unwrap();
+        let base = policy::load_embedded_rules().unwrap();
+        let options = ReplayOptions {
+            home: dir.path(),
+            dir_override: None,
+            cli_trust_level: None,
+            cli_safety_level: None,
+            profile: None,
+        };
+        replay(
+            &base,
+            &[(path, "claude")],
+            &AuditFilter::default(),
+            &options,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_replay_reports_flips_against_logged_policy_decision() {
+        let mut judged = entry("allow", "frobctl status");
+        judged["original_decision"] = serde_json::json!("ask");
+        let report = run(&[
+            entry("allow", "ls -la"),
+            entry("allow", "rm -rf /"),
+            entry("allow", "rm -rf /"),
+            entry("ask", "git status"),
+            judged,
+            serde_json::json!({ "tool": "Read", "command": "x", "decision": "ask" }),
+        ]);
+        assert_eq!(report.replayed, 5);
+        assert_eq!(report.unchanged, 2);
+        assert_eq!(report.tightened, 2);
+        assert_eq!(report.loosened, 1);
+        let transitions: Vec<(Decision, Decision, &str, usize)> = report
+            .flips
+            .iter()
+            .map(|f| (f.from, f.to, f.command.as_str(), f.count))
+            .collect();
+        assert_eq!(
+            transitions,
+            vec![
+                (Decision::Allow, Decision::Deny, "rm -rf /", 2),
+                (Decision::Ask, Decision::Allow, "git status", 1),
+            ]
+        );
+        assert_eq!(
+            report.flips[0].rule_id.as_deref(),
+            Some("rm-recursive-root")
+        );
+    }
+
+    #[test]
+    fn test_replay_evaluates_from_the_logged_directory() {
+        let at = |cwd: &str| {
+            let mut e = entry("allow", "echo x > hosts");
+            e["cwd"] = serde_json::json!(cwd);
+            e
+        };
+        let report = run(&[at("/tmp"), at("/etc")]);
+        assert_eq!(report.unchanged, 1);
+        assert_eq!(report.tightened, 1);
+        assert_eq!(report.flips[0].cwd, "/etc");
+        assert_eq!(
+            report.flips[0].rule_id.as_deref(),
+            Some("redirect-write-etc")
+        );
+    }
+
+    #[test]
+    fn test_budget_violations() {
+        let report = run(&[entry("allow", "rm -rf /"), entry("ask", "git status")]);
+        assert!(budget_violations(&report, None, None).is_empty());
+        assert!(budget_violations(&report, Some(1), Some(1)).is_empty());
+        assert_eq!(
+            budget_violations(&report, Some(0), Some(0)),
+            vec![
+                "1 tightened decision(s) exceed --max-tightened 0",
+                "1 loosened decision(s) exceed --max-loosened 0",
+            ]
+        );
+    }
+}
@@ -0,0 +1,497 @@
+//! `longline test <file-or-dir>`: run YAML decision suites against the
+//! configuration, in the same format as the repo's golden tests.
+//!
+//! Each case is evaluated under the base rules finalized for the case's
+//! `cwd`, `runtime` and `profile`. A case's `overlay` (or the suite's)
+//! stands in for the global `longline.yaml`: it is written to a scratch
+//! HOME that replaces the real one for that case.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use longline::config;
+use longline::domain::{Decision, PolicyResult};
+use longline::parser;
+use longline::paths;
+use longline::policy::{self, PathTool, RulesConfig, SafetyLevel, TrustLevel};
+use longline::urls;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Suite {
+    /// Overlay for every case that does not set its own.
+    #[serde(default)]
+    overlay: Option<String>,
+    tests: Vec<Case>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Case {
+    id: String,
+    /// Shell command for `Bash`, target path for path tools, URL for
+    /// `WebFetch`.
+    #[serde(default)]
+    command: String,
+    /// Hook tool the case exercises (default `Bash`).
+    #[serde(default)]
+    tool: Option<String>,
+    /// MCP tool call to evaluate instead of `command`.
+    #[serde(default)]
+    mcp: Option<McpCall>,
+    /// WebFetch URL to evaluate instead of `command`.
+    #[serde(default)]
+    fetch: Option<String>,
+    /// Hook cwd: selects the project overlay and anchors relative paths.
+    /// Relative to the suite file's directory.
+    #[serde(default)]
+    cwd: Option<PathBuf>,
+    /// `claude` (default) or `codex`, for runtime profile defaults.
+    #[serde(default)]
+    runtime: Option<String>,
+    expected: Expected,
+    #[serde(default)]
+    overlay: Option<String>,
+    #[serde(default)]
+    profile: Option<String>,
+    #[serde(default)]
+    #[allow(dead_code)]
+    note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct McpCall {
+    tool: String,
+    #[serde(default)]
+    input: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Expected {
+    decision: Decision,
+    #[serde(default)]
+    rule_id: Option<String>,
+}
+
+impl Case {
+    /// What the case evaluates, for reports.
+    fn subject(&self) -> String {
+        match (&self.mcp, &self.fetch, self.tool.as_deref()) {
+            (Some(mcp), _, _) => format!("mcp={} input={}", mcp.tool, mcp.input),
+            (None, Some(url), _) => format!("fetch='{url}'"),
+            (None, None, Some(tool)) if tool != "Bash" => format!("{tool}='{}'", self.command),
+            (None, None, _) => format!("command='{}'", self.command),
+        }
+    }
+}
+
+/// How each case's configuration is finalized.
+pub(crate) struct SuiteOptions<'a> {
+    pub home: &'a Path,
+    /// Project directory for cases without a `cwd`.
+    pub dir_override: Option<&'a Path>,
+    pub cli_trust_level: Option<TrustLevel>,
+    pub cli_safety_level: Option<SafetyLevel>,
+    /// Profile for cases without a `profile`.
+    pub profile: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct CaseResult {
+    pub id: String,
+    pub subject: String,
+    pub expected: Decision,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_rule_id: Option<String>,
+    /// Unset when the case could not be evaluated.
+    pub decision: Option<Decision>,
+    pub rule_id: Option<String>,
+    /// Set when the case could not be evaluated (a parse or config error);
+    /// the case then fails.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl CaseResult {
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+            && self.decision == Some(self.expected)
+            && self
+                .expected_rule_id
+                .as_ref()
+                .is_none_or(|id| self.rule_id.as_ref() == Some(id))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SuiteReport {
+    pub file: String,
+    /// Set when the file could not be read or parsed; it has no cases then.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub cases: Vec<CaseResult>,
+}
+
+impl SuiteReport {
+    pub fn failed(&self) -> usize {
+        self.cases.iter().filter(|c| !c.passed()).count()
+    }
+
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.failed() == 0
+    }
+}
+
+/// The suite files under `path`: the file itself, or the `.yaml`/`.yml`
+/// files directly inside a directory, sorted by name.
+pub(crate) fn suite_files(path: &Path) -> Result<Vec<PathBuf>, String> {
+    if !path.is_dir() {
+        if !path.exists() {
+            return Err(format!("{} does not exist", path.display()));
+        }
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let entries =
+        std::fs::read_dir(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.is_file()
+                && p.extension()
+                    .is_some_and(|ext| ext == "yaml" || ext == "yml")
+        })
+        .collect();
+    files.sort();
+    if files.is_empty() {
+        return Err(format!("No .yaml suite files in {}", path.display()));
+    }
+    Ok(files)
+}
+
+/// Run every suite file under `base` finalized per case.
+pub(crate) fn run_suites(
+    base: &RulesConfig,
+    files: &[PathBuf],
+    options: &SuiteOptions,
+) -> Vec<SuiteReport> {
+    let mut configs: HashMap<ConfigKey, Result<RulesConfig, String>> = HashMap::new();
+    files
+        .iter()
+        .map(|file| run_suite(base, file, options, &mut configs))
+        .collect()
+}
+
+/// (project dir, runtime, profile) of a case without an overlay.
+type ConfigKey = (Option<PathBuf>, String, Option<String>);
+
+fn run_suite(
+    base: &RulesConfig,
+    file: &Path,
+    options: &SuiteOptions,
+    configs: &mut HashMap<ConfigKey, Result<RulesConfig, String>>,
+) -> SuiteReport {
+    let mut report = SuiteReport {
+        file: file.display().to_string(),
+        error: None,
+        cases: vec![],
+    };
+    let suite: Suite = match std::fs::read_to_string(file)
+        .map_err(|e| format!("Failed to read {}: {e}", file.display()))
+        .and_then(|content| {
+            serde_norway::from_str(&content)
+                .map_err(|e| format!("Failed to parse {}: {e}", file.display()))
+        }) {
+        Ok(suite) => suite,
+        Err(e) => {
+            report.error = Some(e);
+            return report;
+        }
+    };
+    let suite_dir = file.parent().unwrap_or(Path::new("."));
+
+    for case in &suite.tests {
+        let cwd = case.cwd.as_ref().map(|cwd| suite_dir.join(cwd));
+        let overlay = case.overlay.as_deref().or(suite.overlay.as_deref());
+        let outcome = case_config(base, case, overlay, cwd.as_deref(), options, configs)
+            .and_then(|config| evaluate_case(&config, case, cwd.as_deref()));
+        let (decision, rule_id, error) = match outcome {
+            Ok(result) => (Some(result.decision), result.rule_id, None),
+            Err(e) => (None, None, Some(e)),
+        };
+        report.cases.push(CaseResult {
+            id: case.id.clone(),
+            subject: case.subject(),
+            expected: case.expected.decision,
+            expected_rule_id: case.expected.rule_id.clone(),
+            decision,
+            rule_id,
+            error,
+        });
+    }
+    report
+}
+
+fn case_config(
+    base: &RulesConfig,
+    case: &Case,
+    overlay: Option<&str>,
+    cwd: Option<&Path>,
+    options: &SuiteOptions,
+    configs: &mut HashMap<ConfigKey, Result<RulesConfig, String>>,
+) -> Result<RulesConfig, String> {
+    let runtime = case.runtime.as_deref().unwrap_or("claude");
+    if !matches!(runtime, "claude" | "codex") {
+        return Err(format!(
+            "unknown runtime '{runtime}' (expected claude or codex)"
+        ));
+    }
+    let project_dir = cwd.or(options.dir_override);
+    let profile = case.profile.as_deref().or(options.profile);
+    let finalize = |home: &Path| {
+        config::finalize_config(
+            base.clone(),
+            home,
+            project_dir,
+            options.cli_trust_level,
+            options.cli_safety_level,
+            runtime,
+            profile,
+        )
+        .map(|fc| fc.rules)
+    };
+
+    let Some(overlay) = overlay else {
+        let key = (
+            project_dir.map(Path::to_path_buf),
+            runtime.to_string(),
+            profile.map(str::to_string),
+        );
+        return configs
+            .entry(key)
+            .or_insert_with(|| finalize(options.home))
+            .clone();
+    };
+    let home = ScratchHome::new()?;
+    if !overlay.trim().is_empty() {
+        let path = config::global_config_path(&home.0);
+        std::fs::create_dir_all(path.parent().unwrap())
+            .and_then(|()| std::fs::write(&path, overlay))
+            .map_err(|e| format!("Failed to write overlay: {e}"))?;
+    }
+    finalize(&home.0)
+}
+
+fn evaluate_case(
+    config: &RulesConfig,
+    case: &Case,
+    cwd: Option<&Path>,
+) -> Result<PolicyResult, String> {
+    if let Some(mcp) = &case.mcp {
+        return evaluate_mcp(config, &mcp.tool, &mcp.input);
+    }
+    if let Some(raw) = &case.fetch {
+        return evaluate_url(config, raw);
+    }
+    let path_access = |access: PathTool| {
+        let cwd = cwd.map(|p| p.to_string_lossy());
+        let resolved = paths::resolve(&case.command, cwd.as_deref());
+        policy::evaluate_path_access(config, access, &resolved)
+    };
+    match case.tool.as_deref().unwrap_or("Bash") {
+        "Bash" => {
+            let stmt = parser::parse(&case.command)?;
+            let cwd = cwd.and_then(|p| std::path::absolute(p).ok());
+            let cwd = cwd.as_deref().and_then(Path::to_str);
+            Ok(policy::evaluate_with_findings(config, &stmt, cwd).result)
+        }
+        "Read" => Ok(path_access(PathTool::Read)),
+        "Grep" | "Glob" => Ok(path_access(PathTool::Search)),
+        "Write" | "Edit" | "MultiEdit" | "NotebookEdit" => Ok(path_access(PathTool::Write)),
+        "WebFetch" => evaluate_url(config, &case.command),
+        name if name.starts_with("mcp__") => evaluate_mcp(config, name, &serde_json::json!({})),
+        other => Err(format!("unsupported tool '{other}'")),
+    }
+}
+
+fn evaluate_mcp(
+    config: &RulesConfig,
+    name: &str,
+    input: &serde_json::Value,
+) -> Result<PolicyResult, String> {
+    let (server, tool) = policy::split_mcp_tool_name(name)
+        .ok_or_else(|| format!("'{name}' is not an mcp__<server>__<tool> name"))?;
+    Ok(policy::evaluate_mcp_call(config, server, tool, input))
+}
+
+fn evaluate_url(config: &RulesConfig, raw: &str) -> Result<PolicyResult, String> {
+    let url = urls::parse(raw, None).ok_or_else(|| format!("'{raw}' is not a URL"))?;
+    Ok(policy::evaluate_url_access(config, &url))
+}
+
+/// A temporary HOME, removed on drop.
+struct ScratchHome(PathBuf);
+
+impl ScratchHome {
+    fn new() -> Result<Self, String> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let n = NEXT.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("longline-test-{}-{n}", std::process::id()));
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        Ok(Self(dir))
+    }
+}
+
+impl Drop for ScratchHome {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Render `reports` as JUnit XML: one `<testsuite>` per file, failed
+/// expectations as `<failure>` and unevaluable cases as `<error>`.
+pub(crate) fn junit_xml(reports: &[SuiteReport]) -> String {
+    let count = |f: &dyn Fn(&SuiteReport) -> usize| reports.iter().map(f).sum::<usize>();
+    let tests = count(&|r| r.cases.len().max(usize::from(r.error.is_some())));
+    let errors = count(&suite_errors);
+    let failures = count(&|r| r.failed()) - count(&case_errors);
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"longline\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\">\n"
+    ));
+    for report in reports {
+        let name = escape(&report.file);
+        let suite_failures = report.failed() - case_errors(report);
+        xml.push_str(&format!(
+            "  <testsuite name=\"{name}\" tests=\"{}\" failures=\"{suite_failures}\" errors=\"{}\">\n",
+            report.cases.len().max(usize::from(report.error.is_some())),
+            suite_errors(report)
+        ));
+        if let Some(error) = &report.error {
+            xml.push_str(&format!(
+                "    <testcase name=\"{name}\" classname=\"{name}\">\n      <error message=\"{}\"/>\n    </testcase>\n",
+                escape(error)
+            ));
+        }
+        for case in &report.cases {
+            xml.push_str(&format!(
+                "    <testcase name=\"{}\" classname=\"{name}\"",
+                escape(&case.id)
+            ));
+            if case.passed() {
+                xml.push_str("/>\n");
+                continue;
+            }
+            let (tag, message) = match &case.error {
+                Some(error) => ("error", error.clone()),
+                None => ("failure", mismatch(case)),
+            };
+            xml.push_str(&format!(
+                ">\n      <{tag} message=\"{}\">{}</{tag}>\n    </testcase>\n",
+                escape(&message),
+                escape(&case.subject)
+            ));
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn case_errors(report: &SuiteReport) -> usize {
+    report.cases.iter().filter(|c| c.error.is_some()).count()
+}
+
+fn suite_errors(report: &SuiteReport) -> usize {
+    case_errors(report) + usize::from(report.error.is_some())
+}
+
+/// "expected deny (rule-a), got ask (rule-b)" for a failed case.
+pub(crate) fn mismatch(case: &CaseResult) -> String {
+    let decision = |d: Option<Decision>, rule: Option<&String>| {
+        let d = d.map_or("nothing".to_string(), |d| format!("{d:?}").to_lowercase());
+        match rule {
+            Some(rule) => format!("{d} ({rule})"),
+            None => d,
+        }
+    };
+    format!(
+        "expected {}, got {}",
+        decision(Some(case.expected), case.expected_rule_id.as_ref()),
+        decision(case.decision, case.rule_id.as_ref())
+    )
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\n', "&#10;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn case(id: &str, expected: Decision, decision: Option<Decision>) -> CaseResult {
+        CaseResult {
+            id: id.to_string(),
+            subject: "command='rm -rf /'".to_string(),
+            expected,
+            expected_rule_id: None,
+            decision,
+            rule_id: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_case_passes_only_on_matching_decision_and_rule() {
+        let mut c = case("a", Decision::Deny, Some(Decision::Deny));
+        assert!(c.passed());
+        c.expected_rule_id = Some("rm-root".to_string());
+        assert!(!c.passed());
+        c.rule_id = Some("rm-root".to_string());
+        assert!(c.passed());
+        c.decision = Some(Decision::Ask);
+        assert!(!c.passed());
+        assert_eq!(mismatch(&c), "expected deny (rm-root), got ask (rm-root)");
+    }
+
+    #[test]
+    fn test_junit_counts_failures_and_errors() {
+        let mut errored = case("c", Decision::Allow, None);
+        errored.error = Some("parse error".to_string());
+        let reports = vec![
+            SuiteReport {
+                file: "a&b.yaml".to_string(),
+                error: None,
+                cases: vec![
+                    case("a", Decision::Deny, Some(Decision::Deny)),
+                    case("b", Decision::Deny, Some(Decision::Ask)),
+                    errored,
+                ],
+            },
+            SuiteReport {
+                file: "broken.yaml".to_string(),
+                error: Some("Failed to parse".to_string()),
+                cases: vec![],
+            },
+        ];
+        let xml = junit_xml(&reports);
+        assert!(xml.contains(r#"<testsuites name="longline" tests="4" failures="1" errors="2">"#));
+        assert!(
+            xml.contains(r#"<testsuite name="a&amp;b.yaml" tests="3" failures="1" errors="1">"#)
+        );
+        assert!(xml.contains(r#"<testcase name="a" classname="a&amp;b.yaml"/>"#));
+        assert!(xml.contains(r#"<failure message="expected deny, got ask">"#));
+        assert!(xml.contains(r#"<error message="Failed to parse"/>"#));
+    }
+}
@@ -0,0 +1,140 @@
+//! Minimal URL parsing shared by `url` rules on WebFetch and on the URL
+//! arguments of HTTP clients (`curl`, `wget`, `http`).
+
+/// The parts of a URL that `url` rules match on. `host` and `scheme` are
+/// lowercased; `port` is the explicit port or the scheme's default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedUrl {
+    pub raw: String,
+    pub scheme: String,
+    pub host: String,
+    pub port: Option<u16>,
+    /// Path component, `/` when the URL has none.
+    pub path: String,
+    /// Query string without the leading `?`, empty when absent.
+    pub query: String,
+}
+
+/// Parse `raw` as `scheme://[userinfo@]host[:port][/path][?query][#fragment]`.
+/// When `raw` has no `scheme://` prefix it is read as `host[:port][/path]`
+/// under `default_scheme`, the way `curl example.com` is; without a default
+/// scheme it is rejected. Returns `None` for an empty host or a bad port.
+pub fn parse(raw: &str, default_scheme: Option<&str>) -> Option<ParsedUrl> {
+    let trimmed = raw.trim();
+    let (scheme, rest) = match trimmed.split_once("://") {
+        Some((scheme, rest)) if is_scheme(scheme) => (scheme.to_ascii_lowercase(), rest),
+        Some(_) => return None,
+        None => (default_scheme?.to_ascii_lowercase(), trimmed),
+    };
+
+    let rest = rest.split('#').next().unwrap_or_default();
+    let authority_end = rest.find(['/', '?']).unwrap_or(rest.len());
+    let (authority, tail) = rest.split_at(authority_end);
+    let (path, query) = match tail.split_once('?') {
+        Some((path, query)) => (path, query),
+        None => (tail, ""),
+    };
+
+    let host_port = authority.rsplit_once('@').map_or(authority, |(_, hp)| hp);
+    let (host, port) = split_host_port(host_port)?;
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    if host.is_empty() {
+        return None;
+    }
+    let port = match port {
+        Some(port) => Some(port.parse::<u16>().ok()?),
+        None => default_port(&scheme),
+    };
+
+    Some(ParsedUrl {
+        raw: raw.to_string(),
+        scheme,
+        host,
+        port,
+        path: if path.is_empty() { "/" } else { path }.to_string(),
+        query: query.to_string(),
+    })
+}
+
+fn is_scheme(scheme: &str) -> bool {
+    let mut chars = scheme.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// Split `host[:port]`, keeping the brackets off an IPv6 literal.
+fn split_host_port(authority: &str) -> Option<(&str, Option<&str>)> {
+    if let Some(rest) = authority.strip_prefix('[') {
+        let (host, after) = rest.split_once(']')?;
+        return match after {
+            "" => Some((host, None)),
+            _ => Some((host, Some(after.strip_prefix(':')?))),
+        };
+    }
+    Some(match authority.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (authority, None),
+    })
+}
+
+fn default_port(scheme: &str) -> Option<u16> {
+    match scheme {
+        "http" | "ws" => Some(80),
+        "https" | "wss" => Some(443),
+        "ftp" => Some(21),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_url() {
+        let url = parse(
+            "HTTPS://user:pw@Docs.RS:8443/serde/latest?search=x#frag",
+            None,
+        )
+        .unwrap();
+        assert_eq!(url.scheme, "https");
+        assert_eq!(url.host, "docs.rs");
+        assert_eq!(url.port, Some(8443));
+        assert_eq!(url.path, "/serde/latest");
+        assert_eq!(url.query, "search=x");
+    }
+
+    #[test]
+    fn test_parse_defaults_port_and_path() {
+        let url = parse("https://github.com", None).unwrap();
+        assert_eq!(url.port, Some(443));
+        assert_eq!(url.path, "/");
+        assert_eq!(url.query, "");
+        assert_eq!(parse("http://example.com?q=1", None).unwrap().query, "q=1");
+    }
+
+    #[test]
+    fn test_parse_schemeless_uses_default_scheme() {
+        let url = parse("example.com:8080/api", Some("http")).unwrap();
+        assert_eq!(url.scheme, "http");
+        assert_eq!(url.host, "example.com");
+        assert_eq!(url.port, Some(8080));
+        assert_eq!(url.path, "/api");
+        assert!(parse("example.com/api", None).is_none());
+    }
+
+    #[test]
+    fn test_parse_ipv6_literal() {
+        let url = parse("http://[::1]:3000/health", None).unwrap();
+        assert_eq!(url.host, "::1");
+        assert_eq!(url.port, Some(3000));
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed() {
+        assert!(parse("https://", None).is_none());
+        assert!(parse("https://host:http/", None).is_none());
+        assert!(parse("1http://host/", None).is_none());
+        assert!(parse("", Some("http")).is_none());
+    }
+}
@@ -86,7 +86,7 @@ fn temp_home() -> tempfile::TempDir {
 
 #[test]
 fn test_e2e_non_bash_tool_passes_through() {
-    let result = run_claude_hook("Write", "");
+    let result = run_claude_hook("TodoWrite", "");
     assert_eq!(result.exit_code, 0);
     assert_eq!(
         result.stdout.trim(),
@@ -97,7 +97,7 @@ fn test_e2e_non_bash_tool_passes_through() {
 
 #[test]
 fn test_e2e_unsupported_tool_passthrough_exact_json() {
-    let result = run_claude_hook("Write", "");
+    let result = run_claude_hook("TodoWrite", "");
     assert_eq!(result.exit_code, 0);
     assert_eq!(result.stdout, "{}\n");
     assert_eq!(result.stderr, "");
@@ -169,7 +169,7 @@ fn test_e2e_unsupported_tool_config_finalization_error_exits_2_without_json() {
         .with_global_config("override_trust_level: impossible\n")
         .build();
 
-    let result = env.run_claude_tool_hook("Write", "");
+    let result = env.run_claude_tool_hook("TodoWrite", "");
 
     assert_eq!(result.exit_code, 2);
     assert_eq!(result.stdout, "");
@@ -396,6 +396,45 @@ fn test_e2e_claude_log_entry_includes_runtime_field() {
     assert_eq!(entry["decision"], "deny");
 }
 
+#[test]
+fn test_e2e_claude_log_entry_records_findings_and_deciding_leaf() {
+    let env = TestEnv::new().build();
+    let result = env.run_claude_hook("timeout 5 rm -rf / && echo $(cat ~/.ssh/id_rsa)");
+    result.assert_claude_decision("deny");
+
+    let entry = last_audit_entry(env.home_path(), "claude");
+    assert_eq!(
+        entry["matched_rules"],
+        serde_json::json!(["rm-recursive-root"])
+    );
+    assert_eq!(
+        entry["deciding_leaf"],
+        serde_json::json!({ "text": "rm -rf /", "bucket": "wrapper" })
+    );
+    assert_eq!(
+        entry["findings"],
+        serde_json::json!([
+            { "rule_id": "rm-recursive-root", "decision": "deny", "leaf": "rm -rf /", "bucket": "wrapper" },
+            { "rule_id": "rm-generic", "decision": "ask", "leaf": "rm -rf /", "bucket": "wrapper" },
+            { "rule_id": "cat-ssh-key", "decision": "ask", "leaf": "cat ~/.ssh/id_rsa", "bucket": "substitution" },
+        ])
+    );
+}
+
+#[test]
+fn test_e2e_claude_log_entry_omits_findings_when_nothing_matched() {
+    let env = TestEnv::new().build();
+    env.run_claude_hook("ls -la")
+        .assert_claude_decision("allow");
+
+    let entry = last_audit_entry(env.home_path(), "claude");
+    assert!(entry.get("findings").is_none(), "{entry}");
+    assert_eq!(
+        entry["deciding_leaf"],
+        serde_json::json!({ "text": "ls -la", "bucket": "original" })
+    );
+}
+
 #[test]
 fn git_editor_overrides_preserve_claude_wire_and_audit_contract() {
     let cases = [
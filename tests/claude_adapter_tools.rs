@@ -1,7 +1,9 @@
 mod support;
 use support::claude::{
-    run_claude_glob_hook, run_claude_grep_hook, run_claude_read_hook, ClaudeRunResultExt,
+    run_claude_glob_hook, run_claude_grep_hook, run_claude_read_hook, run_claude_write_hook,
+    ClaudeRunResultExt, ClaudeTestEnvExt,
 };
+use support::config::TestEnv;
 
 // ── Normal files: should allow ──────────────────────────────────────
 
@@ -125,6 +127,16 @@ fn test_read_etc_shadow_asks() {
     result.assert_claude_decision("ask");
 }
 
+#[test]
+fn test_read_etc_shadow_via_parent_dir_asks() {
+    // cwd is /tmp, so ../etc/shadow resolves to /etc/shadow.
+    let result = run_claude_read_hook("../etc/shadow");
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("ask");
+    result.assert_claude_reason_contains("path-etc-shadow");
+    result.assert_claude_reason_contains("../etc/shadow -> /etc/shadow");
+}
+
 // ── Grep: normal paths (allow) ──────────────────────────────────────
 
 #[test]
@@ -230,3 +242,331 @@ fn test_glob_etc_shadow_asks() {
     assert_eq!(result.exit_code, 0);
     result.assert_claude_decision("ask");
 }
+
+// ── Write/Edit/MultiEdit/NotebookEdit: normal paths (allow) ─────────
+
+#[test]
+fn test_write_project_file_allows() {
+    let result = run_claude_write_hook("Write", "/home/user/project/src/main.rs");
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("allow");
+}
+
+#[test]
+fn test_edit_tmp_file_allows() {
+    let result = run_claude_write_hook("Edit", "/tmp/scratch.txt");
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("allow");
+}
+
+#[test]
+fn test_notebook_edit_project_notebook_allows() {
+    let result = run_claude_write_hook("NotebookEdit", "/home/user/project/analysis.ipynb");
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("allow");
+}
+
+// ── Write/Edit: redirect-protected paths (ask/deny) ─────────────────
+
+#[test]
+fn test_edit_bashrc_asks() {
+    let result = run_claude_write_hook("Edit", "/home/user/.bashrc");
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("ask");
+    result.assert_claude_reason_contains("redirect-write-shell-profile");
+}
+
+#[test]
+fn test_multi_edit_zshrc_asks() {
+    let result = run_claude_write_hook("MultiEdit", "/home/user/.zshrc");
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("ask");
+}
+
+#[test]
+fn test_write_env_file_asks() {
+    let result = run_claude_write_hook("Write", "/home/user/project/.env");
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("ask");
+    result.assert_claude_reason_contains("redirect-write-env-file");
+}
+
+#[test]
+fn test_write_authorized_keys_asks() {
+    let result = run_claude_write_hook("Write", "/home/user/.ssh/authorized_keys");
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("ask");
+    result.assert_claude_reason_contains("redirect-write-ssh-authorized-keys");
+}
+
+#[test]
+fn test_edit_etc_file_asks() {
+    let result = run_claude_write_hook("Edit", "/etc/nginx/nginx.conf");
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("ask");
+    result.assert_claude_reason_contains("redirect-write-etc");
+}
+
+#[test]
+fn test_write_ci_workflow_asks() {
+    let result = run_claude_write_hook("Write", "/home/user/project/.github/workflows/ci.yml");
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("ask");
+    result.assert_claude_reason_contains("path-ci-config");
+}
+
+#[test]
+fn test_read_ci_workflow_allows() {
+    let result = run_claude_read_hook("/home/user/project/.github/workflows/ci.yml");
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("allow");
+}
+
+#[test]
+fn test_edit_etc_file_via_relative_path_asks() {
+    let result = run_claude_write_hook("Edit", "./sub/../../etc/hosts");
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("ask");
+    result.assert_claude_reason_contains("redirect-write-etc");
+}
+
+#[test]
+fn test_write_aws_credentials_asks() {
+    let result = run_claude_write_hook("Write", "/home/user/.aws/credentials");
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("ask");
+    result.assert_claude_reason_contains("redirect-write-cloud-credentials");
+}
+
+#[test]
+fn test_write_git_hook_denies() {
+    let result = run_claude_write_hook("Write", "/home/user/project/.git/hooks/pre-commit");
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("deny");
+    result.assert_claude_reason_contains("redirect-write-git-admin");
+}
+
+// ── Path rules from overlays ────────────────────────────────────────
+
+const PROJECT_PATH_RULES: &str = r#"
+rules:
+  - id: project-secrets-dir
+    level: high
+    match:
+      path:
+        target: "**/secrets/**"
+    decision: ask
+    reason: "Accessing project secrets"
+  - id: project-pem-read
+    level: high
+    match:
+      path:
+        target: "**/*.pem"
+        tools: [read]
+    decision: deny
+    reason: "Reading PEM key material"
+"#;
+
+#[test]
+fn test_project_path_rule_asks_on_read() {
+    let env = TestEnv::new()
+        .with_project_config(PROJECT_PATH_RULES)
+        .build();
+    let result = env.run_claude_path_hook("Read", "/repo/secrets/prod.yaml");
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("ask");
+    result.assert_claude_reason_contains("project-secrets-dir");
+}
+
+#[test]
+fn test_project_path_rule_applies_to_writes() {
+    let env = TestEnv::new()
+        .with_project_config(PROJECT_PATH_RULES)
+        .build();
+    let result = env.run_claude_path_hook("Edit", "/repo/secrets/prod.yaml");
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("ask");
+}
+
+#[test]
+fn test_project_path_rule_tools_filter_skips_search() {
+    let env = TestEnv::new()
+        .with_project_config(PROJECT_PATH_RULES)
+        .build();
+    let read = env.run_claude_path_hook("Read", "/repo/certs/server.pem");
+    read.assert_claude_decision("deny");
+    let search = env.run_claude_path_hook("Grep", "/repo/certs/server.pem");
+    search.assert_claude_decision("allow");
+}
+
+#[test]
+fn test_project_disable_rules_removes_builtin_path_rule() {
+    let env = TestEnv::new()
+        .with_project_config("disable_rules:\n  - path-ssh-dir\n")
+        .build();
+    let result = env.run_claude_path_hook("Read", "/home/user/.ssh/config");
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("allow");
+}
+
+#[test]
+fn test_project_path_allowlist_exempts_builtin_ask() {
+    let env = TestEnv::new()
+        .with_project_config("allowlists:\n  paths:\n    - \"**/.ssh/known_hosts\"\n")
+        .build();
+    let allowed = env.run_claude_path_hook("Read", "/home/user/.ssh/known_hosts");
+    allowed.assert_claude_decision("allow");
+    let still_asks = env.run_claude_path_hook("Read", "/home/user/.ssh/id_rsa");
+    still_asks.assert_claude_decision("ask");
+}
+
+// ── MCP tool calls: `mcp` rules from config, passthrough otherwise ──
+
+const PROJECT_MCP_RULES: &str = r#"
+rules:
+  - id: project-mcp-db-destructive
+    level: high
+    match:
+      mcp:
+        server: "postgres*"
+        tool: query
+        input:
+          - pointer: /sql
+            contains: { any_of: ["drop table", "truncate"] }
+    decision: deny
+    reason: "Destructive SQL via MCP"
+  - id: project-mcp-db-select
+    level: high
+    match:
+      mcp:
+        server: "postgres*"
+        tool: query
+        input:
+          - pointer: /sql
+            matches: "SELECT *"
+    decision: allow
+    reason: "Read-only query"
+"#;
+
+#[test]
+fn test_mcp_rule_denies_matching_input() {
+    let env = TestEnv::new()
+        .with_project_config(PROJECT_MCP_RULES)
+        .build();
+    let result = env.run_claude_tool_input_hook(
+        "mcp__postgres-prod__query",
+        serde_json::json!({"sql": "DROP TABLE users"}),
+    );
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("deny");
+    result.assert_claude_reason_contains(
+        "[project-mcp-db-destructive] Destructive SQL via MCP (mcp__postgres-prod__query)",
+    );
+}
+
+#[test]
+fn test_mcp_rule_allows_matching_input() {
+    let env = TestEnv::new()
+        .with_project_config(PROJECT_MCP_RULES)
+        .build();
+    let result = env.run_claude_tool_input_hook(
+        "mcp__postgres__query",
+        serde_json::json!({"sql": "SELECT id FROM users"}),
+    );
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("allow");
+}
+
+#[test]
+fn test_mcp_call_without_matching_rule_passes_through() {
+    let env = TestEnv::new()
+        .with_project_config(PROJECT_MCP_RULES)
+        .build();
+    let result = env.run_claude_tool_input_hook(
+        "mcp__postgres__query",
+        serde_json::json!({"sql": "UPDATE users SET name = 'x'"}),
+    );
+    assert_eq!(result.exit_code, 0);
+    assert_eq!(result.stdout.trim(), "{}");
+}
+
+// ---------- WebFetch: `url` rules plus `allowlists.hosts` ----------
+
+const PROJECT_URL_RULES: &str = r#"
+allowlists:
+  hosts: ["docs.rs", "github.com", "*.github.com", "docs.internal.test"]
+rules:
+  - id: project-url-unlisted-host
+    level: high
+    match:
+      url: {}
+    decision: ask
+    reason: "Fetching from a host outside the allowlist"
+  - id: project-url-paste-site
+    level: high
+    match:
+      url:
+        host: pastebin.com
+    decision: deny
+    reason: "Paste sites are an exfiltration channel"
+"#;
+
+fn web_fetch_input(url: &str) -> serde_json::Value {
+    serde_json::json!({"url": url, "prompt": "Summarize this page"})
+}
+
+#[test]
+fn test_web_fetch_allowlisted_host_allows() {
+    let env = TestEnv::new()
+        .with_project_config(PROJECT_URL_RULES)
+        .build();
+    let result =
+        env.run_claude_tool_input_hook("WebFetch", web_fetch_input("https://docs.rs/serde"));
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("allow");
+    result.assert_claude_reason_contains("Allowlisted host (docs.rs)");
+}
+
+#[test]
+fn test_web_fetch_unlisted_host_asks() {
+    let env = TestEnv::new()
+        .with_project_config(PROJECT_URL_RULES)
+        .build();
+    let result =
+        env.run_claude_tool_input_hook("WebFetch", web_fetch_input("https://example.com/"));
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("ask");
+    result.assert_claude_reason_contains("project-url-unlisted-host");
+}
+
+#[test]
+fn test_web_fetch_deny_rule_denies() {
+    let env = TestEnv::new()
+        .with_project_config(PROJECT_URL_RULES)
+        .build();
+    let result =
+        env.run_claude_tool_input_hook("WebFetch", web_fetch_input("https://pastebin.com/raw/abc"));
+    assert_eq!(result.exit_code, 0);
+    result.assert_claude_decision("deny");
+}
+
+#[test]
+fn test_curl_shares_url_policy_with_web_fetch() {
+    let env = TestEnv::new()
+        .with_project_config(PROJECT_URL_RULES)
+        .build();
+    env.run_claude_hook("curl -sSL https://docs.rs/serde")
+        .assert_claude_decision("allow");
+    let result = env.run_claude_hook("curl -s https://example.com/install.sh -o install.sh");
+    result.assert_claude_decision("ask");
+    result.assert_claude_reason_contains("project-url-unlisted-host");
+}
+
+#[test]
+fn test_web_fetch_without_url_rules_passes_through() {
+    let env = TestEnv::new().build();
+    let result =
+        env.run_claude_tool_input_hook("WebFetch", web_fetch_input("https://example.com/"));
+    assert_eq!(result.exit_code, 0);
+    assert_eq!(result.stdout.trim(), "{}");
+}
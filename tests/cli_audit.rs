@@ -0,0 +1,145 @@
+mod support;
+use support::cli::run_subcommand_with_home;
+
+fn entry(runtime: &str, ts: &str, decision: &str, command: &str, rules: &[&str]) -> String {
+    serde_json::json!({
+        "version": "0.0.0",
+        "runtime": runtime,
+        "profile": "default",
+        "ts": ts,
+        "tool": "Bash",
+        "cwd": "",
+        "command": command,
+        "decision": decision,
+        "matched_rules": rules,
+        "reason": null,
+        "parse_ok": true,
+        "session_id": "abc",
+    })
+    .to_string()
+}
+
+fn write_logs(home: &std::path::Path) {
+    let claude = home.join(".claude/hooks-logs");
+    let codex = home.join(".codex/hooks-logs");
+    std::fs::create_dir_all(&claude).unwrap();
+    std::fs::create_dir_all(&codex).unwrap();
+    std::fs::write(
+        claude.join("longline.jsonl"),
+        [
+            entry(
+                "claude",
+                "2026-03-02T10:00:00Z",
+                "ask",
+                "kubectl get pods",
+                &[],
+            ),
+            entry("claude", "2026-03-02T11:00:00Z", "allow", "ls", &[]),
+        ]
+        .join("\n"),
+    )
+    .unwrap();
+    std::fs::write(
+        claude.join("longline.jsonl.1"),
+        entry(
+            "claude",
+            "2026-01-01T00:00:00Z",
+            "ask",
+            "kubectl delete pod x",
+            &[],
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        codex.join("longline.jsonl"),
+        entry(
+            "codex",
+            "2026-03-02T12:00:00Z",
+            "deny",
+            "rm -rf /",
+            &["rm-recursive-root"],
+        ),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_e2e_audit_json_reads_both_runtimes_and_rotated_files() {
+    let home = tempfile::tempdir().unwrap();
+    write_logs(home.path());
+
+    let result = run_subcommand_with_home(&["audit", "--json"], home.path().to_str().unwrap());
+    assert_eq!(result.exit_code, 0, "stderr: {}", result.stderr);
+    let report: serde_json::Value = serde_json::from_str(&result.stdout).unwrap();
+    assert_eq!(report["files"].as_array().unwrap().len(), 3);
+    assert_eq!(report["entries"], 4);
+    assert_eq!(report["ask"], 2);
+    assert_eq!(report["deny"], 1);
+    assert_eq!(
+        report["by_family"][0],
+        serde_json::json!({ "key": "kubectl", "ask": 2, "deny": 0 })
+    );
+    assert_eq!(
+        report["by_runtime"],
+        serde_json::json!([
+            { "key": "claude", "ask": 2, "deny": 0 },
+            { "key": "codex", "ask": 0, "deny": 1 },
+        ])
+    );
+}
+
+#[test]
+fn test_e2e_audit_filters_runtime_and_window() {
+    let home = tempfile::tempdir().unwrap();
+    write_logs(home.path());
+
+    let result = run_subcommand_with_home(
+        &[
+            "audit",
+            "--runtime",
+            "claude",
+            "--since",
+            "2026-02-01",
+            "--json",
+        ],
+        home.path().to_str().unwrap(),
+    );
+    assert_eq!(result.exit_code, 0, "stderr: {}", result.stderr);
+    let report: serde_json::Value = serde_json::from_str(&result.stdout).unwrap();
+    assert_eq!(report["entries"], 2);
+    assert_eq!(report["ask"], 1);
+    assert_eq!(report["deny"], 0);
+}
+
+#[test]
+fn test_e2e_audit_table_output() {
+    let home = tempfile::tempdir().unwrap();
+    write_logs(home.path());
+
+    let result = run_subcommand_with_home(&["audit"], home.path().to_str().unwrap());
+    assert_eq!(result.exit_code, 0, "stderr: {}", result.stderr);
+    for expected in [
+        "2 ask, 1 deny",
+        "COMMAND",
+        "RULE",
+        "rm-recursive-root",
+        "RUNTIME",
+    ] {
+        assert!(
+            result.stdout.contains(expected),
+            "missing {expected:?}: {}",
+            result.stdout
+        );
+    }
+}
+
+#[test]
+fn test_e2e_audit_rejects_bad_time() {
+    let home = tempfile::tempdir().unwrap();
+    let result = run_subcommand_with_home(
+        &["audit", "--since", "yesterday"],
+        home.path().to_str().unwrap(),
+    );
+    assert_eq!(result.exit_code, 1);
+    assert!(result.stderr.contains("invalid time"), "{}", result.stderr);
+}
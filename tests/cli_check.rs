@@ -152,6 +152,24 @@ rules:
     );
 }
 
+#[test]
+fn test_e2e_check_resolves_redirects_against_dir() {
+    let result = run_subcommand(&[
+        "check",
+        "--config",
+        &rules_path(),
+        "--dir",
+        "/etc",
+        "echo x > hosts",
+    ]);
+    assert_eq!(result.exit_code, 0, "stderr={}", result.stderr);
+    assert!(
+        result.stdout.contains("redirect-write-etc"),
+        "Should resolve the redirect from --dir: {}",
+        result.stdout
+    );
+}
+
 #[test]
 fn test_e2e_check_labels_opaque_ask() {
     let dir = tempfile::TempDir::new().unwrap();
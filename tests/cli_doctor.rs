@@ -0,0 +1,68 @@
+mod support;
+use support::cli::run_subcommand_with_home;
+
+#[test]
+fn test_e2e_doctor_clean_home_passes() {
+    let home = tempfile::tempdir().unwrap();
+    let home_str = home.path().to_str().unwrap();
+    run_subcommand_with_home(&["install", "--runtime", "claude"], home_str);
+    run_subcommand_with_home(&["install", "--runtime", "codex"], home_str);
+
+    let result = run_subcommand_with_home(&["doctor", "--dir", home_str], home_str);
+    assert_eq!(result.exit_code, 0, "stdout: {}", result.stdout);
+    assert!(!result.stdout.contains("FAIL"), "{}", result.stdout);
+    assert!(result.stdout.contains("0 failed"), "{}", result.stdout);
+}
+
+#[test]
+fn test_e2e_doctor_reports_overlay_typo_with_its_file() {
+    let home = tempfile::tempdir().unwrap();
+    let project = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(project.path().join(".claude")).unwrap();
+    std::fs::write(
+        project.path().join(".claude/longline.yaml"),
+        "alowlists:\n  commands: []\n",
+    )
+    .unwrap();
+
+    let result = run_subcommand_with_home(
+        &[
+            "doctor",
+            "--json",
+            "--dir",
+            project.path().to_str().unwrap(),
+        ],
+        home.path().to_str().unwrap(),
+    );
+    assert_eq!(result.exit_code, 1, "stdout: {}", result.stdout);
+    let checks: Vec<serde_json::Value> = serde_json::from_str(&result.stdout).unwrap();
+    let overlay = checks
+        .iter()
+        .find(|c| c["name"] == "project overlay")
+        .unwrap();
+    assert_eq!(overlay["status"], "fail");
+    assert!(overlay["detail"].as_str().unwrap().contains("alowlists"));
+    assert!(overlay["fix"].as_str().unwrap().contains("longline.yaml"));
+}
+
+#[test]
+fn test_e2e_doctor_fails_codex_wired_for_one_event() {
+    let home = tempfile::tempdir().unwrap();
+    let hooks = home.path().join(".codex/hooks.json");
+    std::fs::create_dir_all(hooks.parent().unwrap()).unwrap();
+    std::fs::write(
+        &hooks,
+        r#"{"hooks":{"PreToolUse":[{"matcher":"Bash","hooks":[{"type":"command","command":"longline hook codex"}]}]}}"#,
+    )
+    .unwrap();
+    let home_str = home.path().to_str().unwrap();
+
+    let result = run_subcommand_with_home(&["doctor", "--dir", home_str], home_str);
+    assert_eq!(result.exit_code, 1, "stdout: {}", result.stdout);
+    assert!(
+        result.stdout.contains("only PreToolUse runs longline"),
+        "{}",
+        result.stdout
+    );
+    assert!(result.stdout.contains("longline install --runtime codex"));
+}
@@ -0,0 +1,56 @@
+mod support;
+use support::cli::run_subcommand;
+use support::paths::rules_path;
+
+#[test]
+fn test_e2e_explain_prints_trace() {
+    let result = run_subcommand(&[
+        "explain",
+        "--config",
+        &rules_path(),
+        "gh pr view 1 && frobnicate",
+    ]);
+    assert_eq!(result.exit_code, 0, "stderr: {}", result.stderr);
+    for expected in [
+        "Statement",
+        "[original] gh pr view 1",
+        "gh \u{2192} allow",
+        "covered by:  classifier",
+        "[original] frobnicate",
+        "Gate:     applied; uncovered leaf",
+        "Decision: ask",
+    ] {
+        assert!(
+            result.stdout.contains(expected),
+            "missing {expected:?}: {}",
+            result.stdout
+        );
+    }
+}
+
+#[test]
+fn test_e2e_explain_json() {
+    let result = run_subcommand(&[
+        "explain",
+        "--config",
+        &rules_path(),
+        "--json",
+        "timeout 5 rm -rf /",
+    ]);
+    assert_eq!(result.exit_code, 0, "stderr: {}", result.stderr);
+    let json: serde_json::Value = serde_json::from_str(&result.stdout).unwrap();
+    assert_eq!(json["result"]["decision"], "deny");
+    assert_eq!(json["gate"]["applied"], false);
+
+    let leaves = json["leaves"].as_array().unwrap();
+    assert_eq!(leaves[0]["bucket"], "original");
+    assert_eq!(leaves[1]["bucket"], "wrapper");
+    assert_eq!(leaves[1]["is_extra"], true);
+    let rules = leaves[1]["rules"].as_array().unwrap();
+    assert!(
+        rules
+            .iter()
+            .any(|r| r["decision"] == "deny" && r["active"] == true),
+        "{rules:?}"
+    );
+}
@@ -0,0 +1,80 @@
+mod support;
+use support::cli::run_subcommand_with_home;
+
+const EXISTING: &str = r#"{
+  "model": "opus",
+  "hooks": {
+    "PreToolUse": [
+      { "matcher": "Bash", "hooks": [{ "type": "command", "command": "other-hook" }] }
+    ]
+  }
+}
+"#;
+
+fn longline_hook_count(settings: &serde_json::Value, event: &str) -> usize {
+    settings["hooks"][event]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|group| group["hooks"].as_array().into_iter().flatten())
+        .filter(|hook| {
+            hook["command"]
+                .as_str()
+                .is_some_and(|c| c.starts_with("longline"))
+        })
+        .count()
+}
+
+#[test]
+fn test_e2e_install_claude_merges_then_uninstall_restores() {
+    let home = tempfile::tempdir().unwrap();
+    let settings_path = home.path().join(".claude/settings.json");
+    std::fs::create_dir_all(settings_path.parent().unwrap()).unwrap();
+    std::fs::write(&settings_path, EXISTING).unwrap();
+    let home_str = home.path().to_str().unwrap();
+
+    let result = run_subcommand_with_home(&["install", "--runtime", "claude"], home_str);
+    assert_eq!(result.exit_code, 0, "stderr: {}", result.stderr);
+    let settings: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&settings_path).unwrap()).unwrap();
+    assert_eq!(settings["model"], "opus");
+    assert_eq!(longline_hook_count(&settings, "PreToolUse"), 7);
+    assert_eq!(
+        settings["hooks"]["PreToolUse"][0]["hooks"][0]["command"],
+        "other-hook"
+    );
+
+    let again = run_subcommand_with_home(&["install", "--runtime", "claude"], home_str);
+    assert!(again.stdout.starts_with("No changes"), "{}", again.stdout);
+
+    let result = run_subcommand_with_home(&["uninstall", "--runtime", "claude"], home_str);
+    assert_eq!(result.exit_code, 0, "stderr: {}", result.stderr);
+    let restored: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&settings_path).unwrap()).unwrap();
+    assert_eq!(
+        restored,
+        serde_json::from_str::<serde_json::Value>(EXISTING).unwrap()
+    );
+}
+
+#[test]
+fn test_e2e_install_codex_dry_run_prints_diff_without_writing() {
+    let home = tempfile::tempdir().unwrap();
+    let result = run_subcommand_with_home(
+        &[
+            "install",
+            "--runtime",
+            "codex",
+            "--ask-ai-lenient",
+            "--dry-run",
+        ],
+        home.path().to_str().unwrap(),
+    );
+    assert_eq!(result.exit_code, 0, "stderr: {}", result.stderr);
+    assert!(result.stdout.contains("+++ "), "{}", result.stdout);
+    assert!(result.stdout.contains("\"PermissionRequest\""));
+    assert!(result
+        .stdout
+        .contains("\"command\": \"longline --ask-ai-lenient hook codex\""));
+    assert!(!home.path().join(".codex/hooks.json").exists());
+}
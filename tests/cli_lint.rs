@@ -0,0 +1,87 @@
+mod support;
+use support::cli::run_subcommand_with_home;
+
+#[test]
+fn test_e2e_lint_embedded_rules_clean() {
+    let home = tempfile::tempdir().unwrap();
+    let home_str = home.path().to_str().unwrap();
+    let result = run_subcommand_with_home(&["lint", "--dir", home_str], home_str);
+    assert_eq!(result.exit_code, 0, "stdout: {}", result.stdout);
+    assert!(
+        result.stdout.contains("No issues found."),
+        "{}",
+        result.stdout
+    );
+}
+
+#[test]
+fn test_e2e_lint_reports_overlay_issues_with_locations() {
+    let home = tempfile::tempdir().unwrap();
+    let project = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(project.path().join(".claude")).unwrap();
+    let overlay = project.path().join(".claude/longline.yaml");
+    std::fs::write(
+        &overlay,
+        r#"disable_rules:
+  - no-such-rule
+rules:
+  - id: strict-only
+    level: strict
+    match: { command: terraform }
+    decision: ask
+    reason: "Terraform needs review"
+"#,
+    )
+    .unwrap();
+
+    let result = run_subcommand_with_home(
+        &["lint", "--json", "--dir", project.path().to_str().unwrap()],
+        home.path().to_str().unwrap(),
+    );
+    assert_eq!(result.exit_code, 1, "stdout: {}", result.stdout);
+    let issues: Vec<serde_json::Value> = serde_json::from_str(&result.stdout).unwrap();
+    let found: Vec<(&str, &str, u64)> = issues
+        .iter()
+        .map(|i| {
+            (
+                i["severity"].as_str().unwrap(),
+                i["code"].as_str().unwrap(),
+                i["line"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("error", "unknown-disable-rule", 2),
+            ("warning", "inactive-rule", 4),
+        ]
+    );
+    assert_eq!(issues[0]["file"], overlay.display().to_string());
+
+    // The rule fires once the profile raises the safety level.
+    std::fs::write(
+        &overlay,
+        std::fs::read_to_string(&overlay)
+            .unwrap()
+            .replace("  - no-such-rule\n", "  - terraform-init-no-backend\n")
+            + "profiles:\n  strict:\n    safety_level: strict\n",
+    )
+    .unwrap();
+    let result = run_subcommand_with_home(
+        &[
+            "lint",
+            "--profile",
+            "strict",
+            "--dir",
+            project.path().to_str().unwrap(),
+        ],
+        home.path().to_str().unwrap(),
+    );
+    assert_eq!(result.exit_code, 0, "stdout: {}", result.stdout);
+    assert!(
+        result.stdout.contains("No issues found."),
+        "{}",
+        result.stdout
+    );
+}
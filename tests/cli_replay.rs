@@ -0,0 +1,114 @@
+mod support;
+use support::cli::run_subcommand_with_home;
+
+fn entry(cwd: &str, decision: &str, command: &str) -> String {
+    serde_json::json!({
+        "version": "0.0.0",
+        "runtime": "claude",
+        "profile": "default",
+        "ts": "2026-03-02T10:00:00Z",
+        "tool": "Bash",
+        "cwd": cwd,
+        "command": command,
+        "decision": decision,
+        "matched_rules": [],
+        "reason": null,
+        "parse_ok": true,
+        "session_id": "abc",
+    })
+    .to_string()
+}
+
+/// A project whose overlay now asks on `git push` and allows `kubectl get`,
+/// and a log recorded before that overlay existed.
+fn setup() -> (tempfile::TempDir, tempfile::TempDir, std::path::PathBuf) {
+    let home = tempfile::tempdir().unwrap();
+    let project = tempfile::tempdir().unwrap();
+    std::fs::create_dir(project.path().join(".git")).unwrap();
+    std::fs::create_dir(project.path().join(".claude")).unwrap();
+    std::fs::write(
+        project.path().join(".claude/longline.yaml"),
+        r#"
+allowlists:
+  commands:
+    - { command: "kubectl get", trust: standard }
+rules:
+  - id: project-ask-on-push
+    level: high
+    match:
+      command: git
+      args:
+        any_of: ["push"]
+    decision: ask
+    reason: "Requires approval before pushing"
+"#,
+    )
+    .unwrap();
+    let cwd = project.path().to_str().unwrap();
+    let log = home.path().join("history.jsonl");
+    std::fs::write(
+        &log,
+        [
+            entry(cwd, "allow", "git push"),
+            entry(cwd, "allow", "git push"),
+            entry(cwd, "ask", "kubectl get pods"),
+            entry(cwd, "allow", "ls -la"),
+        ]
+        .join("\n"),
+    )
+    .unwrap();
+    (home, project, log)
+}
+
+#[test]
+fn test_e2e_replay_reports_flips_under_the_project_overlay() {
+    let (home, _project, log) = setup();
+    let result = run_subcommand_with_home(
+        &["replay", "--log", log.to_str().unwrap(), "--json"],
+        home.path().to_str().unwrap(),
+    );
+    assert_eq!(result.exit_code, 0, "stderr: {}", result.stderr);
+    let report: serde_json::Value = serde_json::from_str(&result.stdout).unwrap();
+    assert_eq!(report["replayed"], 4);
+    assert_eq!(report["unchanged"], 1);
+    assert_eq!(report["tightened"], 2);
+    assert_eq!(report["loosened"], 1);
+    let flips = report["flips"].as_array().unwrap();
+    assert_eq!(flips.len(), 2);
+    assert_eq!(flips[0]["command"], "git push");
+    assert_eq!(flips[0]["from"], "allow");
+    assert_eq!(flips[0]["to"], "ask");
+    assert_eq!(flips[0]["count"], 2);
+    assert_eq!(flips[0]["rule_id"], "project-ask-on-push");
+    assert_eq!(flips[1]["command"], "kubectl get pods");
+    assert_eq!(flips[1]["to"], "allow");
+}
+
+#[test]
+fn test_e2e_replay_exits_nonzero_over_budget() {
+    let (home, _project, log) = setup();
+    let home = home.path().to_str().unwrap();
+    let log = log.to_str().unwrap();
+
+    let within = run_subcommand_with_home(
+        &[
+            "replay",
+            "--log",
+            log,
+            "--max-tightened",
+            "2",
+            "--max-loosened",
+            "1",
+        ],
+        home,
+    );
+    assert_eq!(within.exit_code, 0, "stderr: {}", within.stderr);
+    assert!(within.stdout.contains("2 tightened, 1 loosened"));
+    assert!(within.stdout.contains("project-ask-on-push"));
+
+    let over = run_subcommand_with_home(&["replay", "--log", log, "--max-loosened", "0"], home);
+    assert_eq!(over.exit_code, 1);
+    assert!(over
+        .stderr
+        .contains("1 loosened decision(s) exceed --max-loosened 0"));
+}
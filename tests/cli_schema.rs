@@ -0,0 +1,66 @@
+mod support;
+use support::cli::{run_subcommand, run_subcommand_with_home};
+
+#[test]
+fn test_e2e_schema_kinds() {
+    for (kind, title) in [
+        ("rules", "longline rules file"),
+        ("overlay", "longline overlay"),
+        ("ai-judge", "longline AI judge config"),
+    ] {
+        let result = run_subcommand(&["schema", kind]);
+        assert_eq!(result.exit_code, 0, "stderr: {}", result.stderr);
+        let schema: serde_json::Value = serde_json::from_str(&result.stdout).unwrap();
+        assert_eq!(schema["title"], title);
+        assert!(schema["$schema"].is_string());
+    }
+
+    let default = run_subcommand(&["schema"]);
+    assert_eq!(default.stdout, run_subcommand(&["schema", "rules"]).stdout);
+}
+
+#[test]
+fn test_e2e_overlay_error_points_at_key_with_suggestion() {
+    let home = tempfile::tempdir().unwrap();
+    let project = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(project.path().join(".claude")).unwrap();
+    std::fs::write(
+        project.path().join(".claude/longline.yaml"),
+        r#"rules:
+  - id: no-prod-deploy
+    level: high
+    match:
+      command: make
+      args:
+        anyof: [deploy-prod]
+    decision: ask
+    reason: "Production deploy"
+"#,
+    )
+    .unwrap();
+
+    let result = run_subcommand_with_home(
+        &[
+            "check",
+            "--dir",
+            project.path().to_str().unwrap(),
+            "make deploy-prod",
+        ],
+        home.path().to_str().unwrap(),
+    );
+    assert_eq!(result.exit_code, 2, "stdout: {}", result.stdout);
+    assert!(
+        result.stderr.contains(
+            "rules[0].match.args: unknown field `anyof`, expected one of `any_of`, `all_of`"
+        ),
+        "{}",
+        result.stderr
+    );
+    assert!(
+        result
+            .stderr
+            .contains("(did you mean `any_of`?) at line 7 column 9"),
+        "{}",
+        result.stderr
+    );
+}
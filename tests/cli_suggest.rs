@@ -0,0 +1,89 @@
+mod support;
+use support::cli::run_subcommand_with_home;
+
+fn entry(cwd: &str, decision: &str, command: &str) -> String {
+    serde_json::json!({
+        "version": "0.0.0",
+        "runtime": "claude",
+        "profile": "default",
+        "ts": "2026-03-02T10:00:00Z",
+        "tool": "Bash",
+        "cwd": cwd,
+        "command": command,
+        "decision": decision,
+        "matched_rules": [],
+        "reason": null,
+        "parse_ok": true,
+        "session_id": "abc",
+    })
+    .to_string()
+}
+
+/// A project with a `.git` marker and a Claude log of its asks.
+fn setup() -> (tempfile::TempDir, tempfile::TempDir) {
+    let home = tempfile::tempdir().unwrap();
+    let project = tempfile::tempdir().unwrap();
+    std::fs::create_dir(project.path().join(".git")).unwrap();
+    let cwd = project.path().to_str().unwrap();
+    let logs = home.path().join(".claude/hooks-logs");
+    std::fs::create_dir_all(&logs).unwrap();
+    std::fs::write(
+        logs.join("longline.jsonl"),
+        [
+            entry(cwd, "ask", "kubectl get pods"),
+            entry(cwd, "ask", "kubectl get pods -n web"),
+            entry(cwd, "ask", "kubectl get pods -o wide"),
+            entry(cwd, "ask", "cat .env"),
+            entry(cwd, "ask", "cat .env"),
+            entry("/elsewhere", "ask", "frobctl status"),
+            entry("/elsewhere", "ask", "frobctl status"),
+        ]
+        .join("\n"),
+    )
+    .unwrap();
+    (home, project)
+}
+
+#[test]
+fn test_e2e_suggest_prints_paste_ready_snippet() {
+    let (home, project) = setup();
+    let result = run_subcommand_with_home(
+        &["suggest", "--project", project.path().to_str().unwrap()],
+        home.path().to_str().unwrap(),
+    );
+    assert_eq!(result.exit_code, 0, "stderr: {}", result.stderr);
+    assert!(
+        result
+            .stdout
+            .contains("- { command: \"kubectl get pods\", trust: standard }"),
+        "stdout: {}",
+        result.stdout
+    );
+    assert!(result.stdout.contains("# 3 asks, e.g. kubectl get pods"));
+    assert!(!result.stdout.contains("cat"), "secrets read proposed");
+    assert!(!result.stdout.contains("frobctl"), "other project proposed");
+}
+
+#[test]
+fn test_e2e_suggest_skips_commands_the_project_already_allows() {
+    let (home, project) = setup();
+    std::fs::create_dir(project.path().join(".claude")).unwrap();
+    std::fs::write(
+        project.path().join(".claude/longline.yaml"),
+        "allowlists:\n  commands:\n    - { command: \"kubectl get\", trust: standard }\n",
+    )
+    .unwrap();
+    let result = run_subcommand_with_home(
+        &[
+            "suggest",
+            "--project",
+            project.path().to_str().unwrap(),
+            "--json",
+        ],
+        home.path().to_str().unwrap(),
+    );
+    assert_eq!(result.exit_code, 0, "stderr: {}", result.stderr);
+    let report: serde_json::Value = serde_json::from_str(&result.stdout).unwrap();
+    assert_eq!(report["asked_commands"], 5);
+    assert_eq!(report["suggestions"], serde_json::json!([]));
+}
@@ -0,0 +1,143 @@
+mod support;
+use support::cli::run_subcommand_with_home;
+
+const PROJECT_CONFIG: &str = r#"
+allowlists:
+  commands:
+    - command: "make test"
+      trust: standard
+      examples:
+        matches: ["make test", "timeout 60 make test"]
+        not_matches: ["make deploy"]
+rules:
+  - id: no-prod-deploy
+    level: high
+    match:
+      command: make
+      args:
+        any_of: ["deploy-prod"]
+    decision: deny
+    reason: "Production deploys go through CI"
+    examples:
+      matches: ["make deploy-prod"]
+      not_matches: ["make deploy-staging"]
+"#;
+
+fn project(config: &str) -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join(".claude")).unwrap();
+    std::fs::write(dir.path().join(".claude/longline.yaml"), config).unwrap();
+    dir
+}
+
+#[test]
+fn test_e2e_test_passes_project_examples() {
+    let home = tempfile::tempdir().unwrap();
+    let project = project(PROJECT_CONFIG);
+    let result = run_subcommand_with_home(
+        &["test", "--dir", project.path().to_str().unwrap()],
+        home.path().to_str().unwrap(),
+    );
+    assert_eq!(result.exit_code, 0, "stdout: {}", result.stdout);
+    assert!(result.stdout.contains(" 0 failed"), "{}", result.stdout);
+}
+
+#[test]
+fn test_e2e_test_fails_on_broken_example() {
+    let home = tempfile::tempdir().unwrap();
+    let broken = PROJECT_CONFIG.replace(
+        r#"not_matches: ["make deploy-staging"]"#,
+        r#"not_matches: ["make deploy-prod --dry-run"]"#,
+    );
+    let project = project(&broken);
+    let result = run_subcommand_with_home(
+        &["test", "--json", "--dir", project.path().to_str().unwrap()],
+        home.path().to_str().unwrap(),
+    );
+    assert_eq!(result.exit_code, 1, "stdout: {}", result.stdout);
+    let results: Vec<serde_json::Value> = serde_json::from_str(&result.stdout).unwrap();
+    let failed: Vec<&serde_json::Value> = results
+        .iter()
+        .filter(|r| r["matched"] != r["expect_match"])
+        .collect();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0]["id"], "no-prod-deploy");
+    assert_eq!(failed[0]["source"], "project");
+    assert_eq!(failed[0]["example"], "make deploy-prod --dry-run");
+}
+
+#[test]
+fn test_e2e_test_runs_golden_suites() {
+    let home = tempfile::tempdir().unwrap();
+    let root = env!("CARGO_MANIFEST_DIR");
+    let result = run_subcommand_with_home(
+        &[
+            "--config",
+            &format!("{root}/rules/rules.yaml"),
+            "test",
+            &format!("{root}/tests/golden"),
+        ],
+        home.path().to_str().unwrap(),
+    );
+    assert_eq!(result.exit_code, 0, "stdout: {}", result.stdout);
+    assert!(result.stdout.contains(" 0 failed\n"), "{}", result.stdout);
+}
+
+#[test]
+fn test_e2e_test_suite_cwd_tool_and_junit() {
+    let home = tempfile::tempdir().unwrap();
+    let project = project(PROJECT_CONFIG);
+    let suites = tempfile::tempdir().unwrap();
+    let suite = format!(
+        r#"
+tests:
+  - id: project-deny
+    command: make deploy-prod
+    cwd: {project}
+    expected: {{ decision: deny, rule_id: no-prod-deploy }}
+  - id: no-project
+    command: make deploy-prod
+    expected: {{ decision: deny }}
+  - id: read-ssh-key
+    tool: Read
+    command: ../.ssh/id_rsa
+    cwd: {home}/work
+    expected: {{ decision: ask, rule_id: path-ssh-dir }}
+  - id: codex-runtime
+    command: ls
+    runtime: codex
+    expected: {{ decision: allow }}
+"#,
+        project = project.path().display(),
+        home = home.path().display()
+    );
+    std::fs::write(suites.path().join("team.yaml"), suite).unwrap();
+    let junit = suites.path().join("junit.xml");
+    let result = run_subcommand_with_home(
+        &[
+            "test",
+            "--json",
+            suites.path().to_str().unwrap(),
+            "--junit",
+            junit.to_str().unwrap(),
+        ],
+        home.path().to_str().unwrap(),
+    );
+    assert_eq!(result.exit_code, 1, "stdout: {}", result.stdout);
+    let reports: Vec<serde_json::Value> = serde_json::from_str(&result.stdout).unwrap();
+    let cases = reports[0]["cases"].as_array().unwrap();
+    let decisions: Vec<&str> = cases
+        .iter()
+        .map(|c| c["decision"].as_str().unwrap())
+        .collect();
+    assert_eq!(decisions, vec!["deny", "allow", "ask", "allow"]);
+    assert_eq!(cases[0]["rule_id"], "no-prod-deploy");
+    assert_eq!(cases[2]["rule_id"], "path-ssh-dir");
+
+    let xml = std::fs::read_to_string(&junit).unwrap();
+    assert!(
+        xml.contains(r#"tests="4" failures="1" errors="0""#),
+        "{xml}"
+    );
+    assert!(xml.contains(r#"<testcase name="no-project""#), "{xml}");
+}
@@ -172,7 +172,9 @@ fn permission_request_bash_ambiguous_returns_no_decision() {
 // ---------- Layer 2: passthrough ----------
 
 #[test]
-fn pre_tool_use_apply_patch_passthrough_no_decision() {
+fn pre_tool_use_apply_patch_without_payload_asks_no_decision() {
+    // apply_patch is evaluated, not passed through; a missing envelope asks,
+    // which PreToolUse expresses as empty stdout.
     let env = TestEnv::new().build();
     let result = run_codex(&env, &codex_input_no_command("PreToolUse", "apply_patch"));
     assert_eq!(result.exit_code, 0);
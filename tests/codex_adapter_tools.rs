@@ -1,6 +1,7 @@
 mod support;
 
 use serde_json::json;
+use support::audit::last_audit_entry;
 use support::bin::run_longline;
 use support::codex::CodexRunResultExt;
 use support::config::TestEnv;
@@ -321,3 +322,304 @@ fn pre_tool_use_missing_cwd_ignores_poisoned_launcher_config() {
     result.assert_codex_no_decision();
     assert_eq!(result.stderr, "");
 }
+
+// ---------- apply_patch: each touched path is held to the write policy ----------
+
+fn apply_patch_input(event: &str, cwd: &std::path::Path, patch: &str) -> String {
+    json!({
+        "hook_event_name": event,
+        "tool_name": "apply_patch",
+        "tool_input": {"command": patch},
+        "session_id": "patch-session",
+        "cwd": cwd.to_string_lossy()
+    })
+    .to_string()
+}
+
+/// A real git work tree with `src/lib.rs` tracked and `scratch.txt`
+/// present but untracked.
+fn git_project() -> tempfile::TempDir {
+    let dir = tempfile::TempDir::new().unwrap();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir.path())
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    };
+    git(&["init", "-q"]);
+    std::fs::create_dir_all(dir.path().join("src")).unwrap();
+    std::fs::write(dir.path().join("src/lib.rs"), "fn a() {}\n").unwrap();
+    std::fs::write(dir.path().join("scratch.txt"), "tmp\n").unwrap();
+    git(&["add", "src/lib.rs"]);
+    dir
+}
+
+fn run_patch(env: &TestEnv, event: &str, project: &std::path::Path, patch: &str) -> RunResult {
+    run_codex(env, &apply_patch_input(event, project, patch))
+}
+
+#[test]
+fn permission_request_apply_patch_source_edit_allows() {
+    let env = TestEnv::new().build();
+    let project = git_project();
+    let patch = "*** Begin Patch\n*** Update File: src/lib.rs\n@@\n-fn a() {}\n+fn b() {}\n*** Add File: src/new.rs\n+fn c() {}\n*** End Patch\n";
+    let result = run_patch(&env, "PermissionRequest", project.path(), patch);
+    assert_eq!(result.exit_code, 0);
+    result.assert_codex_permission_request_behavior("allow");
+
+    let entry = last_audit_entry(env.home_path(), "codex");
+    assert_eq!(entry["tool"], "apply_patch");
+    assert_eq!(entry["decision"], "allow");
+    assert_eq!(entry["command"], "update src/lib.rs; add src/new.rs");
+    assert_eq!(entry["session_id"], "patch-session");
+}
+
+#[test]
+fn permission_request_apply_patch_delete_tracked_file_asks() {
+    let env = TestEnv::new().build();
+    let project = git_project();
+    let patch = "*** Begin Patch\n*** Delete File: src/lib.rs\n*** End Patch\n";
+    let result = run_patch(&env, "PermissionRequest", project.path(), patch);
+    assert_eq!(result.exit_code, 0);
+    result.assert_codex_no_decision();
+
+    let entry = last_audit_entry(env.home_path(), "codex");
+    assert_eq!(entry["decision"], "ask");
+    assert_eq!(entry["reason"], "Patch deletes a git-tracked file");
+    assert_eq!(entry["path"], "src/lib.rs");
+}
+
+#[test]
+fn permission_request_apply_patch_move_tracked_file_asks() {
+    let env = TestEnv::new().build();
+    let project = git_project();
+    let patch = "*** Begin Patch\n*** Update File: src/lib.rs\n*** Move to: src/moved.rs\n@@\n-fn a() {}\n+fn b() {}\n*** End Patch\n";
+    let result = run_patch(&env, "PermissionRequest", project.path(), patch);
+    assert_eq!(result.exit_code, 0);
+    result.assert_codex_no_decision();
+
+    let entry = last_audit_entry(env.home_path(), "codex");
+    assert_eq!(entry["decision"], "ask");
+    assert_eq!(entry["reason"], "Patch moves a git-tracked file");
+    assert_eq!(entry["command"], "update src/lib.rs; move to src/moved.rs");
+}
+
+#[test]
+fn permission_request_apply_patch_delete_untracked_file_allows() {
+    let env = TestEnv::new().build();
+    let project = git_project();
+    let patch = "*** Begin Patch\n*** Delete File: scratch.txt\n*** End Patch\n";
+    let result = run_patch(&env, "PermissionRequest", project.path(), patch);
+    assert_eq!(result.exit_code, 0);
+    result.assert_codex_permission_request_behavior("allow");
+}
+
+#[test]
+fn permission_request_apply_patch_ci_workflow_asks() {
+    let env = TestEnv::new().build();
+    let project = git_project();
+    let patch = "*** Begin Patch\n*** Update File: src/lib.rs\n@@\n-a\n+b\n*** Add File: .github/workflows/ci.yml\n+on: push\n*** End Patch\n";
+    let result = run_patch(&env, "PermissionRequest", project.path(), patch);
+    assert_eq!(result.exit_code, 0);
+    result.assert_codex_no_decision();
+
+    let entry = last_audit_entry(env.home_path(), "codex");
+    assert_eq!(entry["decision"], "ask");
+    assert_eq!(entry["matched_rules"][0], "path-ci-config");
+}
+
+#[test]
+fn permission_request_apply_patch_git_dir_asks() {
+    let env = TestEnv::new().build();
+    let project = git_project();
+    let patch = "*** Begin Patch\n*** Update File: .git/description\n@@\n-a\n+b\n*** End Patch\n";
+    let result = run_patch(&env, "PermissionRequest", project.path(), patch);
+    assert_eq!(result.exit_code, 0);
+    result.assert_codex_no_decision();
+    let entry = last_audit_entry(env.home_path(), "codex");
+    assert_eq!(entry["matched_rules"][0], "path-git-dir");
+}
+
+#[test]
+fn pre_tool_use_apply_patch_git_hook_denies() {
+    let env = TestEnv::new().build();
+    let project = git_project();
+    let patch = "*** Begin Patch\n*** Add File: .git/hooks/pre-commit\n+#!/bin/sh\n*** End Patch\n";
+    let result = run_patch(&env, "PreToolUse", project.path(), patch);
+    assert_eq!(result.exit_code, 0);
+    assert_eq!(
+        result.codex_pre_tool_use_decision().as_deref(),
+        Some("deny")
+    );
+    let reason = result.codex_pre_tool_use_reason().unwrap();
+    assert!(
+        reason.contains("(apply_patch: add .git/hooks/pre-commit)"),
+        "{reason}"
+    );
+}
+
+#[test]
+fn permission_request_apply_patch_env_file_asks() {
+    let env = TestEnv::new().build();
+    let project = git_project();
+    let patch = "*** Begin Patch\n*** Add File: .env\n+TOKEN=x\n*** End Patch\n";
+    let result = run_patch(&env, "PermissionRequest", project.path(), patch);
+    assert_eq!(result.exit_code, 0);
+    result.assert_codex_no_decision();
+    let entry = last_audit_entry(env.home_path(), "codex");
+    assert_eq!(entry["matched_rules"][0], "redirect-write-env-file");
+}
+
+#[test]
+fn permission_request_apply_patch_move_outside_project_asks() {
+    let env = TestEnv::new().build();
+    let project = git_project();
+    let patch = "*** Begin Patch\n*** Update File: scratch.txt\n*** Move to: ../elsewhere/scratch.txt\n@@\n-tmp\n+b\n*** End Patch\n";
+    let result = run_patch(&env, "PermissionRequest", project.path(), patch);
+    assert_eq!(result.exit_code, 0);
+    result.assert_codex_no_decision();
+    let entry = last_audit_entry(env.home_path(), "codex");
+    assert_eq!(
+        entry["reason"],
+        "Patch touches a path outside the project root"
+    );
+}
+
+#[test]
+fn permission_request_apply_patch_unparseable_asks() {
+    let env = TestEnv::new().build();
+    let project = git_project();
+    let result = run_patch(
+        &env,
+        "PermissionRequest",
+        project.path(),
+        "*** Begin Patch\n*** Update File: src/lib.rs\n",
+    );
+    assert_eq!(result.exit_code, 0);
+    result.assert_codex_no_decision();
+    let entry = last_audit_entry(env.home_path(), "codex");
+    assert_eq!(entry["decision"], "ask");
+    assert_eq!(entry["parse_ok"], false);
+}
+
+// ---------- MCP tool calls: `mcp` rules from config, passthrough otherwise ----------
+
+const PROJECT_MCP_RULES: &str = r#"
+rules:
+  - id: project-mcp-browser
+    level: high
+    match:
+      mcp:
+        server: playwright
+        tool: "browser_*"
+    decision: ask
+    reason: "Browser automation"
+  - id: project-mcp-ticket-delete
+    level: high
+    match:
+      mcp:
+        server: jira
+        tool: delete_issue
+    decision: deny
+    reason: "Deleting tickets"
+  - id: project-mcp-ticket-read
+    level: high
+    match:
+      mcp:
+        server: jira
+        tool: { any_of: ["get_issue", "search_issues"] }
+    decision: allow
+    reason: "Reading tickets"
+"#;
+
+fn mcp_input(event: &str, env: &TestEnv, tool_name: &str, tool_input: serde_json::Value) -> String {
+    json!({
+        "hook_event_name": event,
+        "tool_name": tool_name,
+        "tool_input": tool_input,
+        "session_id": "mcp-session",
+        "cwd": env.project_path().to_string_lossy()
+    })
+    .to_string()
+}
+
+#[test]
+fn pre_tool_use_mcp_deny_rule_denies_and_logs() {
+    let env = TestEnv::new()
+        .with_project_config(PROJECT_MCP_RULES)
+        .build();
+    let input = mcp_input(
+        "PreToolUse",
+        &env,
+        "mcp__jira__delete_issue",
+        json!({"key": "OPS-1"}),
+    );
+    let result = run_codex(&env, &input);
+    assert_eq!(result.exit_code, 0);
+    assert_eq!(
+        result.codex_pre_tool_use_decision().as_deref(),
+        Some("deny")
+    );
+
+    let entry = last_audit_entry(env.home_path(), "codex");
+    assert_eq!(entry["tool"], "mcp__jira__delete_issue");
+    assert_eq!(entry["command"], r#"{"key":"OPS-1"}"#);
+    assert_eq!(entry["matched_rules"][0], "project-mcp-ticket-delete");
+}
+
+#[test]
+fn permission_request_mcp_allow_rule_allows() {
+    let env = TestEnv::new()
+        .with_project_config(PROJECT_MCP_RULES)
+        .build();
+    let input = mcp_input(
+        "PermissionRequest",
+        &env,
+        "mcp__jira__get_issue",
+        json!({"key": "OPS-1"}),
+    );
+    let result = run_codex(&env, &input);
+    assert_eq!(result.exit_code, 0);
+    result.assert_codex_permission_request_behavior("allow");
+}
+
+#[test]
+fn permission_request_mcp_ask_rule_emits_no_decision() {
+    let env = TestEnv::new()
+        .with_project_config(PROJECT_MCP_RULES)
+        .build();
+    let input = mcp_input(
+        "PermissionRequest",
+        &env,
+        "mcp__playwright__browser_navigate",
+        json!({"url": "https://example.com"}),
+    );
+    let result = run_codex(&env, &input);
+    assert_eq!(result.exit_code, 0);
+    result.assert_codex_no_decision();
+    let entry = last_audit_entry(env.home_path(), "codex");
+    assert_eq!(entry["decision"], "ask");
+}
+
+#[test]
+fn permission_request_mcp_without_matching_rule_passes_through() {
+    let env = TestEnv::new()
+        .with_project_config(PROJECT_MCP_RULES)
+        .build();
+    let input = mcp_input(
+        "PermissionRequest",
+        &env,
+        "mcp__jira__create_issue",
+        json!({"summary": "x"}),
+    );
+    let result = run_codex(&env, &input);
+    assert_eq!(result.exit_code, 0);
+    result.assert_codex_no_decision();
+    assert!(!env
+        .home_path()
+        .join(".codex/hooks-logs/longline.jsonl")
+        .exists());
+}
@@ -3,13 +3,26 @@ use std::path::PathBuf;
 
 #[derive(Debug, Deserialize)]
 struct TestSuite {
+    /// Overlay for every case that does not set its own.
+    #[serde(default)]
+    overlay: Option<String>,
     tests: Vec<TestCase>,
 }
 
 #[derive(Debug, Deserialize)]
 struct TestCase {
     id: String,
+    #[serde(default)]
     command: String,
+    /// MCP tool call to evaluate instead of `command`.
+    #[serde(default)]
+    mcp: Option<McpCall>,
+    /// WebFetch URL to evaluate instead of `command`.
+    #[serde(default)]
+    fetch: Option<String>,
+    /// Hook cwd, relative to the golden directory: anchors relative paths.
+    #[serde(default)]
+    cwd: Option<PathBuf>,
     expected: Expected,
     #[serde(default)]
     overlay: Option<String>,
@@ -17,6 +30,24 @@ struct TestCase {
     profile: Option<String>,
 }
 
+impl TestCase {
+    /// What the case evaluates, for failure messages.
+    fn subject(&self) -> String {
+        match (&self.mcp, &self.fetch) {
+            (Some(mcp), _) => format!("mcp={} input={}", mcp.tool, mcp.input),
+            (None, Some(url)) => format!("fetch='{url}'"),
+            (None, None) => format!("command='{}'", self.command),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct McpCall {
+    tool: String,
+    #[serde(default)]
+    input: serde_json::Value,
+}
+
 #[derive(Debug, Deserialize)]
 struct Expected {
     decision: String,
@@ -30,27 +61,36 @@ fn rules_path() -> PathBuf {
         .join("rules.yaml")
 }
 
-fn load_golden_tests(filename: &str) -> TestSuite {
-    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .join("tests")
         .join("golden")
-        .join(filename);
+}
+
+fn load_golden_tests(filename: &str) -> TestSuite {
+    let path = golden_dir().join(filename);
     let content = std::fs::read_to_string(&path)
         .unwrap_or_else(|e| panic!("Failed to read {}: {e}", path.display()));
     serde_norway::from_str(&content)
         .unwrap_or_else(|e| panic!("Failed to parse {}: {e}", path.display()))
 }
 
+fn split_mcp_tool_name(name: &str) -> Option<(&str, &str)> {
+    let (server, tool) = name.strip_prefix("mcp__")?.split_once("__")?;
+    (!server.is_empty() && !tool.is_empty()).then_some((server, tool))
+}
+
 fn run_golden_suite(filename: &str) {
     let suite = load_golden_tests(filename);
 
     let mut failures = Vec::new();
 
     for case in &suite.tests {
-        let config = if case.overlay.is_some() || case.profile.is_some() {
+        let overlay = case.overlay.as_ref().or(suite.overlay.as_ref());
+        let config = if overlay.is_some() || case.profile.is_some() {
             // Build a temp HOME with the overlay written out, then finalize.
             let tmp = tempfile::tempdir().unwrap();
-            if let Some(overlay) = &case.overlay {
+            if let Some(overlay) = overlay {
                 if !overlay.trim().is_empty() {
                     let config_dir = tmp.path().join(".config").join("longline");
                     std::fs::create_dir_all(&config_dir).unwrap();
@@ -81,25 +121,47 @@ fn run_golden_suite(filename: &str) {
             longline::policy::load_rules(&rules_path()).expect("Failed to load default rules")
         };
 
-        let stmt = match longline::parser::parse(&case.command) {
-            Ok(s) => s,
-            Err(e) => {
+        let result = if let Some(mcp) = &case.mcp {
+            let Some((server, tool)) = split_mcp_tool_name(&mcp.tool) else {
                 failures.push(format!(
-                    "  PARSE ERROR [{}]: command='{}', error='{e}'",
-                    case.id, case.command
+                    "  PARSE ERROR [{}]: '{}' is not an mcp__<server>__<tool> name",
+                    case.id, mcp.tool
                 ));
                 continue;
-            }
+            };
+            longline::policy::evaluate_mcp_call(&config, server, tool, &mcp.input)
+        } else if let Some(raw) = &case.fetch {
+            let Some(url) = longline::urls::parse(raw, None) else {
+                failures.push(format!("  PARSE ERROR [{}]: fetch='{raw}'", case.id));
+                continue;
+            };
+            longline::policy::evaluate_url_access(&config, &url)
+        } else {
+            let stmt = match longline::parser::parse(&case.command) {
+                Ok(s) => s,
+                Err(e) => {
+                    failures.push(format!(
+                        "  PARSE ERROR [{}]: command='{}', error='{e}'",
+                        case.id, case.command
+                    ));
+                    continue;
+                }
+            };
+            let cwd = case.cwd.as_ref().map(|cwd| golden_dir().join(cwd));
+            let cwd = cwd.as_deref().and_then(|cwd| cwd.to_str());
+            longline::policy::evaluate_with_findings(&config, &stmt, cwd).result
         };
-
-        let result = longline::policy::evaluate(&config, &stmt);
         let actual_decision = format!("{:?}", result.decision).to_lowercase();
         let expected_decision = case.expected.decision.to_lowercase();
 
         if actual_decision != expected_decision {
             failures.push(format!(
-                "  DECISION MISMATCH [{}]: command='{}', expected={}, actual={}, rule={:?}",
-                case.id, case.command, expected_decision, actual_decision, result.rule_id
+                "  DECISION MISMATCH [{}]: {}, expected={}, actual={}, rule={:?}",
+                case.id,
+                case.subject(),
+                expected_decision,
+                actual_decision,
+                result.rule_id
             ));
             continue;
         }
@@ -107,8 +169,11 @@ fn run_golden_suite(filename: &str) {
         if let Some(expected_rule) = &case.expected.rule_id {
             if result.rule_id.as_deref() != Some(expected_rule.as_str()) {
                 failures.push(format!(
-                    "  RULE_ID MISMATCH [{}]: command='{}', expected_rule={}, actual_rule={:?}",
-                    case.id, case.command, expected_rule, result.rule_id
+                    "  RULE_ID MISMATCH [{}]: {}, expected_rule={}, actual_rule={:?}",
+                    case.id,
+                    case.subject(),
+                    expected_rule,
+                    result.rule_id
                 ));
             }
         }
@@ -358,3 +423,13 @@ fn golden_terraform() {
 fn test_golden_profiles() {
     run_golden_suite("profiles.yaml");
 }
+
+#[test]
+fn golden_mcp() {
+    run_golden_suite("mcp.yaml");
+}
+
+#[test]
+fn golden_urls() {
+    run_golden_suite("urls.yaml");
+}
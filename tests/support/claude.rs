@@ -93,6 +93,12 @@ pub trait ClaudeTestEnvExt {
     fn run_claude_hook(&self, command: &str) -> RunResult;
     fn run_claude_hook_with_flags(&self, command: &str, extra_args: &[&str]) -> RunResult;
     fn run_claude_tool_hook(&self, tool_name: &str, command: &str) -> RunResult;
+    fn run_claude_path_hook(&self, tool_name: &str, path: &str) -> RunResult;
+    fn run_claude_tool_input_hook(
+        &self,
+        tool_name: &str,
+        tool_input: serde_json::Value,
+    ) -> RunResult;
 }
 
 impl ClaudeTestEnvExt for TestEnv {
@@ -123,6 +129,40 @@ impl ClaudeTestEnvExt for TestEnv {
 
         run_longline(&[], self.home_path(), Some(&input))
     }
+
+    /// Run the longline binary in hook mode for a path-based file tool.
+    fn run_claude_path_hook(&self, tool_name: &str, path: &str) -> RunResult {
+        let cwd = self
+            .project_path_opt()
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_else(|| "/tmp".to_string());
+        let input = claude_path_hook_input(tool_name, path, &cwd);
+
+        run_longline(&[], self.home_path(), Some(&input))
+    }
+
+    /// Run the longline binary in hook mode with a raw `tool_input` object
+    /// (MCP calls, WebFetch).
+    fn run_claude_tool_input_hook(
+        &self,
+        tool_name: &str,
+        tool_input: serde_json::Value,
+    ) -> RunResult {
+        let cwd = self
+            .project_path_opt()
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_else(|| "/tmp".to_string());
+        let input = json!({
+            "hook_event_name": "PreToolUse",
+            "tool_name": tool_name,
+            "tool_input": tool_input,
+            "session_id": "test-session",
+            "cwd": cwd
+        })
+        .to_string();
+
+        run_longline(&[], self.home_path(), Some(&input))
+    }
 }
 
 pub fn claude_command_hook_input(tool_name: &str, command: &str, cwd: &str) -> String {
@@ -209,3 +249,25 @@ pub fn run_claude_glob_hook(pattern: &str, path: Option<&str>) -> RunResult {
     let config = rules_path();
     run_longline(&["--config", &config], static_test_home(), Some(&input))
 }
+
+pub fn claude_path_hook_input(tool_name: &str, path: &str, cwd: &str) -> String {
+    let path_key = match tool_name {
+        "NotebookEdit" => "notebook_path",
+        "Grep" | "Glob" => "path",
+        _ => "file_path",
+    };
+    json!({
+        "hook_event_name": "PreToolUse",
+        "tool_name": tool_name,
+        "tool_input": { path_key: path },
+        "session_id": "test-session",
+        "cwd": cwd
+    })
+    .to_string()
+}
+
+pub fn run_claude_write_hook(tool_name: &str, file_path: &str) -> RunResult {
+    let input = claude_path_hook_input(tool_name, file_path, "/tmp");
+    let config = rules_path();
+    run_longline(&["--config", &config], static_test_home(), Some(&input))
+}